@@ -0,0 +1,61 @@
+// Counts heap allocations per calculation. The app recalculates on every
+// input event, so while a slider is dragged this is what the WASM allocator
+// sees many times a second.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use norwegian_tax_calculator::money::Money;
+use norwegian_tax_calculator::tax_calculator::{EntityType, NorwegianTaxCalculator, TaxCalculationInput};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations(calculate: impl Fn()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    calculate();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn main() {
+    for entity_type in [
+        EntityType::Individual,
+        EntityType::Corporation,
+        EntityType::Partnership,
+        EntityType::SoleProprietorship,
+        EntityType::ForeignBranch,
+        EntityType::Nonprofit,
+    ] {
+        let input = TaxCalculationInput {
+            gross_income: Money(1_800_000.0),
+            entity_type,
+            allowable_deductions: Money(120_000.0),
+            dividend_income: Money(150_000.0),
+            capital_gains: Money(80_000.0),
+            investment_wealth: Money(4_000_000.0),
+            ..TaxCalculationInput::default()
+        };
+        let full = allocations(|| drop(NorwegianTaxCalculator::calculate_tax(&input)));
+        let totals = allocations(|| drop(NorwegianTaxCalculator::calculate_totals_only(&input)));
+        println!("{:?}: calculate_tax {} allocations, calculate_totals_only {}", entity_type, full, totals);
+    }
+}
@@ -0,0 +1,75 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use norwegian_tax_calculator::money::Money;
+use norwegian_tax_calculator::tax_calculator::{EntityType, NorwegianTaxCalculator, TaxCalculationInput};
+
+const ENTITY_TYPES: [EntityType; 6] = [
+    EntityType::Individual,
+    EntityType::Corporation,
+    EntityType::Partnership,
+    EntityType::SoleProprietorship,
+    EntityType::ForeignBranch,
+    EntityType::Nonprofit,
+];
+
+// A case that exercises every part of the calculation: deductions, all
+// trinnskatt steps, shares and wealth
+fn full_input(entity_type: EntityType) -> TaxCalculationInput {
+    TaxCalculationInput {
+        gross_income: Money(1_800_000.0),
+        entity_type,
+        allowable_deductions: Money(120_000.0),
+        dividend_income: Money(150_000.0),
+        capital_gains: Money(80_000.0),
+        investment_wealth: Money(4_000_000.0),
+        business_expenses: Money(200_000.0),
+        branch_payroll: Money(600_000.0),
+        ..TaxCalculationInput::default()
+    }
+}
+
+// One input per 10 000 NOK, the size of a typical chart
+fn income_range() -> Vec<TaxCalculationInput> {
+    (0..=200)
+        .map(|step| TaxCalculationInput {
+            gross_income: Money(step as f64 * 10_000.0),
+            ..full_input(EntityType::Individual)
+        })
+        .collect()
+}
+
+fn calculate_tax(c: &mut Criterion) {
+    let mut group = c.benchmark_group("calculate_tax");
+    for entity_type in ENTITY_TYPES {
+        let input = full_input(entity_type);
+        group.bench_with_input(BenchmarkId::from_parameter(format!("{:?}", entity_type)), &input, |b, input| {
+            b.iter(|| NorwegianTaxCalculator::calculate_tax(black_box(input)))
+        });
+    }
+    group.finish();
+}
+
+fn calculate_totals_only(c: &mut Criterion) {
+    let mut group = c.benchmark_group("calculate_totals_only");
+    for entity_type in ENTITY_TYPES {
+        let input = full_input(entity_type);
+        group.bench_with_input(BenchmarkId::from_parameter(format!("{:?}", entity_type)), &input, |b, input| {
+            b.iter(|| NorwegianTaxCalculator::calculate_totals_only(black_box(input)))
+        });
+    }
+    group.finish();
+}
+
+fn calculate_many(c: &mut Criterion) {
+    let inputs = income_range();
+    let mut group = c.benchmark_group("income_range");
+    group.bench_function("calculate_many", |b| {
+        b.iter(|| NorwegianTaxCalculator::calculate_many(black_box(&inputs)))
+    });
+    group.bench_function("calculate_many_totals", |b| {
+        b.iter(|| NorwegianTaxCalculator::calculate_many_totals(black_box(&inputs)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, calculate_tax, calculate_totals_only, calculate_many);
+criterion_main!(benches);
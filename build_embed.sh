@@ -0,0 +1,12 @@
+#!/bin/bash
+
+# Builds the package for embedding as <ntc-calculator> instead of mounting
+# the full-page app on load
+wasm-pack build --target web --out-dir pkg --no-typescript -- --features embed
+
+if [ $? -eq 0 ]; then
+    echo "Build successful! Include embed/ntc-calculator.js as a module to register <ntc-calculator>."
+else
+    echo "Build failed!"
+    exit 1
+fi
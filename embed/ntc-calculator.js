@@ -0,0 +1,18 @@
+// Registers <ntc-calculator>. Build the package with the `embed` feature
+// (see build_embed.sh) and load this file as a module next to pkg/.
+import init, { mount_calculator } from '../pkg/norwegian_tax_calculator.js';
+
+const ready = init();
+
+class NtcCalculator extends HTMLElement {
+    async connectedCallback() {
+        if (this.mounted) {
+            return;
+        }
+        this.mounted = true;
+        await ready;
+        mount_calculator(this);
+    }
+}
+
+customElements.define('ntc-calculator', NtcCalculator);
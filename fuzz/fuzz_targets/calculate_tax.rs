@@ -0,0 +1,7 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    norwegian_tax_calculator::fuzzing::fuzz_calculate(data);
+});
@@ -0,0 +1,995 @@
+use crate::commuter::CommuteInput;
+use crate::money::{Money, Rate};
+use crate::property_tax::PropertyTaxInput;
+use crate::special_deductions::SpecialDeductionsInput;
+use crate::tax_calculator::{
+    solve_gross_for_net, EntityType, NorwegianTaxCalculator, TaxCalculationInput, TaxCalculationResult, TaxYear,
+    SICK_PAY_CAP_G,
+};
+
+const INCOME_STEPS: [f64; 6] = [-100_000.0, -50_000.0, -10_000.0, 10_000.0, 50_000.0, 100_000.0];
+
+// Step used to measure the tax on the next krone earned
+const MARGINAL_STEP: Money = Money(1_000.0);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SensitivityRow {
+    pub label: String,
+    pub total_tax: Money,
+    pub net_income: Money,
+    pub tax_change: Money,
+    pub net_income_change: Money,
+    // The municipal rate moves tax between kommune and stat without
+    // changing the total, so the split is shown as well
+    pub municipal_tax_change: Money,
+    pub fellesskatt_change: Money,
+    // Share of the income change that goes to tax, when income changes
+    pub marginal_rate: Option<Rate>,
+}
+
+pub fn sensitivity_analysis(base: &TaxCalculationInput) -> Vec<SensitivityRow> {
+    let mut labels = vec!["Nåværende".to_string()];
+    let mut income_changes = vec![Money::ZERO];
+    let mut inputs = vec![base.clone()];
+
+    for step in INCOME_STEPS.map(Money) {
+        if base.gross_income + step < Money::ZERO {
+            continue;
+        }
+        labels.push(format!(
+            "{}{} NOK inntekt",
+            if step > Money::ZERO { "+" } else { "-" },
+            NorwegianTaxCalculator::format_currency(step.abs()),
+        ));
+        income_changes.push(step);
+        inputs.push(TaxCalculationInput {
+            gross_income: base.gross_income + step,
+            ..base.clone()
+        });
+    }
+
+    labels.push("+1 prosentpoeng kommuneskatt".to_string());
+    income_changes.push(Money::ZERO);
+    inputs.push(TaxCalculationInput {
+        municipal_tax_rate: base.municipal_tax_rate + Rate::from_percent(1.0),
+        ..base.clone()
+    });
+
+    let results = NorwegianTaxCalculator::calculate_many_totals(&inputs);
+    let baseline = &results[0];
+
+    labels
+        .into_iter()
+        .zip(income_changes)
+        .zip(&results)
+        .map(|((label, income_change), result)| {
+            let tax_change = result.total_tax - baseline.total_tax;
+            SensitivityRow {
+                label,
+                total_tax: result.total_tax,
+                net_income: result.net_income,
+                tax_change,
+                net_income_change: result.net_income - baseline.net_income,
+                municipal_tax_change: result.municipal_tax - baseline.municipal_tax,
+                fellesskatt_change: result.fellesskatt - baseline.fellesskatt,
+                marginal_rate: (income_change != Money::ZERO).then(|| Rate::from_fraction(tax_change / income_change)),
+            }
+        })
+        .collect()
+}
+
+// Share of the next krone of gross income that goes to tax
+pub fn marginal_tax_rate(input: &TaxCalculationInput) -> Rate {
+    let current = NorwegianTaxCalculator::calculate_totals_only(input);
+    let next = NorwegianTaxCalculator::calculate_totals_only(&TaxCalculationInput {
+        gross_income: input.gross_income + MARGINAL_STEP,
+        ..input.clone()
+    });
+    Rate::from_fraction((next.total_tax - current.total_tax) / MARGINAL_STEP)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RaiseAnalysis {
+    pub current: TaxCalculationResult,
+    pub offered: TaxCalculationResult,
+    pub monthly_net_change: Money,
+    // Marginal rate at the offered salary
+    pub marginal_rate: Rate,
+    // Share of the whole raise that goes to tax
+    pub share_to_tax: Option<Rate>,
+}
+
+pub fn analyze_raise(base: &TaxCalculationInput, current_salary: Money, offered_salary: Money) -> RaiseAnalysis {
+    let at_salary = |gross_income: Money| TaxCalculationInput {
+        gross_income,
+        ..base.clone()
+    };
+    let offered_input = at_salary(offered_salary);
+    let current = NorwegianTaxCalculator::calculate_tax(&at_salary(current_salary));
+    let offered = NorwegianTaxCalculator::calculate_tax(&offered_input);
+    let raise = offered_salary - current_salary;
+
+    RaiseAnalysis {
+        monthly_net_change: (offered.net_income - current.net_income) / 12.0,
+        marginal_rate: marginal_tax_rate(&offered_input),
+        share_to_tax: (raise != Money::ZERO).then(|| Rate::from_fraction((offered.total_tax - current.total_tax) / raise)),
+        current,
+        offered,
+    }
+}
+
+// SSB's consumer price index (KPI), yearly average with 2015 = 100. The
+// figure for 2025 is a forecast until the full year is published.
+pub fn consumer_price_index(tax_year: TaxYear) -> f64 {
+    match tax_year {
+        TaxYear::Y2023 => 130.0,
+        TaxYear::Y2024 => 133.9,
+        TaxYear::Y2025 => 137.9,
+    }
+}
+
+// An amount from one year in the kroner of another
+pub fn in_prices_of(amount: Money, from: TaxYear, to: TaxYear) -> Money {
+    amount * (consumer_price_index(to) / consumer_price_index(from))
+}
+
+// The same input under every supported year's rules
+pub fn compare_years(base: &TaxCalculationInput) -> Vec<(TaxYear, TaxCalculationResult)> {
+    let inputs = TaxYear::ALL
+        .iter()
+        .map(|&tax_year| TaxCalculationInput {
+            tax_year,
+            ..base.clone()
+        })
+        .collect::<Vec<_>>();
+
+    TaxYear::ALL
+        .into_iter()
+        .zip(NorwegianTaxCalculator::calculate_many(&inputs))
+        .collect()
+}
+// This year's estimate against the tax assessed last year. The same input
+// under last year's rules splits the change in two: what the new rules do,
+// and the rest, which comes from changed income and deductions or from items
+// in the assessment that the input leaves out.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LastYearComparison {
+    pub assessed: Money,
+    pub estimate: Money,
+    // None when the calculator has no rules for last year
+    pub rule_changes: Option<Money>,
+}
+
+impl LastYearComparison {
+    pub fn change(&self) -> Money {
+        self.estimate - self.assessed
+    }
+
+    pub fn other_changes(&self) -> Money {
+        self.change() - self.rule_changes.unwrap_or_default()
+    }
+}
+
+pub fn compare_with_last_year(base: &TaxCalculationInput, assessed: Money) -> LastYearComparison {
+    let estimate = NorwegianTaxCalculator::calculate_totals_only(base).total_tax;
+    let rule_changes = base.tax_year.previous().map(|tax_year| {
+        estimate - NorwegianTaxCalculator::calculate_totals_only(&TaxCalculationInput { tax_year, ..base.clone() }).total_tax
+    });
+    LastYearComparison { assessed, estimate, rule_changes }
+}
+
+pub const WORKING_DAYS_PER_YEAR: f64 = 230.0;
+
+const MONTH_NAMES: [&str; 12] = [
+    "januar", "februar", "mars", "april", "mai", "juni", "juli", "august", "september", "oktober", "november", "desember",
+];
+
+// The yearly tax spread over the year. The tax freedom day is the first day
+// whose income the user keeps if all of the year's tax is paid first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TaxCalendar {
+    pub per_month: Money,
+    pub per_working_day: Money,
+    // Month from 1, and day of the month
+    pub freedom_day: (usize, u32),
+}
+
+impl TaxCalendar {
+    pub fn freedom_day_label(&self) -> String {
+        let (month, day) = self.freedom_day;
+        format!("{}. {}", day, MONTH_NAMES[month - 1])
+    }
+}
+
+pub fn tax_calendar(result: &TaxCalculationResult) -> TaxCalendar {
+    let year = result.tax_year.year();
+    let leap_year = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    let month_lengths = [31, if leap_year { 29 } else { 28 }, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    let days: u32 = month_lengths.iter().sum();
+
+    let share = result.effective_tax_rate.fraction().clamp(0.0, 1.0);
+    let mut day = ((share * days as f64).floor() as u32 + 1).min(days);
+    let mut month = 0;
+    while day > month_lengths[month] {
+        day -= month_lengths[month];
+        month += 1;
+    }
+
+    TaxCalendar {
+        per_month: result.total_tax / 12.0,
+        per_working_day: result.total_tax / WORKING_DAYS_PER_YEAR,
+        freedom_day: (month + 1, day),
+    }
+}
+
+// Assumptions behind the soft-benefit adjustments. The AS side assumes the
+// owner draws the profit as salary and so has the rights of an employee.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenefitAssumptions {
+    pub sick_days: f64,
+    // Share of the yearly pension accrual counted as income today
+    pub pension_weight: Rate,
+    // Yearly value put on the right to dagpenger, which ENK owners don't have
+    pub unemployment_value: Money,
+}
+
+impl Default for BenefitAssumptions {
+    fn default() -> Self {
+        Self {
+            sick_days: 10.0,
+            pension_weight: Rate::from_percent(50.0),
+            unemployment_value: Money::ZERO,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenefitAdjustment {
+    pub label: &'static str,
+    pub enk: Money,
+    pub corporation: Money,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntityComparison {
+    pub enk: TaxCalculationResult,
+    pub corporation: TaxCalculationResult,
+    pub adjustments: Vec<BenefitAdjustment>,
+}
+
+impl EntityComparison {
+    pub fn enk_adjusted(&self) -> Money {
+        self.enk.net_income + self.adjustments.iter().map(|adjustment| adjustment.enk).sum::<Money>()
+    }
+
+    pub fn corporation_adjusted(&self) -> Money {
+        self.corporation.net_income + self.adjustments.iter().map(|adjustment| adjustment.corporation).sum::<Money>()
+    }
+}
+
+// Income lost to expected sick days, given the share of each day covered
+fn sick_pay_loss(income: Money, tax_year: TaxYear, sick_days: f64, waiting_days: f64, coverage: f64) -> Money {
+    let daily_income = income.max(Money::ZERO) / WORKING_DAYS_PER_YEAR;
+    let covered_daily = income.clamp(Money::ZERO, tax_year.rules().grunnbeloep.times(SICK_PAY_CAP_G)) / WORKING_DAYS_PER_YEAR;
+    let uncovered_days = sick_days.min(waiting_days);
+    let paid_days = (sick_days - waiting_days).max(0.0);
+    daily_income * uncovered_days + (daily_income - covered_daily * coverage) * paid_days
+}
+
+// ENK against AS with the differences in social security rights priced in,
+// so the verdict isn't based on tax alone
+pub fn compare_enk_and_as(base: &TaxCalculationInput, assumptions: &BenefitAssumptions) -> EntityComparison {
+    let enk = NorwegianTaxCalculator::calculate_tax(&base.comparison_variant(EntityType::SoleProprietorship));
+    let corporation = NorwegianTaxCalculator::calculate_tax(&base.comparison_variant(EntityType::Corporation));
+    let tax_year = base.tax_year;
+    let enk_income = (base.gross_income - base.business_expenses).max(Money::ZERO);
+    let salary = base.gross_income.max(Money::ZERO);
+
+    // Employees are covered in full from the first day; ENK owners get 80%
+    // from day 17 unless they pay for extra cover
+    let sick_days = assumptions.sick_days.max(0.0);
+    let (waiting_days, coverage) = base.sick_pay_insurance.coverage();
+    let sick_pay = BenefitAdjustment {
+        label: "Sykepenger",
+        enk: -sick_pay_loss(enk_income, tax_year, sick_days, waiting_days, coverage),
+        corporation: -sick_pay_loss(salary, tax_year, sick_days, 0.0, 1.0),
+    };
+
+    let pension = BenefitAdjustment {
+        label: "Pensjonsopptjening",
+        enk: enk.pension_accrual * assumptions.pension_weight,
+        corporation: NorwegianTaxCalculator::pension_accrual(tax_year, salary) * assumptions.pension_weight,
+    };
+
+    let unemployment = BenefitAdjustment {
+        label: "Dagpenger",
+        enk: Money::ZERO,
+        corporation: assumptions.unemployment_value.max(Money::ZERO),
+    };
+
+    EntityComparison {
+        enk,
+        corporation,
+        adjustments: vec![sick_pay, pension, unemployment],
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MunicipalWealthTax {
+    pub municipal_rate: Rate,
+    // The state's share and the municipality's together
+    pub wealth_tax_rate: Rate,
+    pub wealth_tax: Money,
+}
+
+// Wealth tax on the same wealth with another municipality's share of the rate
+pub fn wealth_tax_with_municipal_rate(base: &TaxCalculationInput, municipal_rate: Rate) -> MunicipalWealthTax {
+    let input = TaxCalculationInput {
+        municipal_wealth_tax_rate: Some(municipal_rate),
+        ..base.clone()
+    };
+    MunicipalWealthTax {
+        municipal_rate,
+        wealth_tax_rate: NorwegianTaxCalculator::wealth_tax_rate(&input),
+        wealth_tax: NorwegianTaxCalculator::calculate_totals_only(&input).wealth_tax,
+    }
+}
+
+// Until the tax card is changed, the employer withholds half of the pay
+// above the frikort limit
+pub const FRIKORT_EXCESS_WITHHOLDING: Rate = Rate::from_percent(50.0);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrikortStatus {
+    pub income: Money,
+    pub limit: Money,
+    pub minstefradrag: Money,
+    // Withheld over the year on the frikort
+    pub withholding: Money,
+    // The year's tax, settled in the skatteoppgjør
+    pub tax: Money,
+    // The highest pay with no tax at all, at most the frikort limit
+    pub tax_free_income: Money,
+}
+
+impl FrikortStatus {
+    pub fn within_limit(&self) -> bool {
+        self.income <= self.limit
+    }
+
+    // Pay left before tax is withheld
+    pub fn remaining(&self) -> Money {
+        (self.limit - self.income).max(Money::ZERO)
+    }
+
+    // Pay left before any tax is owed
+    pub fn remaining_tax_free(&self) -> Money {
+        (self.tax_free_income - self.income).max(Money::ZERO)
+    }
+
+    // Restskatt when positive, money back when negative
+    pub fn settlement(&self) -> Money {
+        self.tax - self.withholding
+    }
+}
+
+// The tax on wages alone, with minstefradrag as the only deduction, which is
+// also what the withholding tables assume
+fn wage_tax(base: &TaxCalculationInput, wages: Money) -> Money {
+    let input = TaxCalculationInput {
+        gross_income: wages,
+        allowable_deductions: base.rules().minstefradrag(wages),
+        commute: CommuteInput::default(),
+        donations: Money::ZERO,
+        ips_contribution: Money::ZERO,
+        special_deductions: SpecialDeductionsInput::default(),
+        custom_taxes: Vec::new(),
+        property_tax: PropertyTaxInput::default(),
+        ..earned_income_only(base, EntityType::Individual, wages)
+    };
+    NorwegianTaxCalculator::calculate_totals_only(&input).total_tax
+}
+
+pub fn frikort_status(base: &TaxCalculationInput, income: Money) -> FrikortStatus {
+    let rules = base.rules();
+    let limit = rules.frikort_limit;
+    let owes_tax = |income: Money| wage_tax(base, income) > Money::ZERO;
+
+    // Tax rises with pay, so the last krone without any can be bisected
+    let (mut low, mut high) = (Money::ZERO, limit);
+    if !owes_tax(high) {
+        low = high;
+    }
+    while high - low > Money(1.0) {
+        let middle = Money(((low + high) / 2.0).amount().floor());
+        if owes_tax(middle) {
+            high = middle;
+        } else {
+            low = middle;
+        }
+    }
+
+    FrikortStatus {
+        income,
+        limit,
+        minstefradrag: rules.minstefradrag(income),
+        withholding: (income - limit).max(Money::ZERO) * FRIKORT_EXCESS_WITHHOLDING,
+        tax: wage_tax(base, income),
+        tax_free_income: low,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DualEmployment {
+    // Tabelltrekk on the main job, which withholds the year's tax as if that
+    // pay were the only income
+    pub main_withholding: Money,
+    // Prosenttrekk on the secondary job
+    pub secondary_withholding: Money,
+    // The tax on both jobs' pay together
+    pub tax: Money,
+    // The lowest prosenttrekk that covers the tax on the secondary pay
+    pub required_rate: Rate,
+}
+
+impl DualEmployment {
+    pub fn withholding(&self) -> Money {
+        self.main_withholding + self.secondary_withholding
+    }
+
+    // Restskatt when positive, money back when negative
+    pub fn settlement(&self) -> Money {
+        self.tax - self.withholding()
+    }
+}
+
+pub fn dual_employment(base: &TaxCalculationInput, main_pay: Money, secondary_pay: Money, secondary_rate: Rate) -> DualEmployment {
+    let main_withholding = wage_tax(base, main_pay);
+    let tax = wage_tax(base, main_pay + secondary_pay);
+    let required_rate = if secondary_pay > Money::ZERO {
+        Rate::from_fraction(((tax - main_withholding) / secondary_pay).max(0.0))
+    } else {
+        Rate::ZERO
+    };
+    DualEmployment {
+        main_withholding,
+        secondary_withholding: secondary_pay * secondary_rate,
+        tax,
+        required_rate,
+    }
+}
+
+// Dagpenger are 62.4 % of the income before the job loss, counted up to 6G
+pub const DAGPENGER_RATE: Rate = Rate::from_percent(62.4);
+pub const DAGPENGER_CAP_G: f64 = 6.0;
+
+// A year of pay and a benefit taxed like pay, such as dagpenger
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenefitYear {
+    pub salary: Money,
+    pub benefit: Money,
+    pub tax: Money,
+}
+
+impl BenefitYear {
+    fn new(base: &TaxCalculationInput, salary: Money, benefit: Money) -> Self {
+        Self {
+            salary,
+            benefit,
+            tax: wage_tax(base, salary + benefit),
+        }
+    }
+
+    pub fn income(&self) -> Money {
+        self.salary + self.benefit
+    }
+
+    pub fn net_income(&self) -> Money {
+        self.income() - self.tax
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnemploymentScenario {
+    pub full_year: BenefitYear,
+    pub with_dagpenger: BenefitYear,
+}
+
+// The year's salary for the months in work and dagpenger for the rest,
+// against a full year in work
+pub fn unemployment_scenario(base: &TaxCalculationInput, salary: Money, prior_income: Money, months: f64) -> UnemploymentScenario {
+    let months = months.clamp(0.0, 12.0);
+    let cap = base.rules().grunnbeloep.times(DAGPENGER_CAP_G);
+    let dagpenger = prior_income.clamp(Money::ZERO, cap) * DAGPENGER_RATE * (months / 12.0);
+    UnemploymentScenario {
+        full_year: BenefitYear::new(base, salary, Money::ZERO),
+        with_dagpenger: BenefitYear::new(base, salary * ((12.0 - months) / 12.0), dagpenger),
+    }
+}
+
+pub const FORELDREPENGER_CAP_G: f64 = 6.0;
+const WEEKS_PER_YEAR: f64 = 52.0;
+
+// The same leave quota taken with full pay or spread over more weeks at 80 %
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParentalCoverage {
+    Full,
+    Reduced,
+}
+
+impl ParentalCoverage {
+    pub const ALL: [ParentalCoverage; 2] = [ParentalCoverage::Full, ParentalCoverage::Reduced];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ParentalCoverage::Full => "100 % dekning",
+            ParentalCoverage::Reduced => "80 % dekning",
+        }
+    }
+
+    pub fn rate(&self) -> Rate {
+        match self {
+            ParentalCoverage::Full => Rate::from_percent(100.0),
+            ParentalCoverage::Reduced => Rate::from_percent(80.0),
+        }
+    }
+
+    // Weeks of leave per week at full coverage: the whole period is 49
+    // weeks at 100 % or 61 weeks at 80 %
+    pub fn weeks_factor(&self) -> f64 {
+        match self {
+            ParentalCoverage::Full => 1.0,
+            ParentalCoverage::Reduced => 61.0 / 49.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParentalLeave {
+    pub coverage: ParentalCoverage,
+    // Weeks of leave in the year; the rest of a longer leave falls in the next
+    pub weeks: f64,
+    pub year: BenefitYear,
+}
+
+// The year's income with a leave starting at the beginning of the year.
+// NAV pays foreldrepenger on salary up to 6G, and an employer that tops up
+// pays the rest of the salary at the same coverage.
+pub fn parental_leave(
+    base: &TaxCalculationInput,
+    salary: Money,
+    full_coverage_weeks: f64,
+    employer_top_up: bool,
+    coverage: ParentalCoverage,
+) -> ParentalLeave {
+    let salary = salary.max(Money::ZERO);
+    let weeks = (full_coverage_weeks.max(0.0) * coverage.weeks_factor()).min(WEEKS_PER_YEAR);
+    let share_on_leave = weeks / WEEKS_PER_YEAR;
+    let cap = base.rules().grunnbeloep.times(FORELDREPENGER_CAP_G);
+    let foreldrepenger = salary.min(cap) * coverage.rate() * share_on_leave;
+    let top_up = if employer_top_up {
+        (salary - cap).max(Money::ZERO) * coverage.rate() * share_on_leave
+    } else {
+        Money::ZERO
+    };
+    ParentalLeave {
+        coverage,
+        weeks,
+        year: BenefitYear::new(base, salary * (1.0 - share_on_leave) + top_up, foreldrepenger),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequiredGross {
+    pub salary: Option<Money>,
+    // Includes the business expenses from the input
+    pub enk_revenue: Option<Money>,
+    // Paid out in full as dividend to the owner
+    pub as_profit: Option<Money>,
+}
+
+// Only income from work counts towards the target, so investment income,
+// benefits and wealth from the input are left out
+fn earned_income_only(base: &TaxCalculationInput, entity_type: EntityType, gross_income: Money) -> TaxCalculationInput {
+    TaxCalculationInput {
+        gross_income,
+        sick_pay: Money::ZERO,
+        disability_benefit: Money::ZERO,
+        dividend_income: Money::ZERO,
+        capital_gains: Money::ZERO,
+        investment_wealth: Money::ZERO,
+        ..base.comparison_variant(entity_type)
+    }
+}
+
+// What each entity type has to earn for the owner to net the given monthly amount
+pub fn required_gross_for_net(base: &TaxCalculationInput, monthly_net: Money) -> RequiredGross {
+    let target = monthly_net * 12.0;
+    let salary_net = |salary: Money| {
+        NorwegianTaxCalculator::calculate_totals_only(&earned_income_only(base, EntityType::Individual, salary)).net_income
+    };
+
+    let as_net_dividend = |profit: Money| {
+        let company = NorwegianTaxCalculator::calculate_totals_only(&earned_income_only(base, EntityType::Corporation, profit));
+        let owner = TaxCalculationInput {
+            dividend_income: company.net_income.max(Money::ZERO),
+            ..earned_income_only(base, EntityType::Individual, Money::ZERO)
+        };
+        NorwegianTaxCalculator::calculate_totals_only(&owner).net_income
+    };
+
+    RequiredGross {
+        salary: solve_gross_for_net(target, salary_net),
+        enk_revenue: required_enk_revenue(base, target, base.business_expenses),
+        as_profit: solve_gross_for_net(target, as_net_dividend),
+    }
+}
+
+// ENK revenue that leaves the owner the target after tax and expenses. The
+// ENK result's net income is before the business expenses are paid.
+fn required_enk_revenue(base: &TaxCalculationInput, target_net: Money, business_expenses: Money) -> Option<Money> {
+    solve_gross_for_net(target_net, |revenue| {
+        let input = TaxCalculationInput {
+            business_expenses,
+            ..earned_income_only(base, EntityType::SoleProprietorship, revenue)
+        };
+        NorwegianTaxCalculator::calculate_totals_only(&input).net_income - business_expenses
+    })
+}
+
+#[cfg(feature = "vat")]
+pub const MVA_RATE: Rate = Rate::from_percent(25.0);
+// Turnover over twelve months above which registration is mandatory
+#[cfg(feature = "vat")]
+pub const MVA_REGISTRATION_THRESHOLD: Money = Money(50_000.0);
+
+#[cfg(feature = "vat")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct HourlyRate {
+    // Yearly revenue excluding MVA
+    pub revenue: Money,
+    pub rate: Money,
+    // What the customer pays per hour when MVA is charged
+    pub rate_with_mva: Option<Money>,
+    pub must_register: bool,
+}
+
+// Hourly rate an ENK consultant has to invoice to net the given monthly
+// amount. Expenses are taken to include MVA, which a registered business
+// gets back as inngående MVA.
+#[cfg(feature = "vat")]
+pub fn required_hourly_rate(
+    base: &TaxCalculationInput,
+    monthly_net: Money,
+    billable_hours: f64,
+    mva_registered: bool,
+) -> Option<HourlyRate> {
+    if billable_hours <= 0.0 {
+        return None;
+    }
+    let business_expenses = if mva_registered {
+        base.business_expenses / (1.0 + MVA_RATE.fraction())
+    } else {
+        base.business_expenses
+    };
+    let revenue = required_enk_revenue(base, monthly_net * 12.0, business_expenses)?;
+    let rate = revenue / billable_hours;
+
+    Some(HourlyRate {
+        revenue,
+        rate,
+        rate_with_mva: mva_registered.then_some(rate + rate * MVA_RATE),
+        must_register: !mva_registered && revenue > MVA_REGISTRATION_THRESHOLD,
+    })
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SmoothingYear {
+    pub profit: Money,
+    pub tax: Money,
+    pub even_profit: Money,
+    pub even_tax: Money,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IncomeSmoothing {
+    pub years: Vec<SmoothingYear>,
+}
+
+impl IncomeSmoothing {
+    pub fn total_tax(&self) -> Money {
+        self.years.iter().map(|year| year.tax).sum()
+    }
+
+    pub fn even_total_tax(&self) -> Money {
+        self.years.iter().map(|year| year.even_tax).sum()
+    }
+
+    // Tax saved by earning the same total evenly across the years
+    pub fn saving(&self) -> Money {
+        self.total_tax() - self.even_total_tax()
+    }
+}
+
+// ENK profits year by year against the same total spread evenly. Trinnskatt
+// is progressive, so a lumpy profit pays more in total.
+pub fn income_smoothing(base: &TaxCalculationInput, profits: &[Money]) -> IncomeSmoothing {
+    let even_profit = profits.iter().sum::<Money>() / profits.len().max(1) as f64;
+    let enk_tax = |profit: Money| {
+        let input = TaxCalculationInput {
+            gross_income: profit,
+            business_expenses: Money::ZERO,
+            ..base.comparison_variant(EntityType::SoleProprietorship)
+        };
+        NorwegianTaxCalculator::calculate_totals_only(&input).total_tax
+    };
+    let even_tax = enk_tax(even_profit);
+
+    IncomeSmoothing {
+        years: profits
+            .iter()
+            .map(|&profit| SmoothingYear {
+                profit,
+                tax: enk_tax(profit),
+                even_profit,
+                even_tax,
+            })
+            .collect(),
+    }
+}
+
+// One spouse's side of the household's wealth
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpouseShare {
+    // Net wealth the spouse owns alone
+    pub own: Money,
+    pub joint_assets: Money,
+    pub joint_debt: Money,
+    pub wealth_tax: Money,
+}
+
+impl SpouseShare {
+    pub fn net_wealth(&self) -> Money {
+        self.own + self.joint_assets - self.joint_debt
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpouseAllocation {
+    pub jointly_assessed: bool,
+    // Jointly held assets and debt split half and half
+    pub even: [SpouseShare; 2],
+    pub recommended: [SpouseShare; 2],
+}
+
+impl SpouseAllocation {
+    pub fn even_tax(&self) -> Money {
+        self.even.iter().map(|share| share.wealth_tax).sum()
+    }
+
+    pub fn recommended_tax(&self) -> Money {
+        self.recommended.iter().map(|share| share.wealth_tax).sum()
+    }
+
+    pub fn saving(&self) -> Money {
+        self.even_tax() - self.recommended_tax()
+    }
+}
+
+// Splits jointly held assets and debt between two spouses to keep their
+// combined wealth tax as low as possible. Married couples are assessed
+// together with twice the threshold, so how the joint wealth is split does
+// not change their tax and the even split stands. Spouses assessed
+// separately each get their own threshold, and since the tax only grows
+// above it, the least tax comes from evening out their net wealth. Debt is
+// moved before assets.
+pub fn spouse_wealth_allocation(
+    base: &TaxCalculationInput,
+    own: [Money; 2],
+    joint_assets: Money,
+    joint_debt: Money,
+    jointly_assessed: bool,
+) -> SpouseAllocation {
+    let wealth_tax = |wealth: Money| {
+        let input = TaxCalculationInput {
+            investment_wealth: wealth,
+            ..base.clone()
+        };
+        NorwegianTaxCalculator::calculate_totals_only(&input).wealth_tax
+    };
+    let split = |first_assets: Money, first_debt: Money| {
+        let mut shares = [
+            (own[0], first_assets, first_debt),
+            (own[1], joint_assets - first_assets, joint_debt - first_debt),
+        ]
+        .map(|(own, joint_assets, joint_debt)| SpouseShare {
+            own,
+            joint_assets,
+            joint_debt,
+            wealth_tax: Money::ZERO,
+        });
+        if jointly_assessed {
+            let half = shares.iter().map(SpouseShare::net_wealth).sum::<Money>() / 2.0;
+            let tax = wealth_tax(half);
+            shares.iter_mut().for_each(|share| share.wealth_tax = tax);
+        } else {
+            shares.iter_mut().for_each(|share| share.wealth_tax = wealth_tax(share.net_wealth()));
+        }
+        shares
+    };
+
+    let even = split(joint_assets / 2.0, joint_debt / 2.0);
+    if jointly_assessed {
+        return SpouseAllocation {
+            jointly_assessed,
+            recommended: even.clone(),
+            even,
+        };
+    }
+
+    // The first spouse's net share of the joint wealth that evens out the
+    // two, as far as the joint assets and debt reach
+    let joint = joint_assets - joint_debt;
+    let target = ((own[1] + joint - own[0]) / 2.0).clamp(-joint_debt, joint_assets);
+    let shift = target - joint / 2.0;
+    let first_debt = (joint_debt / 2.0 - shift).clamp(Money::ZERO, joint_debt);
+    let first_assets = (joint_assets / 2.0 + shift - (joint_debt / 2.0 - first_debt)).clamp(Money::ZERO, joint_assets);
+
+    SpouseAllocation {
+        jointly_assessed,
+        even,
+        recommended: split(first_assets, first_debt),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PensionSavingComparison {
+    pub contributions: Money,
+    // Lower tax over the saving years from the IPS deduction
+    pub tax_saved: Money,
+    pub ips_balance: Money,
+    pub ips_withdrawal_tax: Money,
+    // A fund bought with what the IPS contributions cost after the deduction
+    pub fund_balance: Money,
+    pub fund_gain_tax: Money,
+}
+
+impl PensionSavingComparison {
+    pub fn ips_net(&self) -> Money {
+        self.ips_balance - self.ips_withdrawal_tax
+    }
+
+    pub fn fund_net(&self) -> Money {
+        self.fund_balance - self.fund_gain_tax
+    }
+
+    pub fn advantage(&self) -> Money {
+        self.ips_net() - self.fund_net()
+    }
+}
+
+// The same yearly contribution to IPS and, for the same net cost, to an
+// ordinary fund. IPS is deducted now and taxed as alminnelig inntekt at
+// withdrawal; the fund is bought with taxed income and its gain is taxed at
+// the shareholder rate. Skjerming and wealth tax are left out.
+pub fn ips_comparison(
+    base: &TaxCalculationInput,
+    yearly: Money,
+    years: u32,
+    annual_return: Rate,
+    withdrawal_rate: Rate,
+) -> PensionSavingComparison {
+    let tax = |ips_contribution: Money| {
+        NorwegianTaxCalculator::calculate_totals_only(&TaxCalculationInput {
+            ips_contribution,
+            ..base.clone()
+        })
+        .total_tax
+    };
+    let yearly = yearly.max(Money::ZERO);
+    let yearly_saving = tax(Money::ZERO) - tax(yearly);
+    // Paid in at the end of each year
+    let growth = |payment: Money| {
+        (0..years).fold(Money::ZERO, |balance, _| balance * (1.0 + annual_return.fraction()) + payment)
+    };
+    let ips_balance = growth(yearly);
+    let fund_payment = yearly - yearly_saving;
+    let fund_balance = growth(fund_payment);
+    PensionSavingComparison {
+        contributions: yearly * years as f64,
+        tax_saved: yearly_saving * years as f64,
+        ips_balance,
+        ips_withdrawal_tax: ips_balance * withdrawal_rate,
+        fund_balance,
+        fund_gain_tax: (fund_balance - fund_payment * years as f64).max(Money::ZERO) * base.rules().investment_tax_rate,
+    }
+}
+
+// Yearly payments to and from the household that are outside the tax base:
+// barnetrygd and kontantstøtte are tax-free, and barnebidrag is neither
+// taxed for the one who gets it nor deductible for the one who pays it
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HouseholdTransfers {
+    pub child_benefit: Money,
+    pub cash_for_care: Money,
+    pub child_support_received: Money,
+    pub child_support_paid: Money,
+}
+
+impl HouseholdTransfers {
+    pub fn net(&self) -> Money {
+        self.child_benefit + self.cash_for_care + self.child_support_received - self.child_support_paid
+    }
+}
+
+// Each spouse's tax when assessed alone next to the couple assessed together
+#[derive(Clone, Debug, PartialEq)]
+pub struct HouseholdComparison {
+    pub gross_income: [Money; 2],
+    // Tax on each spouse's own income, which is assessed separately either way
+    pub income_tax: [Money; 2],
+    pub separate: SpouseAllocation,
+    pub joint: SpouseAllocation,
+    pub transfers: HouseholdTransfers,
+}
+
+impl HouseholdComparison {
+    // Alone, with the joint assets and debt split half and half
+    pub fn standalone_tax(&self) -> [Money; 2] {
+        [0, 1].map(|spouse| self.income_tax[spouse] + self.separate.even[spouse].wealth_tax)
+    }
+
+    pub fn joint_tax(&self) -> [Money; 2] {
+        [0, 1].map(|spouse| self.income_tax[spouse] + self.joint.recommended[spouse].wealth_tax)
+    }
+
+    // Saved by splitting the joint assets and debt well while still assessed apart
+    pub fn allocation_saving(&self) -> Money {
+        self.separate.saving()
+    }
+
+    // Saved on top of that by sharing the doubled threshold
+    pub fn threshold_saving(&self) -> Money {
+        self.separate.recommended_tax() - self.joint.recommended_tax()
+    }
+
+    pub fn saving(&self) -> Money {
+        self.allocation_saving() + self.threshold_saving()
+    }
+
+    // The household's tax with the recommended split
+    pub fn total_tax(&self, jointly_assessed: bool) -> Money {
+        let allocation = if jointly_assessed { &self.joint } else { &self.separate };
+        self.income_tax.iter().copied().sum::<Money>() + allocation.recommended_tax()
+    }
+
+    // Income after tax, plus the transfers outside the tax base
+    pub fn disposable_income(&self, jointly_assessed: bool) -> Money {
+        self.gross_income.iter().copied().sum::<Money>() - self.total_tax(jointly_assessed) + self.transfers.net()
+    }
+}
+
+// The base input is the first spouse; the second has only the given pay
+pub fn household_comparison(
+    base: &TaxCalculationInput,
+    second_income: Money,
+    own: [Money; 2],
+    joint_assets: Money,
+    joint_debt: Money,
+    transfers: HouseholdTransfers,
+) -> HouseholdComparison {
+    let income_tax = |input: &TaxCalculationInput| {
+        let totals = NorwegianTaxCalculator::calculate_totals_only(input);
+        totals.total_tax - totals.wealth_tax
+    };
+    HouseholdComparison {
+        gross_income: [base.gross_income, second_income],
+        income_tax: [
+            income_tax(base),
+            income_tax(&earned_income_only(base, EntityType::Individual, second_income)),
+        ],
+        separate: spouse_wealth_allocation(base, own, joint_assets, joint_debt, false),
+        joint: spouse_wealth_allocation(base, own, joint_assets, joint_debt, true),
+        transfers,
+    }
+}
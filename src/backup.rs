@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::clients::{load_clients, save_client, ClientProfile};
+use crate::municipalities::MUNICIPALITIES_KEY;
+use crate::storage;
+
+// Bumped when the layout changes, so an older app can refuse a newer file
+pub const BACKUP_VERSION: u32 = 1;
+pub const BACKUP_FILENAME: &str = "skattekalkulator-sikkerhetskopi.json";
+
+// Everything the app keeps in the browser: the session, saved scenarios,
+// comparisons and settings from local storage, and the client profiles
+// from IndexedDB
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Backup {
+    pub version: u32,
+    // Local storage values by key, as saved
+    pub settings: BTreeMap<String, Value>,
+    #[serde(default)]
+    pub clients: Vec<ClientProfile>,
+}
+
+// The municipality list is a cache of public data and is fetched again when
+// needed, so it is neither exported nor restored
+fn is_backed_up(key: &str) -> bool {
+    key.starts_with(storage::KEY_PREFIX) && key != MUNICIPALITIES_KEY
+}
+
+pub async fn export_backup() -> Result<Vec<u8>, String> {
+    let settings = storage::entries()
+        .into_iter()
+        .filter(|(key, _)| is_backed_up(key))
+        .filter_map(|(key, raw)| Some((key, serde_json::from_str(&raw).ok()?)))
+        .collect();
+    let clients = load_clients()
+        .await
+        .map_err(|err| format!("Kunne ikke hente klienter: {:?}", err))?;
+    let backup = Backup {
+        version: BACKUP_VERSION,
+        settings,
+        clients,
+    };
+    serde_json::to_vec_pretty(&backup).map_err(|err| err.to_string())
+}
+
+pub fn parse_backup(text: &str) -> Result<Backup, String> {
+    let backup: Backup = serde_json::from_str(text).map_err(|err| format!("Ikke en gyldig sikkerhetskopi: {}", err))?;
+    if backup.version > BACKUP_VERSION {
+        return Err("Sikkerhetskopien er laget av en nyere versjon av kalkulatoren".to_string());
+    }
+    Ok(backup)
+}
+
+// Settings and clients in the backup replace the ones with the same key or
+// id; anything else already saved in the browser is kept
+pub async fn restore_backup(backup: &Backup) -> Result<(), String> {
+    for (key, value) in backup.settings.iter().filter(|(key, _)| is_backed_up(key)) {
+        storage::save(key, value);
+    }
+    for client in &backup.clients {
+        save_client(client)
+            .await
+            .map_err(|err| format!("Kunne ikke lagre klienten {}: {:?}", client.name, err))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::money::{Money, Rate};
+use crate::tax_calculator::{EntityType, NorwegianTaxCalculator, TaxCalculationInput, TaxCalculationResult, TaxYear};
+
+// Columns accepted in a client CSV. Only `name` is required; a missing
+// column or empty cell falls back to the calculator's default input.
+pub const CLIENT_COLUMNS: [&str; 14] = [
+    "name",
+    "entity_type",
+    "tax_year",
+    "gross_income",
+    "allowable_deductions",
+    "business_expenses",
+    "branch_payroll",
+    "dividend_income",
+    "capital_gains",
+    "investment_wealth",
+    "municipal_tax_rate",
+    "county_tax_rate",
+    "church_tax_rate",
+    "is_church_member",
+];
+
+#[derive(Debug)]
+pub enum BatchImportError {
+    Csv(csv::Error),
+    UnknownTaxYear { row: usize, year: u16 },
+}
+
+impl fmt::Display for BatchImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchImportError::Csv(err) => write!(f, "Ugyldig CSV: {}", err),
+            BatchImportError::UnknownTaxYear { row, year } => {
+                write!(f, "Rad {}: skatteåret {} støttes ikke", row, year)
+            }
+        }
+    }
+}
+
+impl From<csv::Error> for BatchImportError {
+    fn from(err: csv::Error) -> Self {
+        BatchImportError::Csv(err)
+    }
+}
+
+#[derive(Deserialize)]
+struct ClientRecord {
+    name: String,
+    entity_type: Option<EntityType>,
+    tax_year: Option<u16>,
+    gross_income: Option<Money>,
+    allowable_deductions: Option<Money>,
+    business_expenses: Option<Money>,
+    branch_payroll: Option<Money>,
+    dividend_income: Option<Money>,
+    capital_gains: Option<Money>,
+    investment_wealth: Option<Money>,
+    // In percent, like the form
+    municipal_tax_rate: Option<Rate>,
+    county_tax_rate: Option<Rate>,
+    church_tax_rate: Option<Rate>,
+    is_church_member: Option<bool>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchClient {
+    pub name: String,
+    pub input: TaxCalculationInput,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchRow {
+    pub name: String,
+    pub input: TaxCalculationInput,
+    pub result: TaxCalculationResult,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BatchSortKey {
+    Name,
+    GrossIncome,
+    TotalTax,
+    NetIncome,
+    EffectiveRate,
+}
+
+pub fn parse_clients(data: &str) -> Result<Vec<BatchClient>, BatchImportError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(data.as_bytes());
+
+    reader
+        .deserialize::<ClientRecord>()
+        .enumerate()
+        .map(|(index, record)| {
+            let record = record?;
+            let defaults = TaxCalculationInput::default();
+            let tax_year = match record.tax_year {
+                Some(year) => TaxYear::from_year(year)
+                    .ok_or(BatchImportError::UnknownTaxYear { row: index + 1, year })?,
+                None => defaults.tax_year,
+            };
+
+            Ok(BatchClient {
+                name: record.name,
+                input: TaxCalculationInput {
+                    gross_income: record.gross_income.unwrap_or(defaults.gross_income),
+                    entity_type: record.entity_type.unwrap_or(defaults.entity_type),
+                    municipal_tax_rate: record.municipal_tax_rate.unwrap_or(defaults.municipal_tax_rate),
+                    county_tax_rate: record.county_tax_rate.unwrap_or(defaults.county_tax_rate),
+                    church_tax_rate: record.church_tax_rate.unwrap_or(defaults.church_tax_rate),
+                    is_church_member: record.is_church_member.unwrap_or(defaults.is_church_member),
+                    historical_church_tax: defaults.historical_church_tax,
+                    allowable_deductions: record.allowable_deductions.unwrap_or(defaults.allowable_deductions),
+                    dividend_income: record.dividend_income.unwrap_or(defaults.dividend_income),
+                    capital_gains: record.capital_gains.unwrap_or(defaults.capital_gains),
+                    investment_wealth: record.investment_wealth.unwrap_or(defaults.investment_wealth),
+                    business_expenses: record.business_expenses.unwrap_or(defaults.business_expenses),
+                    commute: defaults.commute,
+                    donations: defaults.donations,
+                    ips_contribution: defaults.ips_contribution,
+                    special_deductions: defaults.special_deductions,
+                    sick_pay: defaults.sick_pay,
+                    disability_benefit: defaults.disability_benefit,
+                    tax_year,
+                    municipality: None,
+                    sick_pay_insurance: defaults.sick_pay_insurance,
+                    tiltakssone: defaults.tiltakssone,
+                    municipal_wealth_tax_rate: defaults.municipal_wealth_tax_rate,
+                    branch_payroll: record.branch_payroll.unwrap_or(defaults.branch_payroll),
+                    charitable_purpose: defaults.charitable_purpose,
+                    resource_rent: defaults.resource_rent,
+                    petroleum: defaults.petroleum,
+                    custom_taxes: defaults.custom_taxes,
+                    property_tax: defaults.property_tax,
+                    policy: None,
+                },
+            })
+        })
+        .collect()
+}
+
+pub fn calculate_clients(clients: &[BatchClient]) -> Vec<BatchRow> {
+    let inputs = clients.iter().map(|client| client.input.clone()).collect::<Vec<_>>();
+
+    clients
+        .iter()
+        .zip(NorwegianTaxCalculator::calculate_many(&inputs))
+        .map(|(client, result)| BatchRow {
+            name: client.name.clone(),
+            input: client.input.clone(),
+            result,
+        })
+        .collect()
+}
+
+pub fn sort_rows(rows: &mut [BatchRow], key: BatchSortKey, ascending: bool) {
+    rows.sort_by(|a, b| {
+        let ordering = match key {
+            BatchSortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            BatchSortKey::GrossIncome => a.result.gross_income.total_cmp(&b.result.gross_income),
+            BatchSortKey::TotalTax => a.result.total_tax.total_cmp(&b.result.total_tax),
+            BatchSortKey::NetIncome => a.result.net_income.total_cmp(&b.result.net_income),
+            BatchSortKey::EffectiveRate => a.result.effective_tax_rate.total_cmp(&b.result.effective_tax_rate),
+        };
+        if ascending { ordering } else { ordering.reverse() }
+    });
+}
+
+#[derive(Serialize)]
+struct ResultRecord<'a> {
+    name: &'a str,
+    entity_type: EntityType,
+    tax_year: u16,
+    gross_income: Money,
+    total_tax: Money,
+    net_income: Money,
+    // Percent with two decimals
+    effective_tax_rate: f64,
+    ruleset_version: &'static str,
+}
+
+pub fn results_to_csv(rows: &[BatchRow]) -> Result<Vec<u8>, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    for row in rows {
+        writer.serialize(ResultRecord {
+            name: &row.name,
+            entity_type: row.input.entity_type,
+            tax_year: row.result.tax_year.year(),
+            gross_income: row.result.gross_income.round(),
+            total_tax: row.result.total_tax.round(),
+            net_income: row.result.net_income.round(),
+            effective_tax_rate: (row.result.effective_tax_rate.percent() * 100.0).round() / 100.0,
+            ruleset_version: NorwegianTaxCalculator::ruleset_version(row.result.tax_year),
+        })?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|err| csv::Error::from(err.into_error()))
+}
@@ -0,0 +1,52 @@
+use crate::money::{Money, Rate};
+
+// Income distribution for residents 17 and over, rounded from SSB's income
+// and wealth statistics for persons. Gross income is wages, business income,
+// capital income and transfers before tax, close to the calculator's
+// gross income for a person.
+pub const BENCHMARK_YEAR: u16 = 2023;
+
+// Upper limit of deciles 1 through 9; decile 10 has no upper limit
+pub const INCOME_DECILE_LIMITS: [Money; 9] = [
+    Money(150_000.0),
+    Money(270_000.0),
+    Money(360_000.0),
+    Money(440_000.0),
+    Money(520_000.0),
+    Money(600_000.0),
+    Money(690_000.0),
+    Money(800_000.0),
+    Money(1_000_000.0),
+];
+
+// Assessed tax over gross income, averaged within each decile
+pub const DECILE_TAX_RATES: [Rate; 10] = [
+    Rate::from_percent(5.0),
+    Rate::from_percent(13.0),
+    Rate::from_percent(17.0),
+    Rate::from_percent(20.0),
+    Rate::from_percent(22.0),
+    Rate::from_percent(23.0),
+    Rate::from_percent(25.0),
+    Rate::from_percent(26.0),
+    Rate::from_percent(28.0),
+    Rate::from_percent(32.0),
+];
+
+// 1 for the lowest tenth, 10 for the highest
+pub fn income_decile(gross_income: Money) -> usize {
+    INCOME_DECILE_LIMITS.iter().filter(|limit| gross_income >= **limit).count() + 1
+}
+
+// Share of the population with a lower income, interpolated within the
+// decile. Capped at 90 in the open top decile.
+pub fn income_percentile(gross_income: Money) -> f64 {
+    let decile = income_decile(gross_income);
+    if decile > INCOME_DECILE_LIMITS.len() {
+        return 90.0;
+    }
+    let lower = if decile == 1 { Money::ZERO } else { INCOME_DECILE_LIMITS[decile - 2] };
+    let upper = INCOME_DECILE_LIMITS[decile - 1];
+    let within = ((gross_income - lower) / (upper - lower)).clamp(0.0, 1.0);
+    (decile - 1) as f64 * 10.0 + within * 10.0
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbObjectStore, IdbRequest, IdbTransactionMode};
+
+use crate::tax_calculator::TaxCalculationInput;
+
+const DATABASE_NAME: &str = "ntc";
+const DATABASE_VERSION: u32 = 1;
+const CLIENT_STORE: &str = "clients";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClientProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub notes: String,
+    pub input: TaxCalculationInput,
+}
+
+impl ClientProfile {
+    pub fn new(name: String, input: TaxCalculationInput) -> Self {
+        Self {
+            id: format!("client-{}", js_sys::Date::now() as u64),
+            name,
+            notes: String::new(),
+            input,
+        }
+    }
+}
+
+// Resolves with the request's result once IndexedDB fires success, or
+// rejects with its error
+async fn request_result(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        let success_request = request.clone();
+        let on_success = Closure::once_into_js(move |_: web_sys::Event| {
+            let result = success_request.result().unwrap_or(JsValue::UNDEFINED);
+            let _ = resolve.call1(&JsValue::NULL, &result);
+        });
+        let error_request = request.clone();
+        let on_error = Closure::once_into_js(move |_: web_sys::Event| {
+            let error = error_request.error().ok().flatten().map(JsValue::from).unwrap_or(JsValue::UNDEFINED);
+            let _ = reject.call1(&JsValue::NULL, &error);
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    JsFuture::from(promise).await
+}
+
+async fn open_database() -> Result<IdbDatabase, JsValue> {
+    let factory = web_sys::window()
+        .ok_or_else(|| JsValue::from_str("no window"))?
+        .indexed_db()?
+        .ok_or_else(|| JsValue::from_str("IndexedDB is unavailable"))?;
+    let request = factory.open_with_u32(DATABASE_NAME, DATABASE_VERSION)?;
+
+    let upgrade_request = request.clone();
+    let on_upgrade = Closure::once_into_js(move |_: web_sys::Event| {
+        if let Ok(database) = upgrade_request.result().and_then(|db| db.dyn_into::<IdbDatabase>()) {
+            if !database.object_store_names().contains(CLIENT_STORE) {
+                let _ = database.create_object_store(CLIENT_STORE);
+            }
+        }
+    });
+    request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+
+    request_result(&request).await?.dyn_into::<IdbDatabase>()
+}
+
+async fn client_store(mode: IdbTransactionMode) -> Result<IdbObjectStore, JsValue> {
+    open_database()
+        .await?
+        .transaction_with_str_and_mode(CLIENT_STORE, mode)?
+        .object_store(CLIENT_STORE)
+}
+
+pub async fn load_clients() -> Result<Vec<ClientProfile>, JsValue> {
+    let store = client_store(IdbTransactionMode::Readonly).await?;
+    let values = request_result(&store.get_all()?).await?;
+    let mut clients = js_sys::Array::from(&values)
+        .iter()
+        .filter_map(|value| value.as_string())
+        .filter_map(|raw| serde_json::from_str::<ClientProfile>(&raw).ok())
+        .collect::<Vec<_>>();
+    clients.sort_by_key(|client| client.name.to_lowercase());
+    Ok(clients)
+}
+
+pub async fn save_client(client: &ClientProfile) -> Result<(), JsValue> {
+    let raw = serde_json::to_string(client).map_err(|err| JsValue::from_str(&err.to_string()))?;
+    let store = client_store(IdbTransactionMode::Readwrite).await?;
+    request_result(&store.put_with_key(&JsValue::from_str(&raw), &JsValue::from_str(&client.id))?).await?;
+    Ok(())
+}
+
+pub async fn delete_client(id: &str) -> Result<(), JsValue> {
+    let store = client_store(IdbTransactionMode::Readwrite).await?;
+    request_result(&store.delete(&JsValue::from_str(id))?).await?;
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::WORKING_DAYS_PER_YEAR;
+use crate::money::Money;
+use crate::tax_calculator::TaxRules;
+
+// Reisefradrag for the daily trip between home and work, and for trips home
+// by pendlere living away from home. The year's travel is counted at a rate
+// per km, and only the part above the bunnbeløp is deducted, up to the cap
+// (skatteloven § 6-44). Pendlere can also deduct lodging and board while
+// away (§ 6-13).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommuteInput {
+    // One way, along the usual route
+    pub distance_km: f64,
+    pub workdays: f64,
+    // Nights spent away from home for work
+    pub nights_away: f64,
+    pub lodging: Money,
+    // Without receipts, lodging is deducted at the year's rate per night
+    pub lodging_documented: bool,
+    // Board is deducted at a lower rate where the lodging has a kitchen
+    pub kitchen: bool,
+    pub home_visits: f64,
+    // One way, between the lodging and home
+    pub home_visit_km: f64,
+}
+
+impl Default for CommuteInput {
+    fn default() -> Self {
+        Self {
+            distance_km: 0.0,
+            workdays: WORKING_DAYS_PER_YEAR,
+            nights_away: 0.0,
+            lodging: Money::ZERO,
+            lodging_documented: true,
+            kitchen: true,
+            home_visits: 0.0,
+            home_visit_km: 0.0,
+        }
+    }
+}
+
+impl CommuteInput {
+    // There and back on each workday and each trip home
+    pub fn yearly_km(&self) -> f64 {
+        2.0 * (self.distance_km.max(0.0) * self.workdays.max(0.0) + self.home_visit_km.max(0.0) * self.home_visits.max(0.0))
+    }
+
+    // The travel expense before the bunnbeløp
+    pub fn travel_expense(&self, rules: &TaxRules) -> Money {
+        rules.commuter_rate_per_km * self.yearly_km()
+    }
+
+    pub fn deduction(&self, rules: &TaxRules) -> Money {
+        (self.travel_expense(rules) - rules.commuter_threshold).clamp(Money::ZERO, rules.commuter_deduction_max)
+    }
+
+    pub fn lodging_deduction(&self, rules: &TaxRules) -> Money {
+        let nights = self.nights_away.max(0.0);
+        if nights == 0.0 {
+            Money::ZERO
+        } else if self.lodging_documented {
+            self.lodging.max(Money::ZERO)
+        } else {
+            rules.commuter_lodging_per_night * nights
+        }
+    }
+
+    pub fn board_deduction(&self, rules: &TaxRules) -> Money {
+        let per_day = if self.kitchen {
+            rules.commuter_board_with_kitchen
+        } else {
+            rules.commuter_board_without_kitchen
+        };
+        per_day * self.nights_away.max(0.0)
+    }
+}
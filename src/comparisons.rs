@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use crate::scenarios::{next_scenario_name, SavedScenario, ScenarioNotes};
+use crate::storage;
+use crate::tax_calculator::{EntityType, TaxCalculationInput};
+
+pub const COMPARISONS_KEY: &str = "ntc.comparisons";
+
+pub const ENTITY_COMPARISONS: [EntityType; 6] = [
+    EntityType::Individual,
+    EntityType::Corporation,
+    EntityType::Partnership,
+    EntityType::SoleProprietorship,
+    EntityType::ForeignBranch,
+    EntityType::Nonprofit,
+];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Comparison {
+    // The current input as another entity type would be taxed
+    Entity(EntityType),
+    // A fixed input that doesn't follow the fields being edited
+    Scenario(Box<SavedScenario>),
+}
+
+impl Comparison {
+    pub fn title(&self) -> String {
+        match self {
+            Comparison::Entity(entity) => entity_title(*entity).to_string(),
+            Comparison::Scenario(scenario) => scenario.name.clone(),
+        }
+    }
+
+    // Stable while a scenario is edited, so its card isn't rebuilt
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Comparison::Entity(entity) => entity_title(*entity),
+            Comparison::Scenario(_) => "scenario",
+        }
+    }
+
+    pub fn input(&self, current: &TaxCalculationInput) -> TaxCalculationInput {
+        match self {
+            Comparison::Entity(entity) => current.comparison_variant(*entity),
+            Comparison::Scenario(scenario) => scenario.input.clone(),
+        }
+    }
+}
+
+pub fn entity_title(entity: EntityType) -> &'static str {
+    match entity {
+        EntityType::Individual => "Person",
+        EntityType::Corporation => "Aksjeselskap (AS)",
+        EntityType::Partnership => "Deltakerlignet selskap",
+        EntityType::SoleProprietorship => "ENK (Enkeltpersonforetak)",
+        EntityType::ForeignBranch => "NUF (Norskregistrert utenlandsk foretak)",
+        EntityType::Nonprofit => "Ideell organisasjon eller stiftelse",
+    }
+}
+
+// NUF and nonprofits are special cases, so their cards are opt-in
+pub fn default_comparisons() -> Vec<Comparison> {
+    ENTITY_COMPARISONS
+        .into_iter()
+        .filter(|entity| !matches!(entity, EntityType::ForeignBranch | EntityType::Nonprofit))
+        .map(Comparison::Entity)
+        .collect()
+}
+
+pub fn load_comparisons() -> Vec<Comparison> {
+    storage::load(COMPARISONS_KEY).unwrap_or_else(default_comparisons)
+}
+
+pub fn save_comparisons(comparisons: &[Comparison]) {
+    storage::save(COMPARISONS_KEY, &comparisons);
+}
+
+// Adds the comparison if it isn't shown, otherwise removes it
+pub fn toggle_comparison(comparisons: &mut Vec<Comparison>, comparison: Comparison) {
+    match comparisons.iter().position(|existing| *existing == comparison) {
+        Some(index) => {
+            comparisons.remove(index);
+        }
+        None => comparisons.push(comparison),
+    }
+}
+
+// Clones the current input into a new scenario card
+pub fn add_scenario(comparisons: &mut Vec<Comparison>, input: &TaxCalculationInput) {
+    let existing = comparisons
+        .iter()
+        .filter_map(|comparison| match comparison {
+            Comparison::Scenario(scenario) => Some(scenario.as_ref().clone()),
+            Comparison::Entity(_) => None,
+        })
+        .collect::<Vec<_>>();
+    comparisons.push(Comparison::Scenario(Box::new(SavedScenario {
+        name: next_scenario_name(&existing),
+        input: input.clone(),
+        notes: ScenarioNotes::default(),
+    })));
+}
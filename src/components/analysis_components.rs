@@ -0,0 +1,1397 @@
+use leptos::*;
+use crate::analysis::{
+    analyze_raise, compare_enk_and_as, compare_with_last_year, compare_years, dual_employment, frikort_status, in_prices_of, income_smoothing, ips_comparison, parental_leave,
+    required_gross_for_net, sensitivity_analysis, spouse_wealth_allocation, household_comparison, unemployment_scenario, wealth_tax_with_municipal_rate, BenefitAssumptions, BenefitYear,
+    DualEmployment, FrikortStatus, HouseholdComparison, HouseholdTransfers, IncomeSmoothing, ParentalCoverage, PensionSavingComparison, RequiredGross, SpouseShare, DAGPENGER_CAP_G, DAGPENGER_RATE,
+    FORELDREPENGER_CAP_G, FRIKORT_EXCESS_WITHHOLDING,
+};
+#[cfg(feature = "vat")]
+use crate::analysis::{required_hourly_rate, MVA_REGISTRATION_THRESHOLD};
+use crate::components::use_currency;
+use crate::currency::CurrencySettings;
+use crate::money::{Money, Rate};
+use crate::municipalities::Municipality;
+use crate::tax_calculator::TaxCalculationInput;
+
+fn format_change(currency: &CurrencySettings, amount: Money) -> String {
+    if amount > Money::ZERO {
+        format!("+{}", currency.format(amount))
+    } else {
+        currency.format(amount)
+    }
+}
+
+#[component]
+pub fn SensitivityTable(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let rows = create_memo(move |_| input.with(sensitivity_analysis));
+    let currency = use_currency();
+
+    view! {
+        <div class="chart-container">
+            <h4>"Sensitivitetsanalyse"</h4>
+            <table class="analysis-table">
+                <thead>
+                    <tr>
+                        <th>"Endring"</th>
+                        <th>"Total skatt"</th>
+                        <th>"Endring i skatt"</th>
+                        <th>"Nettoinntekt"</th>
+                        <th>"Endring i netto"</th>
+                        <th>"Kommuneskatt / fellesskatt"</th>
+                        <th>"Marginalskatt"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || {
+                        let currency = currency.get();
+                        rows.get().into_iter().enumerate().map(|(index, row)| view! {
+                        <tr class:analysis-baseline=index == 0>
+                            <td>{row.label}</td>
+                            <td>{currency.format(row.total_tax)}</td>
+                            <td>{format_change(&currency, row.tax_change)}</td>
+                            <td>{currency.format(row.net_income)}</td>
+                            <td>{format_change(&currency, row.net_income_change)}</td>
+                            <td>
+                                {format!(
+                                    "{} / {}",
+                                    format_change(&currency, row.municipal_tax_change),
+                                    format_change(&currency, row.fellesskatt_change),
+                                )}
+                            </td>
+                            <td>{row.marginal_rate.map(|rate| format!("{:.1}", rate)).unwrap_or_default()}</td>
+                        </tr>
+                        }).collect::<Vec<_>>()
+                    }}
+                </tbody>
+            </table>
+            <p class="chart-note">
+                "Skatten på alminnelig inntekt er den samme uansett kommune. Et prosentpoeng mer i kommuneskatt \
+                 endrer derfor ikke total skatt, men flytter skatt fra staten (fellesskatt) til kommunen. \
+                 Går kommune- og fylkeskatt da over totalsatsen, settes fylkeskatten ned, og resten kommer fra fylket."
+            </p>
+        </div>
+    }
+}
+
+#[component]
+pub fn YearComparisonTable(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let years = create_memo(move |_| input.with(compare_years));
+    let selected_year = move || input.with(|i| i.tax_year);
+    let (inflation_adjusted, set_inflation_adjusted) = create_signal(false);
+    let currency = use_currency();
+
+    view! {
+        <div class="chart-container">
+            <h4>"Sammenligning av skatteår"</h4>
+            <div class="simulation-parameters">
+                <label class="checkbox-label">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || inflation_adjusted.get()
+                        on:change=move |ev| set_inflation_adjusted.set(event_target_checked(&ev))
+                    />
+                    "Juster for prisvekst (KPI)"
+                </label>
+            </div>
+            <table class="analysis-table">
+                <thead>
+                    <tr>
+                        <th>"Regelverk"</th>
+                        <th>"Total skatt"</th>
+                        <th>"Nettoinntekt"</th>
+                        <th>"Effektiv skattesats"</th>
+                        <th>"Endring mot valgt år"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || {
+                        let currency = currency.get();
+                        let years = years.get();
+                        let selected = selected_year();
+                        // In the selected year's kroner when adjusted
+                        let amount = move |amount: Money, tax_year| {
+                            if inflation_adjusted.get() {
+                                in_prices_of(amount, tax_year, selected)
+                            } else {
+                                amount
+                            }
+                        };
+                        let baseline_tax = years.iter()
+                            .find(|(tax_year, _)| *tax_year == selected)
+                            .map(|(_, result)| result.total_tax)
+                            .unwrap_or_default();
+                        years.into_iter().map(|(tax_year, result)| view! {
+                            <tr class:analysis-baseline=tax_year == selected>
+                                <td>{tax_year.year()}</td>
+                                <td>{currency.format(amount(result.total_tax, tax_year))}</td>
+                                <td>{currency.format(amount(result.net_income, tax_year))}</td>
+                                <td>{format!("{:.1}", result.effective_tax_rate)}</td>
+                                <td>{format_change(&currency, amount(result.total_tax, tax_year) - baseline_tax)}</td>
+                            </tr>
+                        }).collect::<Vec<_>>()
+                    }}
+                </tbody>
+            </table>
+            <Show when=move || inflation_adjusted.get()>
+                <p class="chart-note">
+                    {move || format!(
+                        "Beløpene er regnet om til {}-kroner med SSBs konsumprisindeks. Indeksen for 2025 er en prognose.",
+                        selected_year().year()
+                    )}
+                </p>
+            </Show>
+        </div>
+    }
+}
+#[component]
+pub fn LastYearComparisonPanel(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let (assessed, set_assessed) = create_signal(0.0);
+    let comparison = create_memo(move |_| input.with(|i| compare_with_last_year(i, Money(assessed.get()))));
+    let has_assessed = move || assessed.get() > 0.0;
+    let tax_year = move || input.with(|i| i.tax_year.year());
+    let currency = use_currency();
+
+    let explanation = move || {
+        let currency = currency.get();
+        let comparison = comparison.get();
+        let year = tax_year();
+        let rules = match comparison.rule_changes {
+            Some(change) => format!(
+                "Med de samme tallene gir reglene for {} {} i skatt mot reglene for {}.",
+                year,
+                format_change(&currency, change),
+                year - 1
+            ),
+            None => format!("Kalkulatoren har ikke reglene for {}, så endringen kan ikke deles opp.", year - 1),
+        };
+        format!(
+            "{} Resten, {}, kommer av endret inntekt, fradrag og formue, eller av poster i skatteoppgjøret som ikke er \
+             fylt inn her.",
+            rules,
+            format_change(&currency, comparison.other_changes())
+        )
+    };
+
+    view! {
+        <div class="chart-container">
+            <h4>"Sammenligning med fjorårets skatteoppgjør"</h4>
+            <div class="simulation-parameters">
+                <label class="simulation-parameter">
+                    {move || format!("Utlignet skatt for {} (NOK)", tax_year() - 1)}
+                    <input
+                        type="number"
+                        class="input-field"
+                        min="0"
+                        step="1000"
+                        prop:value=move || assessed.get()
+                        on:input=move |ev| {
+                            if let Ok(amount) = event_target_value(&ev).parse::<f64>() {
+                                set_assessed.set(amount.max(0.0));
+                            }
+                        }
+                    />
+                </label>
+            </div>
+            <Show
+                when=has_assessed
+                fallback=|| view! {
+                    <p class="chart-note">"Fyll inn skatten fra fjorårets skatteoppgjør for å se hva som har endret seg."</p>
+                }
+            >
+                <table class="analysis-table">
+                    <tbody>
+                        <tr>
+                            <td>{move || format!("Utlignet skatt for {}", tax_year() - 1)}</td>
+                            <td>{move || currency.get().format(comparison.get().assessed)}</td>
+                        </tr>
+                        <tr>
+                            <td>{move || format!("Beregnet skatt for {}", tax_year())}</td>
+                            <td>{move || currency.get().format(comparison.get().estimate)}</td>
+                        </tr>
+                        <tr>
+                            <td>"Endring"</td>
+                            <td>{move || format_change(&currency.get(), comparison.get().change())}</td>
+                        </tr>
+                        <Show when=move || comparison.get().rule_changes.is_some()>
+                            <tr>
+                                <td>"Av dette endrede regler"</td>
+                                <td>{move || format_change(&currency.get(), comparison.get().rule_changes.unwrap_or_default())}</td>
+                            </tr>
+                        </Show>
+                        <tr>
+                            <td>"Av dette andre endringer"</td>
+                            <td>{move || format_change(&currency.get(), comparison.get().other_changes())}</td>
+                        </tr>
+                    </tbody>
+                </table>
+                <p class="chart-note">{explanation}</p>
+            </Show>
+        </div>
+    }
+}
+
+#[component]
+pub fn EntityBenefitComparison(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let assumptions = create_rw_signal(BenefitAssumptions::default());
+    let comparison = create_memo(move |_| input.with(|input| compare_enk_and_as(input, &assumptions.get())));
+    let currency = use_currency();
+
+    let number_input = move |label: &'static str, step: &'static str, get: fn(&BenefitAssumptions) -> f64, set: fn(&mut BenefitAssumptions, f64)| view! {
+        <label class="simulation-parameter">
+            {label}
+            <input
+                type="number"
+                class="input-field"
+                min="0"
+                step=step
+                prop:value=move || assumptions.with(get)
+                on:input=move |ev| {
+                    if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                        assumptions.update(|a| set(a, value.max(0.0)));
+                    }
+                }
+            />
+        </label>
+    };
+
+    view! {
+        <div class="chart-container">
+            <h4>"ENK eller AS: skatt og rettigheter"</h4>
+            <p class="chart-note">
+                "Sammenligningen forutsetter at du som eier av AS tar ut overskuddet som lønn og dermed har \
+                 rettighetene til en ansatt. Juster antagelsene for å se hva rettighetene er verdt for deg."
+            </p>
+            <div class="simulation-parameters">
+                {number_input("Sykedager per år", "1", |a| a.sick_days, |a, v| a.sick_days = v)}
+                <label class="simulation-parameter">
+                    "Vekt på pensjon (%)"
+                    <input
+                        type="number"
+                        class="input-field"
+                        min="0"
+                        max="100"
+                        step="10"
+                        prop:value=move || assumptions.with(|a| a.pension_weight.percent())
+                        on:input=move |ev| {
+                            if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                                assumptions.update(|a| a.pension_weight = Rate::from_percent(value).clamp(Rate::ZERO, Rate::from_percent(100.0)));
+                            }
+                        }
+                    />
+                </label>
+                {number_input("Verdi av dagpengerett per år", "1000", |a| a.unemployment_value.amount(), |a, v| a.unemployment_value = Money(v))}
+            </div>
+            <table class="analysis-table">
+                <thead>
+                    <tr>
+                        <th>"Post"</th>
+                        <th>"ENK"</th>
+                        <th>"AS"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || {
+                        let currency = currency.get();
+                        let comparison = comparison.get();
+                        let enk_adjusted = comparison.enk_adjusted();
+                        let corporation_adjusted = comparison.corporation_adjusted();
+                        let rows = comparison.adjustments.into_iter().map(|adjustment| view! {
+                            <tr>
+                                <td>{adjustment.label}</td>
+                                <td>{format_change(&currency, adjustment.enk)}</td>
+                                <td>{format_change(&currency, adjustment.corporation)}</td>
+                            </tr>
+                        }).collect::<Vec<_>>();
+                        view! {
+                            <tr>
+                                <td>"Nettoinntekt etter skatt"</td>
+                                <td>{currency.format(comparison.enk.net_income)}</td>
+                                <td>{currency.format(comparison.corporation.net_income)}</td>
+                            </tr>
+                            {rows}
+                            <tr class="analysis-baseline">
+                                <td>"Justert nettoverdi"</td>
+                                <td>{currency.format(enk_adjusted)}</td>
+                                <td>{currency.format(corporation_adjusted)}</td>
+                            </tr>
+                        }
+                    }}
+                </tbody>
+            </table>
+            <p class="chart-note">
+                {move || {
+                    let comparison = comparison.get();
+                    let difference = comparison.enk_adjusted() - comparison.corporation_adjusted();
+                    let currency = currency.get();
+                    if difference.abs() < Money(1.0) {
+                        "ENK og AS kommer likt ut med disse antagelsene.".to_string()
+                    } else if difference > Money::ZERO {
+                        format!("ENK kommer {} bedre ut per år med rettighetene medregnet.", currency.format(difference))
+                    } else {
+                        format!("AS kommer {} bedre ut per år med rettighetene medregnet.", currency.format(-difference))
+                    }
+                }}
+            </p>
+        </div>
+    }
+}
+
+#[component]
+pub fn TargetNetIncomePanel(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let (monthly_net, set_monthly_net) = create_signal(40_000.0);
+    let required = create_memo(move |_| input.with(|input| required_gross_for_net(input, Money(monthly_net.get()))));
+    let currency = use_currency();
+
+    let column = move |title: &'static str, note: &'static str, amount: fn(&RequiredGross) -> Option<Money>| view! {
+        <div class="comparison-card">
+            <h3>{title}</h3>
+            <div class="result-row">
+                <span>"Per år:"</span>
+                <span class="nok">
+                    {move || required.with(amount).map(|gross| currency.get().format(gross)).unwrap_or_else(|| "Uoppnåelig".to_string())}
+                </span>
+            </div>
+            <div class="result-row">
+                <span>"Per måned:"</span>
+                <span>{move || required.with(amount).map(|gross| currency.get().format(gross / 12.0)).unwrap_or_default()}</span>
+            </div>
+            <p class="chart-note">{note}</p>
+        </div>
+    };
+
+    view! {
+        <div class="chart-container">
+            <h4>"Ønsket nettoinntekt"</h4>
+            <div class="simulation-parameters">
+                <label class="simulation-parameter">
+                    "Netto per måned"
+                    <input
+                        type="number"
+                        class="input-field"
+                        min="0"
+                        step="1000"
+                        prop:value=move || monthly_net.get()
+                        on:input=move |ev| {
+                            if let Ok(amount) = event_target_value(&ev).parse::<f64>() {
+                                set_monthly_net.set(amount.max(0.0));
+                            }
+                        }
+                    />
+                </label>
+            </div>
+            <div class="comparison">
+                {column("Bruttolønn", "Som arbeidstaker.", |required| required.salary)}
+                {column("Omsetning i ENK", "Inkludert driftskostnadene over.", |required| required.enk_revenue)}
+                {column("Overskudd i AS", "Utbetalt som utbytte etter selskapsskatt.", |required| required.as_profit)}
+            </div>
+            <p class="chart-note">
+                "Beregnet med dine satser og fradrag, uten investeringsinntekt og formue."
+            </p>
+        </div>
+    }
+}
+
+fn frikort_message(currency: &CurrencySettings, status: &FrikortStatus) -> String {
+    if !status.within_limit() {
+        return format!(
+            "Du har tjent {} over frikortgrensen. Med frikortet trekker arbeidsgiveren {:.0} % av det overskytende, \
+             til sammen {}, til du bytter til vanlig skattekort.",
+            currency.format(status.income - status.limit),
+            FRIKORT_EXCESS_WITHHOLDING.percent(),
+            currency.format(status.withholding),
+        );
+    }
+    if status.remaining_tax_free() > Money::ZERO {
+        let mut message = format!("Du kan tjene {} til skattefritt i år.", currency.format(status.remaining_tax_free()));
+        if status.tax_free_income < status.limit {
+            message.push_str(&format!(
+                " Over det betaler du trygdeavgift ved skatteoppgjøret, men det trekkes ikke skatt før du har tjent {} til.",
+                currency.format(status.remaining()),
+            ));
+        }
+        message
+    } else {
+        format!(
+            "Du er under frikortgrensen og får ikke trukket skatt, men skylder {} som kommer som restskatt. \
+             Du kan tjene {} til før frikortgrensen.",
+            currency.format(status.tax),
+            currency.format(status.remaining()),
+        )
+    }
+}
+
+// A simplified view for students with part-time pay: where the pay is
+// against the frikort limit, and what the skatteoppgjør will bring
+#[component]
+pub fn StudentPanel(input: RwSignal<TaxCalculationInput>) -> impl IntoView {
+    let status = create_memo(move |_| input.with(|input| frikort_status(input, input.gross_income)));
+    let currency = use_currency();
+
+    let row = move |label: &'static str, amount: fn(&FrikortStatus) -> Money| view! {
+        <tr>
+            <td>{label}</td>
+            <td>{move || currency.get().format(status.with(amount))}</td>
+        </tr>
+    };
+
+    view! {
+        <div class="chart-container">
+            <h4>"Student med frikort"</h4>
+            <div class="simulation-parameters">
+                <label class="simulation-parameter">
+                    "Lønn fra deltidsjobb i år"
+                    <input
+                        type="number"
+                        class="input-field"
+                        min="0"
+                        step="1000"
+                        prop:value=move || input.with(|i| i.gross_income.amount())
+                        on:input=move |ev| {
+                            if let Ok(amount) = event_target_value(&ev).parse::<f64>() {
+                                input.update(|i| i.gross_income = Money(amount.max(0.0)));
+                            }
+                        }
+                    />
+                </label>
+            </div>
+            <p class="student-status" role="status">
+                {move || status.with(|status| frikort_message(&currency.get(), status))}
+            </p>
+            <table class="analysis-table">
+                <tbody>
+                    {row("Frikortgrense", |status| status.limit)}
+                    {row("Minstefradrag", |status| status.minstefradrag)}
+                    {row("Trukket i løpet av året", |status| status.withholding)}
+                    {row("Skatt for året", |status| status.tax)}
+                    <tr>
+                        <td>{move || if status.with(FrikortStatus::settlement) > Money::ZERO { "Restskatt" } else { "Til gode" }}</td>
+                        <td>{move || currency.get().format(status.with(FrikortStatus::settlement).abs())}</td>
+                    </tr>
+                </tbody>
+            </table>
+            <p class="chart-note">
+                "Forutsetter at lønnen er eneste inntekt, at du har frikort hele året og at minstefradraget er \
+                 eneste fradrag. Det trekkes automatisk, uavhengig av fradragene over."
+            </p>
+        </div>
+    }
+}
+
+// Withholding with a second employer: tabelltrekk on the main job, whose pay
+// is the gross income above, and prosenttrekk on the other
+#[component]
+pub fn DualEmploymentPanel(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    const INITIAL_SECONDARY_PAY: Money = Money(100_000.0);
+    let (secondary_pay, set_secondary_pay) = create_signal(INITIAL_SECONDARY_PAY.amount());
+    // Skatteetaten sets the rate on the tax card from the expected income,
+    // close to the average rate on both jobs together
+    let initial_rate = input.with_untracked(|i| {
+        let tax = dual_employment(i, i.gross_income, INITIAL_SECONDARY_PAY, Rate::ZERO).tax;
+        Rate::from_fraction(tax / (i.gross_income + INITIAL_SECONDARY_PAY))
+    });
+    let (secondary_rate, set_secondary_rate) = create_signal(Rate::from_percent(initial_rate.percent().ceil()));
+    let analysis = create_memo(move |_| {
+        input.with(|i| {
+            dual_employment(i, i.gross_income, Money(secondary_pay.get()), secondary_rate.get())
+        })
+    });
+    let currency = use_currency();
+
+    let row = move |label: &'static str, amount: fn(&DualEmployment) -> Money| view! {
+        <tr>
+            <td>{label}</td>
+            <td>{move || currency.get().format(analysis.with(amount))}</td>
+        </tr>
+    };
+
+    view! {
+        <div class="chart-container">
+            <h4>"To arbeidsgivere"</h4>
+            <div class="simulation-parameters">
+                <label class="simulation-parameter">
+                    "Lønn fra biarbeidsgiver"
+                    <input
+                        type="number"
+                        class="input-field"
+                        min="0"
+                        step="10000"
+                        prop:value=move || secondary_pay.get()
+                        on:input=move |ev| {
+                            if let Ok(amount) = event_target_value(&ev).parse::<f64>() {
+                                set_secondary_pay.set(amount.max(0.0));
+                            }
+                        }
+                    />
+                </label>
+                <label class="simulation-parameter">
+                    "Prosenttrekk på skattekortet (%)"
+                    <input
+                        type="number"
+                        class="input-field"
+                        min="0"
+                        max="100"
+                        step="1"
+                        prop:value=move || secondary_rate.get().percent()
+                        on:input=move |ev| {
+                            if let Ok(rate) = event_target_value(&ev).parse::<f64>() {
+                                set_secondary_rate.set(Rate::from_percent(rate).clamp(Rate::ZERO, Rate::from_percent(100.0)));
+                            }
+                        }
+                    />
+                </label>
+            </div>
+            <table class="analysis-table">
+                <tbody>
+                    {row("Tabelltrekk hos hovedarbeidsgiver", |analysis| analysis.main_withholding)}
+                    {row("Prosenttrekk hos biarbeidsgiver", |analysis| analysis.secondary_withholding)}
+                    {row("Trukket til sammen", DualEmployment::withholding)}
+                    {row("Skatt for året", |analysis| analysis.tax)}
+                    <tr>
+                        <td>{move || if analysis.with(DualEmployment::settlement) > Money::ZERO { "Restskatt" } else { "Til gode" }}</td>
+                        <td>{move || currency.get().format(analysis.with(DualEmployment::settlement).abs())}</td>
+                    </tr>
+                </tbody>
+            </table>
+            <p class="chart-note">
+                {move || analysis.with(|analysis| {
+                    if analysis.settlement() > Money::ZERO {
+                        format!(
+                            "Trekket dekker ikke skatten, fordi tabelltrekket regner som om hovedjobben er eneste inntekt. \
+                             Med minst {:.0} % prosenttrekk på bijobben unngår du restskatt.",
+                            analysis.required_rate.percent().ceil(),
+                        )
+                    } else {
+                        "Trekket dekker skatten for året, så du kan vente penger til gode eller å gå i null.".to_string()
+                    }
+                })}
+                " Bruttoinntekten over er lønnen fra hovedarbeidsgiveren, og minstefradraget er eneste fradrag."
+            </p>
+        </div>
+    }
+}
+
+type BenefitYearAmount = fn(&BenefitYear) -> Money;
+
+// Rows of a table comparing two years with a benefit, the second against the first
+fn benefit_year_rows(
+    currency: &CurrencySettings,
+    benefit: &'static str,
+    columns: [&'static str; 2],
+    years: [&BenefitYear; 2],
+) -> impl IntoView {
+    let rows: [(&str, BenefitYearAmount); 4] = [
+        ("Lønn", |year| year.salary),
+        (benefit, |year| year.benefit),
+        ("Skatt", |year| year.tax),
+        ("Nettoinntekt", BenefitYear::net_income),
+    ];
+    view! {
+        <thead>
+            <tr>
+                <th></th>
+                <th>{columns[0]}</th>
+                <th>{columns[1]}</th>
+                <th>"Endring"</th>
+            </tr>
+        </thead>
+        <tbody>
+            {rows.into_iter().map(|(label, amount)| view! {
+                <tr>
+                    <td>{label}</td>
+                    <td>{currency.format(amount(years[0]))}</td>
+                    <td>{currency.format(amount(years[1]))}</td>
+                    <td>{format_change(currency, amount(years[1]) - amount(years[0]))}</td>
+                </tr>
+            }).collect::<Vec<_>>()}
+        </tbody>
+    }
+}
+
+// Planning around a job loss: the rest of the year on dagpenger, based on
+// the income before it
+#[component]
+pub fn UnemploymentPanel(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let (months, set_months) = create_signal(6.0);
+    let (prior_income, set_prior_income) = create_signal(input.with_untracked(|i| i.gross_income.amount()));
+    let scenario = create_memo(move |_| {
+        input.with(|i| unemployment_scenario(i, i.gross_income, Money(prior_income.get()), months.get()))
+    });
+    let currency = use_currency();
+
+    view! {
+        <div class="chart-container">
+            <h4>"Dagpenger ved jobbtap"</h4>
+            <div class="simulation-parameters">
+                <label class="simulation-parameter">
+                    "Måneder med dagpenger"
+                    <input
+                        type="number"
+                        class="input-field"
+                        min="0"
+                        max="12"
+                        step="1"
+                        prop:value=move || months.get()
+                        on:input=move |ev| {
+                            if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                                set_months.set(value.clamp(0.0, 12.0));
+                            }
+                        }
+                    />
+                </label>
+                <label class="simulation-parameter">
+                    "Inntekt siste 12 måneder"
+                    <input
+                        type="number"
+                        class="input-field"
+                        min="0"
+                        step="10000"
+                        prop:value=move || prior_income.get()
+                        on:input=move |ev| {
+                            if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                                set_prior_income.set(value.max(0.0));
+                            }
+                        }
+                    />
+                </label>
+            </div>
+            <table class="analysis-table">
+                {move || scenario.with(|scenario| benefit_year_rows(
+                    &currency.get(),
+                    "Dagpenger",
+                    ["Hele året i jobb", "Med dagpenger"],
+                    [&scenario.full_year, &scenario.with_dagpenger],
+                ))}
+            </table>
+            <p class="chart-note">
+                {move || format!(
+                    "Dagpengene er {:.1} % av inntekten før jobbtapet, regnet av inntekt opp til {}G ({}). \
+                     De skattlegges som lønn. Bruttoinntekten over er årslønnen i jobben, og minstefradraget er eneste \
+                     fradrag. Ventetid og eventuell ny jobb er ikke med.",
+                    DAGPENGER_RATE.percent(),
+                    DAGPENGER_CAP_G,
+                    currency.get().format(input.with(|i| i.rules().grunnbeloep.times(DAGPENGER_CAP_G))),
+                )}
+            </p>
+        </div>
+    }
+}
+
+// Foreldrepenger at 100 % against 80 % coverage for the same quota, over
+// the year the leave starts in
+#[component]
+pub fn ParentalLeavePanel(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let (weeks, set_weeks) = create_signal(15.0);
+    let (top_up, set_top_up) = create_signal(false);
+    let leaves = create_memo(move |_| {
+        input.with(|i| ParentalCoverage::ALL.map(|coverage| parental_leave(i, i.gross_income, weeks.get(), top_up.get(), coverage)))
+    });
+    let currency = use_currency();
+
+    view! {
+        <div class="chart-container">
+            <h4>"Foreldrepenger: 100 % eller 80 %"</h4>
+            <div class="simulation-parameters">
+                <label class="simulation-parameter">
+                    "Uker permisjon med 100 % dekning"
+                    <input
+                        type="number"
+                        class="input-field"
+                        min="0"
+                        max="49"
+                        step="1"
+                        prop:value=move || weeks.get()
+                        on:input=move |ev| {
+                            if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                                set_weeks.set(value.clamp(0.0, 49.0));
+                            }
+                        }
+                    />
+                </label>
+                <label class="checkbox-label">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || top_up.get()
+                        on:change=move |ev| set_top_up.set(event_target_checked(&ev))
+                    />
+                    {move || format!("Arbeidsgiver dekker lønn over {}G", FORELDREPENGER_CAP_G)}
+                </label>
+            </div>
+            <table class="analysis-table">
+                {move || leaves.with(|[full, reduced]| benefit_year_rows(
+                    &currency.get(),
+                    "Foreldrepenger",
+                    [full.coverage.label(), reduced.coverage.label()],
+                    [&full.year, &reduced.year],
+                ))}
+            </table>
+            <p class="chart-note">
+                {move || leaves.with(|[full, reduced]| format!(
+                    "Permisjonen varer {:.0} uker med 100 % og {:.0} uker med 80 % dekning i år. NAV dekker lønn opp til \
+                     {}G ({}). Permisjonen regnes fra starten av året, og det som går over årsskiftet er ikke med. \
+                     Bruttoinntekten over er årslønnen, og minstefradraget er eneste fradrag.",
+                    full.weeks,
+                    reduced.weeks,
+                    FORELDREPENGER_CAP_G,
+                    currency.get().format(input.with(|i| i.rules().grunnbeloep.times(FORELDREPENGER_CAP_G))),
+                ))}
+            </p>
+        </div>
+    }
+}
+
+#[component]
+pub fn SalaryNegotiationPanel(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let (current_salary, set_current_salary) = create_signal(input.with_untracked(|i| i.gross_income.amount()));
+    let (offered_salary, set_offered_salary) = create_signal(input.with_untracked(|i| i.gross_income.amount()) + 50_000.0);
+    let analysis = create_memo(move |_| {
+        input.with(|input| analyze_raise(input, Money(current_salary.get()), Money(offered_salary.get())))
+    });
+    let currency = use_currency();
+
+    let salary_input = move |label: &'static str, value: ReadSignal<f64>, set_value: WriteSignal<f64>| view! {
+        <label class="simulation-parameter">
+            {label}
+            <input
+                type="number"
+                class="input-field"
+                min="0"
+                step="10000"
+                prop:value=move || value.get()
+                on:input=move |ev| {
+                    if let Ok(salary) = event_target_value(&ev).parse::<f64>() {
+                        set_value.set(salary.max(0.0));
+                    }
+                }
+            />
+        </label>
+    };
+
+    view! {
+        <div class="chart-container">
+            <h4>"Lønnsforhandling"</h4>
+            <div class="simulation-parameters">
+                {salary_input("Nåværende lønn", current_salary, set_current_salary)}
+                {salary_input("Tilbudt lønn", offered_salary, set_offered_salary)}
+            </div>
+            <table class="analysis-table">
+                <thead>
+                    <tr>
+                        <th></th>
+                        <th>"Nå"</th>
+                        <th>"Tilbud"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || {
+                        let currency = currency.get();
+                        let analysis = analysis.get();
+                        view! {
+                            <tr>
+                                <td>"Netto per måned"</td>
+                                <td>{currency.format(analysis.current.net_income / 12.0)}</td>
+                                <td>{currency.format(analysis.offered.net_income / 12.0)}</td>
+                            </tr>
+                            <tr>
+                                <td>"Skatt per år"</td>
+                                <td>{currency.format(analysis.current.total_tax)}</td>
+                                <td>{currency.format(analysis.offered.total_tax)}</td>
+                            </tr>
+                            <tr>
+                                <td>"Effektiv skattesats"</td>
+                                <td>{format!("{:.1}", analysis.current.effective_tax_rate)}</td>
+                                <td>{format!("{:.1}", analysis.offered.effective_tax_rate)}</td>
+                            </tr>
+                        }
+                    }}
+                </tbody>
+            </table>
+            <p class="chart-note">
+                {move || analysis.with(|analysis| {
+                    let currency = currency.get();
+                    let share = analysis.share_to_tax
+                        .map(|share| format!(" {:.1} % av økningen går til skatt.", share.percent()))
+                        .unwrap_or_default();
+                    format!(
+                        "Endring i netto per måned: {}. Marginalskatt ved tilbudt lønn: {:.1} %.{}",
+                        format_change(&currency, analysis.monthly_net_change),
+                        analysis.marginal_rate.percent(),
+                        share,
+                    )
+                })}
+            </p>
+        </div>
+    }
+}
+
+#[cfg(feature = "vat")]
+#[component]
+pub fn HourlyRatePanel(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let (monthly_net, set_monthly_net) = create_signal(40_000.0);
+    let (billable_hours, set_billable_hours) = create_signal(1_500.0);
+    let (mva_registered, set_mva_registered) = create_signal(true);
+    let hourly_rate = create_memo(move |_| {
+        input.with(|input| required_hourly_rate(input, Money(monthly_net.get()), billable_hours.get(), mva_registered.get()))
+    });
+    let currency = use_currency();
+
+    let number_input = move |label: &'static str, step: &'static str, value: ReadSignal<f64>, set_value: WriteSignal<f64>| view! {
+        <label class="simulation-parameter">
+            {label}
+            <input
+                type="number"
+                class="input-field"
+                min="0"
+                step=step
+                prop:value=move || value.get()
+                on:input=move |ev| {
+                    if let Ok(amount) = event_target_value(&ev).parse::<f64>() {
+                        set_value.set(amount.max(0.0));
+                    }
+                }
+            />
+        </label>
+    };
+
+    view! {
+        <div class="chart-container">
+            <h4>"Timepris"</h4>
+            <div class="simulation-parameters">
+                {number_input("Netto per måned", "1000", monthly_net, set_monthly_net)}
+                {number_input("Fakturerbare timer per år", "50", billable_hours, set_billable_hours)}
+                <label class="checkbox-label">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || mva_registered.get()
+                        on:change=move |ev| set_mva_registered.set(event_target_checked(&ev))
+                    />
+                    "MVA-registrert"
+                </label>
+            </div>
+            {move || {
+                let currency = currency.get();
+                match hourly_rate.get() {
+                    Some(hourly_rate) => view! {
+                        <div class="result-item">
+                            <span class="result-label">"Timepris eks. MVA:"</span>
+                            <span class="result-value">{currency.format(hourly_rate.rate)}</span>
+                        </div>
+                        {hourly_rate.rate_with_mva.map(|rate| view! {
+                            <div class="result-item">
+                                <span class="result-label">"Timepris inkl. MVA:"</span>
+                                <span class="result-value">{currency.format(rate)}</span>
+                            </div>
+                        })}
+                        <div class="result-item">
+                            <span class="result-label">"Omsetning per år:"</span>
+                            <span class="result-value">{currency.format(hourly_rate.revenue)}</span>
+                        </div>
+                        {hourly_rate.must_register.then(|| view! {
+                            <p class="batch-error" role="alert">
+                                {format!(
+                                    "Omsetning over {} gir plikt til å registrere seg i Merverdiavgiftsregisteret.",
+                                    currency.format(MVA_REGISTRATION_THRESHOLD),
+                                )}
+                            </p>
+                        })}
+                    }.into_view(),
+                    None => view! { <p class="chart-note">"Oppgi antall fakturerbare timer."</p> }.into_view(),
+                }
+            }}
+            <p class="chart-note">
+                "Driftskostnadene over regnes inkludert MVA, som en registrert virksomhet får tilbake som inngående MVA."
+            </p>
+        </div>
+    }
+}
+
+#[component]
+pub fn IncomeSmoothingPanel(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let profits = create_rw_signal(vec![Money(400_000.0), Money(1_200_000.0), Money(200_000.0)]);
+    let smoothing = create_memo(move |_| input.with(|input| profits.with(|profits| income_smoothing(input, profits))));
+    let currency = use_currency();
+    let first_year = move || input.with(|i| i.tax_year.year());
+
+    view! {
+        <div class="chart-container">
+            <h4>"Jevn eller ujevn inntekt"</h4>
+            <div class="simulation-parameters">
+                {(0..profits.with_untracked(Vec::len)).map(|index| view! {
+                    <label class="simulation-parameter">
+                        {move || format!("Overskudd {}", first_year() + index as u16)}
+                        <input
+                            type="number"
+                            class="input-field"
+                            min="0"
+                            step="50000"
+                            prop:value=move || profits.with(|profits| profits[index].amount())
+                            on:input=move |ev| {
+                                if let Ok(profit) = event_target_value(&ev).parse::<f64>() {
+                                    profits.update(|profits| profits[index] = Money(profit.max(0.0)));
+                                }
+                            }
+                        />
+                    </label>
+                }).collect::<Vec<_>>()}
+            </div>
+            <table class="analysis-table">
+                <thead>
+                    <tr>
+                        <th>"År"</th>
+                        <th>"Overskudd"</th>
+                        <th>"Skatt"</th>
+                        <th>"Jevnt overskudd"</th>
+                        <th>"Skatt"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || {
+                        let currency = currency.get();
+                        let smoothing = smoothing.get();
+                        let first_year = first_year();
+                        smoothing.years.into_iter().enumerate().map(|(index, year)| view! {
+                            <tr>
+                                <td>{first_year + index as u16}</td>
+                                <td>{currency.format(year.profit)}</td>
+                                <td>{currency.format(year.tax)}</td>
+                                <td>{currency.format(year.even_profit)}</td>
+                                <td>{currency.format(year.even_tax)}</td>
+                            </tr>
+                        }).collect::<Vec<_>>()
+                    }}
+                    <tr class="analysis-baseline">
+                        <td>"Sum"</td>
+                        <td></td>
+                        <td>{move || currency.get().format(smoothing.with(IncomeSmoothing::total_tax))}</td>
+                        <td></td>
+                        <td>{move || currency.get().format(smoothing.with(IncomeSmoothing::even_total_tax))}</td>
+                    </tr>
+                </tbody>
+            </table>
+            <p class="chart-note">
+                {move || {
+                    let saving = smoothing.with(IncomeSmoothing::saving);
+                    format!(
+                        "Med samme samlede overskudd fordelt jevnt over årene blir skatten {} lavere, fordi trinnskatten \
+                         er progressiv. Beregnet med årets regler for alle år.",
+                        currency.get().format(saving),
+                    )
+                }}
+            </p>
+        </div>
+    }
+}
+
+// Wealth tax on the same wealth in two municipalities, for someone
+// considering a move. Picking a municipality fills in its share of the
+// rate, which can then be edited for one that has lowered it.
+#[component]
+pub fn MunicipalWealthTaxComparison(
+    #[prop(into)] input: Signal<TaxCalculationInput>,
+    #[prop(into)] municipalities: Signal<Vec<Municipality>>,
+) -> impl IntoView {
+    let max_rate = move || input.with(|i| i.rules().municipal_wealth_tax_rate);
+    let current = input.with_untracked(|i| i.municipal_wealth_tax_rate.unwrap_or_else(|| i.rules().municipal_wealth_tax_rate));
+    let rates = [create_rw_signal(current), create_rw_signal(current)];
+    let comparison = create_memo(move |_| {
+        input.with(|input| rates.map(|rate| wealth_tax_with_municipal_rate(input, rate.get())))
+    });
+    let currency = use_currency();
+
+    let side = move |label: &'static str, rate: RwSignal<Rate>| view! {
+        <label class="simulation-parameter">
+            {label}
+            <select
+                class="input-field"
+                on:change=move |ev| {
+                    let number = event_target_value(&ev);
+                    let selected = municipalities.with_untracked(|list| {
+                        list.iter().find(|municipality| municipality.number == number).map(|municipality| municipality.wealth_tax_rate)
+                    });
+                    if let Some(selected) = selected {
+                        rate.set(selected.unwrap_or_else(max_rate));
+                    }
+                }
+            >
+                <option value="">"Velg kommune"</option>
+                {move || municipalities.get().into_iter().map(|municipality| view! {
+                    <option value=municipality.number>{municipality.name}</option>
+                }).collect::<Vec<_>>()}
+            </select>
+        </label>
+        <label class="simulation-parameter">
+            "Kommunal sats (%)"
+            <input
+                type="number"
+                class="input-field"
+                min="0"
+                max=move || max_rate().percent()
+                step="0.025"
+                prop:value=move || rate.get().percent()
+                on:input=move |ev| {
+                    if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                        rate.set(Rate::from_percent(value.max(0.0)));
+                    }
+                }
+            />
+        </label>
+    };
+
+    view! {
+        <div class="chart-container">
+            <h4>"Formueskatt ved flytting"</h4>
+            <div class="simulation-parameters">
+                {side("Kommune nå", rates[0])}
+                {side("Flytter til", rates[1])}
+            </div>
+            <table class="analysis-table">
+                <thead>
+                    <tr>
+                        <th></th>
+                        <th>"Kommunal sats"</th>
+                        <th>"Samlet sats"</th>
+                        <th>"Formueskatt"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || {
+                        let currency = currency.get();
+                        ["Kommune nå", "Flytter til"].into_iter().zip(comparison.get()).map(|(label, side)| view! {
+                            <tr>
+                                <td>{label}</td>
+                                <td>{format!("{:.3} %", side.municipal_rate.percent())}</td>
+                                <td>{format!("{:.3} %", side.wealth_tax_rate.percent())}</td>
+                                <td>{currency.format(side.wealth_tax)}</td>
+                            </tr>
+                        }).collect::<Vec<_>>()
+                    }}
+                </tbody>
+            </table>
+            <p class="chart-note">
+                {move || {
+                    let [from, to] = comparison.get();
+                    format!(
+                        "Flyttingen endrer formueskatten med {} per år. Kommunene kan sette sin del av satsen lavere \
+                         enn maksimalsatsen på {:.3} %; statens del er den samme overalt.",
+                        format_change(&currency.get(), to.wealth_tax - from.wealth_tax),
+                        max_rate().percent(),
+                    )
+                }}
+            </p>
+        </div>
+    }
+}
+
+#[component]
+pub fn SpouseWealthAllocationPanel(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let own = [create_rw_signal(input.with_untracked(|i| i.investment_wealth.0)), create_rw_signal(0.0)];
+    let joint_assets = create_rw_signal(0.0);
+    let joint_debt = create_rw_signal(0.0);
+    let second_income = create_rw_signal(0.0);
+    let child_benefit = create_rw_signal(0.0);
+    let cash_for_care = create_rw_signal(0.0);
+    let child_support_received = create_rw_signal(0.0);
+    let child_support_paid = create_rw_signal(0.0);
+    let (married, set_married) = create_signal(true);
+    let allocation = create_memo(move |_| {
+        input.with(|i| {
+            spouse_wealth_allocation(
+                i,
+                own.map(|amount| Money(amount.get())),
+                Money(joint_assets.get()),
+                Money(joint_debt.get()),
+                married.get(),
+            )
+        })
+    });
+    let comparison = create_memo(move |_| {
+        input.with(|i| {
+            household_comparison(
+                i,
+                Money(second_income.get()),
+                own.map(|amount| Money(amount.get())),
+                Money(joint_assets.get()),
+                Money(joint_debt.get()),
+                HouseholdTransfers {
+                    child_benefit: Money(child_benefit.get()),
+                    cash_for_care: Money(cash_for_care.get()),
+                    child_support_received: Money(child_support_received.get()),
+                    child_support_paid: Money(child_support_paid.get()),
+                },
+            )
+        })
+    });
+    let currency = use_currency();
+
+    let amount = move |label: &'static str, value: RwSignal<f64>| view! {
+        <label class="simulation-parameter">
+            {label}
+            <input
+                type="number"
+                class="input-field"
+                min="0"
+                step="10000"
+                prop:value=move || value.get()
+                on:input=move |ev| {
+                    if let Ok(amount) = event_target_value(&ev).parse::<f64>() {
+                        value.set(amount.max(0.0));
+                    }
+                }
+            />
+        </label>
+    };
+    let row = move |label: &'static str, field: fn(&SpouseShare) -> Money| view! {
+        <tr>
+            <td>{label}</td>
+            {move || {
+                let currency = currency.get();
+                allocation.get().recommended.iter().map(|share| view! {
+                    <td>{currency.format(field(share))}</td>
+                }).collect::<Vec<_>>()
+            }}
+        </tr>
+    };
+
+    let household_row = move |label: &'static str, tax: fn(&HouseholdComparison) -> [Money; 2]| view! {
+        <tr>
+            <td>{label}</td>
+            {move || {
+                let currency = currency.get();
+                let tax = comparison.with(tax);
+                tax.iter()
+                    .chain(std::iter::once(&tax.iter().copied().sum()))
+                    .map(|amount| view! { <td>{currency.format(*amount)}</td> })
+                    .collect::<Vec<_>>()
+            }}
+        </tr>
+    };
+    let saving_row = move |label: &'static str, saving: fn(&HouseholdComparison) -> Money| view! {
+        <tr>
+            <td colspan="3">{label}</td>
+            <td>{move || currency.get().format(comparison.with(saving))}</td>
+        </tr>
+    };
+
+    let disposable_row = move |label: &'static str, amount: fn(&HouseholdComparison, bool) -> Money| view! {
+        <tr>
+            <td>{label}</td>
+            <td>{move || currency.get().format(comparison.with(|comparison| amount(comparison, married.get())))}</td>
+        </tr>
+    };
+
+    let explanation = move || {
+        let currency = currency.get();
+        let allocation = allocation.get();
+        if allocation.jointly_assessed {
+            format!(
+                "Ektefeller lignes samlet for formue med dobbelt bunnfradrag, så fordelingen av felles eiendeler og gjeld \
+                 endrer ikke formueskatten. Den samlede formueskatten er {}.",
+                currency.format(allocation.recommended_tax())
+            )
+        } else if allocation.saving() > Money::ZERO {
+            format!(
+                "Fordelingen over gir {} i formueskatt mot {} ved lik fordeling, en besparelse på {} per år. \
+                 Eierandelene og gjeldsansvaret må følge fordelingen i virkeligheten.",
+                currency.format(allocation.recommended_tax()),
+                currency.format(allocation.even_tax()),
+                currency.format(allocation.saving())
+            )
+        } else {
+            format!(
+                "Lik fordeling av felles eiendeler og gjeld gir allerede lavest formueskatt, {}.",
+                currency.format(allocation.recommended_tax())
+            )
+        }
+    };
+
+    view! {
+        <div class="chart-container">
+            <h4>"Fordeling av formue og gjeld mellom ektefeller"</h4>
+            <div class="simulation-parameters">
+                {amount("Formue ektefelle 1 (NOK)", own[0])}
+                {amount("Formue ektefelle 2 (NOK)", own[1])}
+                {amount("Felles eiendeler (NOK)", joint_assets)}
+                {amount("Felles gjeld (NOK)", joint_debt)}
+                {amount("Inntekt ektefelle 2 (NOK)", second_income)}
+                <label class="checkbox-label">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || married.get()
+                        on:change=move |ev| set_married.set(event_target_checked(&ev))
+                    />
+                    "Gift (formuen lignes samlet)"
+                </label>
+            </div>
+            <table class="analysis-table">
+                <thead>
+                    <tr>
+                        <th>"Anbefalt fordeling"</th>
+                        <th>"Ektefelle 1"</th>
+                        <th>"Ektefelle 2"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {row("Formue alene", |share| share.own)}
+                    {row("Andel av felles eiendeler", |share| share.joint_assets)}
+                    {row("Andel av felles gjeld", |share| share.joint_debt)}
+                    {row("Nettoformue", SpouseShare::net_wealth)}
+                    {row("Formueskatt", |share| share.wealth_tax)}
+                </tbody>
+            </table>
+            <p class="chart-note">{explanation}</p>
+            <table class="analysis-table">
+                <thead>
+                    <tr>
+                        <th>"Skatt hver for seg og samlet"</th>
+                        <th>"Ektefelle 1"</th>
+                        <th>"Ektefelle 2"</th>
+                        <th>"Sum"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {household_row("Hver for seg, lik fordeling", HouseholdComparison::standalone_tax)}
+                    {household_row("Samlet ligning", HouseholdComparison::joint_tax)}
+                    {saving_row("Spart ved fordeling av felles formue og gjeld", HouseholdComparison::allocation_saving)}
+                    {saving_row("Spart ved delt bunnfradrag", HouseholdComparison::threshold_saving)}
+                    <tr class="analysis-baseline">
+                        <td colspan="3">"Samlet besparelse"</td>
+                        <td>{move || currency.get().format(comparison.with(HouseholdComparison::saving))}</td>
+                    </tr>
+                </tbody>
+            </table>
+            <p class="chart-note">
+                "Inntekten skattlegges hver for seg uansett. Ektefelle 1 har inntekten fra skjemaet, ektefelle 2 bare lønnen over. \
+                 Hver for seg har hver sitt bunnfradrag for formueskatt; samlet deles det doble bunnfradraget."
+            </p>
+            <div class="simulation-parameters">
+                {amount("Barnetrygd per år (NOK)", child_benefit)}
+                {amount("Kontantstøtte per år (NOK)", cash_for_care)}
+                {amount("Mottatt barnebidrag per år (NOK)", child_support_received)}
+                {amount("Betalt barnebidrag per år (NOK)", child_support_paid)}
+            </div>
+            <table class="analysis-table">
+                <thead>
+                    <tr>
+                        <th>"Disponibel inntekt for husholdningen"</th>
+                        <th>"Per år"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {disposable_row("Bruttoinntekt", |comparison, _| comparison.gross_income.iter().copied().sum())}
+                    {disposable_row("Skatt", |comparison, married| -comparison.total_tax(married))}
+                    {disposable_row("Barnetrygd", |comparison, _| comparison.transfers.child_benefit)}
+                    {disposable_row("Kontantstøtte", |comparison, _| comparison.transfers.cash_for_care)}
+                    {disposable_row("Mottatt barnebidrag", |comparison, _| comparison.transfers.child_support_received)}
+                    {disposable_row("Betalt barnebidrag", |comparison, _| -comparison.transfers.child_support_paid)}
+                    <tr class="analysis-baseline">
+                        <td>"Disponibel inntekt"</td>
+                        <td>{move || currency.get().format(comparison.with(|comparison| comparison.disposable_income(married.get())))}</td>
+                    </tr>
+                </tbody>
+            </table>
+            <p class="chart-note">
+                "Barnetrygd, kontantstøtte og barnebidrag er skattefrie overføringer utenfor skattegrunnlaget: de er ikke \
+                 med i skatten over, og betalt barnebidrag gir ikke fradrag. De legges bare til disponibel inntekt."
+            </p>
+        </div>
+    }
+}
+
+#[component]
+pub fn IpsComparisonPanel(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let yearly = create_rw_signal(input.with_untracked(|i| {
+        if i.ips_contribution > Money::ZERO {
+            i.ips_contribution.amount()
+        } else {
+            i.rules().ips_deduction_max.amount()
+        }
+    }));
+    let years = create_rw_signal(20.0);
+    let annual_return = create_rw_signal(5.0);
+    let withdrawal_rate = create_rw_signal(input.with_untracked(|i| i.rules().ordinary_income_rate.percent()));
+    let comparison = create_memo(move |_| {
+        input.with(|i| {
+            ips_comparison(
+                i,
+                Money(yearly.get()),
+                years.get() as u32,
+                Rate::from_percent(annual_return.get()),
+                Rate::from_percent(withdrawal_rate.get()),
+            )
+        })
+    });
+    let currency = use_currency();
+
+    let parameter = move |label: &'static str, value: RwSignal<f64>, step: &'static str, max: f64| view! {
+        <label class="simulation-parameter">
+            {label}
+            <input
+                type="number"
+                class="input-field"
+                min="0"
+                max=max
+                step=step
+                prop:value=move || value.get()
+                on:input=move |ev| {
+                    if let Ok(number) = event_target_value(&ev).parse::<f64>() {
+                        value.set(number.clamp(0.0, max));
+                    }
+                }
+            />
+        </label>
+    };
+    let row = move |label: &'static str, ips: fn(&PensionSavingComparison) -> Money, fund: fn(&PensionSavingComparison) -> Money| view! {
+        <tr>
+            <td>{label}</td>
+            <td>{move || currency.get().format(comparison.with(ips))}</td>
+            <td>{move || currency.get().format(comparison.with(fund))}</td>
+        </tr>
+    };
+
+    view! {
+        <div class="chart-container">
+            <h4>"IPS eller vanlig fondssparing"</h4>
+            <div class="simulation-parameters">
+                {parameter("Innskudd per år (NOK)", yearly, "1000", 1_000_000.0)}
+                {parameter("År til uttak", years, "1", 50.0)}
+                {parameter("Årlig avkastning (%)", annual_return, "0.5", 20.0)}
+                {parameter("Skattesats ved uttak (%)", withdrawal_rate, "0.5", 50.0)}
+            </div>
+            <table class="analysis-table">
+                <thead>
+                    <tr>
+                        <th></th>
+                        <th>"IPS"</th>
+                        <th>"Fond"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {row("Innbetalt", |c| c.contributions, |c| c.contributions - c.tax_saved)}
+                    {row("Spart skatt nå", |c| c.tax_saved, |_| Money::ZERO)}
+                    {row("Saldo ved uttak", |c| c.ips_balance, |c| c.fund_balance)}
+                    {row("Skatt ved uttak", |c| c.ips_withdrawal_tax, |c| c.fund_gain_tax)}
+                    {row("Netto etter skatt", PensionSavingComparison::ips_net, PensionSavingComparison::fund_net)}
+                </tbody>
+            </table>
+            <p class="chart-note">
+                {move || {
+                    let currency = currency.get();
+                    let comparison = comparison.get();
+                    let cap = input.with(|i| i.rules().ips_deduction_max);
+                    let advantage = comparison.advantage();
+                    let verdict = if advantage >= Money::ZERO {
+                        format!("IPS gir {} mer etter skatt enn fondet.", currency.format(advantage))
+                    } else {
+                        format!("Fondet gir {} mer etter skatt enn IPS.", currency.format(advantage.abs()))
+                    };
+                    format!(
+                        "Fondet kjøpes for det samme som IPS-innskuddet koster etter fradraget. {} Fradraget gjelder \
+                         innskudd opp til {} i året, og pengene kan ikke tas ut før fylte 62 år. Skjermingsfradrag og \
+                         formueskatt er holdt utenfor.",
+                        verdict,
+                        currency.format(cap)
+                    )
+                }}
+            </p>
+        </div>
+    }
+}
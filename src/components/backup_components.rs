@@ -0,0 +1,50 @@
+use leptos::*;
+use crate::backup::{export_backup, parse_backup, restore_backup, BACKUP_FILENAME};
+use crate::download::download_bytes;
+use crate::upload::{read_file_text, selected_file};
+
+#[component]
+pub fn BackupPanel() -> impl IntoView {
+    let (error, set_error) = create_signal(None::<String>);
+
+    let export_all = move |_| {
+        spawn_local(async move {
+            let result = export_backup().await.and_then(|bytes| {
+                download_bytes(BACKUP_FILENAME, "application/json", &bytes).map_err(|err| format!("{:?}", err))
+            });
+            set_error.set(result.err());
+        });
+    };
+
+    let on_file = move |ev: ev::Event| {
+        let Some(file) = selected_file(&ev) else { return };
+
+        spawn_local(async move {
+            let restored = match read_file_text(file).await.and_then(|text| parse_backup(&text)) {
+                Ok(backup) => restore_backup(&backup).await,
+                Err(message) => Err(message),
+            };
+            match restored {
+                // Every panel reads its saved state when it mounts, so a
+                // reload shows the restored data everywhere at once
+                Ok(()) => {
+                    let _ = window().location().reload();
+                }
+                Err(message) => set_error.set(Some(message)),
+            }
+        });
+    };
+
+    view! {
+        <div class="preset-selector backup-panel">
+            <button class="export-button" on:click=export_all>"Eksporter alt"</button>
+            <label for="backup-file">"Importer"</label>
+            <input id="backup-file" type="file" accept=".json,application/json" on:change=on_file />
+            <p class="chart-note">
+                "Scenarier, klienter og innstillinger i én fil, for å flytte dem til en annen nettleser. \
+                 Import erstatter innstillingene og klientene som finnes i filen."
+            </p>
+            {move || error.get().map(|message| view! { <p class="batch-error" role="alert">{message}</p> })}
+        </div>
+    }
+}
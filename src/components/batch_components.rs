@@ -0,0 +1,125 @@
+use leptos::*;
+use crate::batch::{calculate_clients, parse_clients, results_to_csv, sort_rows, BatchRow, BatchSortKey, CLIENT_COLUMNS};
+use crate::components::PopulationStatsPanel;
+use crate::download::download_bytes;
+use crate::upload::{read_file_text, selected_file};
+use crate::tax_calculator::NorwegianTaxCalculator;
+
+#[component]
+pub fn AccountantBatchPanel() -> impl IntoView {
+    let rows = create_rw_signal(Vec::<BatchRow>::new());
+    let (error, set_error) = create_signal(None::<String>);
+    let sort = create_rw_signal((BatchSortKey::Name, true));
+
+    let sorted_rows = create_memo(move |_| {
+        let (key, ascending) = sort.get();
+        let mut sorted = rows.get();
+        sort_rows(&mut sorted, key, ascending);
+        sorted
+    });
+
+    let on_file = move |ev: ev::Event| {
+        let Some(file) = selected_file(&ev) else { return };
+
+        spawn_local(async move {
+            let parsed = read_file_text(file)
+                .await
+                .and_then(|text| parse_clients(&text).map_err(|err| err.to_string()));
+            match parsed {
+                Ok(clients) => {
+                    rows.set(calculate_clients(&clients));
+                    set_error.set(None);
+                }
+                Err(message) => set_error.set(Some(message)),
+            }
+        });
+    };
+
+    let export_csv = move |_| {
+        let result = results_to_csv(&sorted_rows.get_untracked())
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| {
+                download_bytes("klientberegninger.csv", "text/csv", &bytes).map_err(|err| format!("{:?}", err))
+            });
+        if let Err(err) = result {
+            logging::error!("Kunne ikke eksportere CSV: {}", err);
+        }
+    };
+
+    let sort_header = move |label: &'static str, key: BatchSortKey| {
+        let aria_sort = move || match sort.get() {
+            (current, true) if current == key => "ascending",
+            (current, false) if current == key => "descending",
+            _ => "none",
+        };
+        view! {
+            <th aria-sort=aria_sort>
+                <button
+                    class="batch-sort-button"
+                    on:click=move |_| sort.update(|(current, ascending)| {
+                        if *current == key {
+                            *ascending = !*ascending;
+                        } else {
+                            *current = key;
+                            *ascending = true;
+                        }
+                    })
+                >
+                    {label}
+                    {move || match aria_sort() {
+                        "ascending" => " ▲",
+                        "descending" => " ▼",
+                        _ => "",
+                    }}
+                </button>
+            </th>
+        }
+    };
+
+    view! {
+        <div class="chart-container batch-panel">
+            <h4>"Regnskapsfører: massberegning"</h4>
+            <p class="chart-note">
+                "Last opp en CSV-fil med én klient per rad. Kolonner: "
+                {CLIENT_COLUMNS.join(", ")}
+                ". Bare name er påkrevd."
+            </p>
+            <div class="batch-actions">
+                <input type="file" accept=".csv,text/csv" aria-label="Velg CSV-fil med klienter" on:change=on_file />
+                <button
+                    class="export-button"
+                    disabled=move || rows.with(|rows| rows.is_empty())
+                    on:click=export_csv
+                >
+                    "Eksporter resultater (CSV)"
+                </button>
+            </div>
+            {move || error.get().map(|message| view! { <p class="batch-error" role="alert">{message}</p> })}
+            <Show when=move || rows.with(|rows| !rows.is_empty())>
+                <table class="analysis-table">
+                    <thead>
+                        <tr>
+                            {sort_header("Klient", BatchSortKey::Name)}
+                            {sort_header("Bruttoinntekt", BatchSortKey::GrossIncome)}
+                            {sort_header("Total skatt", BatchSortKey::TotalTax)}
+                            {sort_header("Nettoinntekt", BatchSortKey::NetIncome)}
+                            {sort_header("Effektiv sats", BatchSortKey::EffectiveRate)}
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || sorted_rows.get().into_iter().map(|row| view! {
+                            <tr>
+                                <td>{row.name}</td>
+                                <td>{NorwegianTaxCalculator::format_nok(row.result.gross_income)}</td>
+                                <td>{NorwegianTaxCalculator::format_nok(row.result.total_tax)}</td>
+                                <td>{NorwegianTaxCalculator::format_nok(row.result.net_income)}</td>
+                                <td>{format!("{:.1}", row.result.effective_tax_rate)}</td>
+                            </tr>
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+                <PopulationStatsPanel rows=rows />
+            </Show>
+        </div>
+    }
+}
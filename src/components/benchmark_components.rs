@@ -0,0 +1,102 @@
+use leptos::*;
+use crate::benchmarks::{income_decile, income_percentile, BENCHMARK_YEAR, DECILE_TAX_RATES};
+use crate::money::Rate;
+use crate::tax_calculator::TaxCalculationResult;
+
+const CHART_WIDTH: f64 = 640.0;
+const CHART_HEIGHT: f64 = 200.0;
+const AXIS_HEIGHT: f64 = 24.0;
+
+// Where the user's income and tax rate fall among Norwegian residents. Closed
+// by default since it compares against statistics, not the user's own case.
+#[component]
+pub fn IncomeBenchmarkPanel(result: Memo<TaxCalculationResult>) -> impl IntoView {
+    let gross_income = Signal::derive(move || result.with(|result| result.gross_income));
+    let effective_rate = Signal::derive(move || result.with(|result| result.effective_tax_rate));
+    let decile = Signal::derive(move || income_decile(gross_income.get()));
+
+    let max_rate = DECILE_TAX_RATES.iter().copied().fold(Rate::ZERO, Rate::max);
+    let scale = move || max_rate.max(effective_rate.get()) * 1.15;
+    let plot_height = CHART_HEIGHT - AXIS_HEIGHT;
+    let bar_width = CHART_WIDTH / DECILE_TAX_RATES.len() as f64;
+
+    let summary = move || {
+        let decile = decile.get();
+        let benchmark = DECILE_TAX_RATES[decile - 1];
+        // In percentage points
+        let difference = (effective_rate.get() - benchmark).percent();
+        let comparison = if difference.abs() < 0.5 {
+            "omtrent som snittet".to_string()
+        } else if difference > 0.0 {
+            format!("{:.1} prosentpoeng over snittet", difference)
+        } else {
+            format!("{:.1} prosentpoeng under snittet", -difference)
+        };
+        format!(
+            "Inntekten din er høyere enn for omtrent {:.0} % av befolkningen, i desil {}. Skattesatsen din \
+             på {:.1} % er {} på {:.0} % i denne desilen.",
+            income_percentile(gross_income.get()),
+            decile,
+            effective_rate.get().percent(),
+            comparison,
+            benchmark.percent(),
+        )
+    };
+
+    view! {
+        <details class="chart-container benchmark-panel">
+            <summary>"Hvordan ligger du an?"</summary>
+            <p>{summary}</p>
+            {move || {
+                let scale = scale();
+                let y = move |rate: Rate| plot_height - rate / scale * plot_height;
+                let current = decile.get();
+                view! {
+                    <svg
+                        class="simulation-chart"
+                        viewBox=format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT)
+                        role="img"
+                        aria-label="Gjennomsnittlig skattesats per inntektsdesil med din skattesats"
+                    >
+                        {DECILE_TAX_RATES.iter().enumerate().map(|(index, rate)| {
+                            let x = index as f64 * bar_width;
+                            view! {
+                                <g>
+                                    <rect
+                                        class="benchmark-bar"
+                                        class:benchmark-bar-active=index + 1 == current
+                                        x=x + 4.0
+                                        y=y(*rate)
+                                        width=bar_width - 8.0
+                                        height=plot_height - y(*rate)
+                                    />
+                                    <text class="simulation-axis" x=x + bar_width / 2.0 y=y(*rate) - 4.0 text-anchor="middle">
+                                        {format!("{:.0}", rate)}
+                                    </text>
+                                    <text class="simulation-axis" x=x + bar_width / 2.0 y=CHART_HEIGHT - 6.0 text-anchor="middle">
+                                        {index + 1}
+                                    </text>
+                                </g>
+                            }
+                        }).collect::<Vec<_>>()}
+                        <line
+                            class="bracket-marker"
+                            x1="0"
+                            x2=CHART_WIDTH
+                            y1=y(effective_rate.get())
+                            y2=y(effective_rate.get())
+                        />
+                    </svg>
+                }
+            }}
+            <p class="chart-note">
+                {format!(
+                    "Søylene er gjennomsnittlig skatt i prosent av bruttoinntekt per desil, og linjen er din \
+                     effektive skattesats. Avrundede tall fra SSBs inntektsstatistikk for personer 17 år og eldre, \
+                     {}. Bruttoinntekt hos SSB omfatter også kapitalinntekter og overføringer.",
+                    BENCHMARK_YEAR,
+                )}
+            </p>
+        </details>
+    }
+}
@@ -0,0 +1,240 @@
+use std::borrow::Cow;
+
+use leptos::*;
+use crate::components::use_currency;
+use crate::money::Money;
+use crate::tax_calculator::{BreakdownCategory, NorwegianTaxCalculator, TaxCalculationResult, TaxYear};
+
+const CHART_WIDTH: f64 = 640.0;
+const LABEL_WIDTH: f64 = 230.0;
+const VALUE_WIDTH: f64 = 110.0;
+const ROW_HEIGHT: f64 = 26.0;
+const BAR_HEIGHT: f64 = 16.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WaterfallKind {
+    Total,
+    Tax,
+    Deduction,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WaterfallStep {
+    pub label: Cow<'static, str>,
+    pub start: Money,
+    pub end: Money,
+    pub kind: WaterfallKind,
+}
+
+// Taxes step the running total down towards net income. Deductions only
+// shrink the tax base, so they are drawn beside the running total without
+// moving it.
+pub fn waterfall_steps(result: &TaxCalculationResult) -> Vec<WaterfallStep> {
+    let mut steps = vec![WaterfallStep {
+        label: "Bruttoinntekt".into(),
+        start: Money::ZERO,
+        end: result.gross_income,
+        kind: WaterfallKind::Total,
+    }];
+
+    let mut running = result.gross_income;
+    for item in result.breakdown.iter().filter(|item| item.category != BreakdownCategory::Info) {
+        if item.amount > Money::ZERO {
+            steps.push(WaterfallStep {
+                label: item.description.clone(),
+                start: running,
+                end: running - item.amount,
+                kind: WaterfallKind::Tax,
+            });
+            running -= item.amount;
+        } else if item.amount < Money::ZERO {
+            steps.push(WaterfallStep {
+                label: item.description.clone(),
+                start: running,
+                end: running + item.amount,
+                kind: WaterfallKind::Deduction,
+            });
+        }
+    }
+
+    steps.push(WaterfallStep {
+        label: "Nettoinntekt".into(),
+        start: Money::ZERO,
+        end: result.net_income,
+        kind: WaterfallKind::Total,
+    });
+
+    steps
+}
+
+#[component]
+pub fn WaterfallChart(result: Memo<TaxCalculationResult>) -> impl IntoView {
+    let currency = use_currency();
+
+    view! {
+        <div class="chart-container">
+            <h4>"Fra brutto til netto"</h4>
+            {move || {
+                let currency = currency.get();
+                let steps = waterfall_steps(&result.get());
+                let max_value = steps.iter()
+                    .flat_map(|step| [step.start, step.end])
+                    .fold(Money(1.0), Money::max);
+                let min_value = steps.iter()
+                    .flat_map(|step| [step.start, step.end])
+                    .fold(Money::ZERO, Money::min);
+                let plot_width = CHART_WIDTH - LABEL_WIDTH - VALUE_WIDTH;
+                let scale = plot_width / (max_value - min_value).amount();
+                let x = move |value: Money| LABEL_WIDTH + (value - min_value).amount() * scale;
+                let height = steps.len() as f64 * ROW_HEIGHT;
+
+                view! {
+                    <svg
+                        class="waterfall-chart"
+                        viewBox=format!("0 0 {} {}", CHART_WIDTH, height)
+                        role="img"
+                        aria-label="Fossefallsdiagram fra bruttoinntekt til nettoinntekt"
+                    >
+                        {steps.into_iter().enumerate().map(|(index, step)| {
+                            let y = index as f64 * ROW_HEIGHT;
+                            let left = x(step.start.min(step.end));
+                            let width = (x(step.start.max(step.end)) - left).max(1.0);
+                            let class = match step.kind {
+                                WaterfallKind::Total => "waterfall-bar waterfall-total",
+                                WaterfallKind::Tax => "waterfall-bar waterfall-tax",
+                                WaterfallKind::Deduction => "waterfall-bar waterfall-deduction",
+                            };
+                            let amount = match step.kind {
+                                WaterfallKind::Total => step.end,
+                                _ => step.end - step.start,
+                            };
+
+                            view! {
+                                <g>
+                                    <text class="waterfall-label" x="0" y=y + ROW_HEIGHT / 2.0 + 4.0>
+                                        {step.label}
+                                    </text>
+                                    <rect
+                                        class=class
+                                        x=left
+                                        y=y + (ROW_HEIGHT - BAR_HEIGHT) / 2.0
+                                        width=width
+                                        height=BAR_HEIGHT
+                                        rx="2"
+                                    />
+                                    <text
+                                        class="waterfall-value"
+                                        x=CHART_WIDTH
+                                        y=y + ROW_HEIGHT / 2.0 + 4.0
+                                        text-anchor="end"
+                                    >
+                                        {currency.format(amount)}
+                                    </text>
+                                </g>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </svg>
+                }
+            }}
+            <p class="chart-note">"Fradrag reduserer skattegrunnlaget og vises ved siden av løpende saldo."</p>
+        </div>
+    }
+}
+
+#[component]
+pub fn BracketVisualizer(
+    #[prop(into)] personal_income: Signal<Money>,
+    #[prop(into)] tax_year: Signal<TaxYear>,
+) -> impl IntoView {
+    view! {
+        <div class="chart-container">
+            <h4>"Trinnskatt"</h4>
+            {move || {
+                let income = personal_income.get();
+                let brackets = NorwegianTaxCalculator::state_tax_brackets(tax_year.get(), income);
+                let last_threshold = brackets.last().map(|bracket| bracket.threshold).unwrap_or(Money::ZERO);
+                let scale_max = (income * 1.1).max(last_threshold * 1.15);
+                let plot_width = CHART_WIDTH;
+                let x = move |value: Money| (value / scale_max * plot_width).min(plot_width);
+                let first_threshold = brackets.first().map(|bracket| bracket.threshold).unwrap_or(Money::ZERO);
+
+                let marginal = brackets.iter().rev().find(|bracket| income >= bracket.threshold);
+                let marginal_text = match marginal {
+                    Some(bracket) => format!(
+                        "Neste krone havner i trinn {} ({:.1}).",
+                        bracket.step,
+                        bracket.rate,
+                    ),
+                    None => format!(
+                        "Neste krone gir ikke trinnskatt før personinntekten passerer {}.",
+                        NorwegianTaxCalculator::format_nok(first_threshold),
+                    ),
+                };
+
+                view! {
+                    <svg
+                        class="bracket-chart"
+                        viewBox=format!("0 0 {} 70", CHART_WIDTH)
+                        role="img"
+                        aria-label="Trinnskatt fordelt på trinn med markør for personinntekt"
+                    >
+                        <rect class="bracket-free" x="0" y="10" width=x(first_threshold) height="28" />
+                        {brackets.iter().map(|bracket| {
+                            let left = x(bracket.threshold);
+                            let right = x(bracket.upper.unwrap_or(scale_max));
+                            let filled = x(bracket.threshold + bracket.income_in_bracket);
+                            view! {
+                                <g>
+                                    <rect
+                                        class=format!("bracket-step bracket-step-{}", bracket.step)
+                                        x=left
+                                        y="10"
+                                        width=(right - left).max(0.0)
+                                        height="28"
+                                    />
+                                    <rect
+                                        class="bracket-filled"
+                                        x=left
+                                        y="10"
+                                        width=(filled - left).max(0.0)
+                                        height="28"
+                                    />
+                                    <text class="bracket-label" x=left + 4.0 y="29">
+                                        {format!("{:.1}", bracket.rate)}
+                                    </text>
+                                </g>
+                            }
+                        }).collect::<Vec<_>>()}
+                        <line class="bracket-marker" x1=x(income) x2=x(income) y1="4" y2="44" />
+                        <text class="bracket-marker-label" x=x(income) y="60" text-anchor="middle">
+                            {NorwegianTaxCalculator::format_nok(income)}
+                        </text>
+                    </svg>
+                    <table class="bracket-table">
+                        <thead>
+                            <tr>
+                                <th>"Trinn"</th>
+                                <th>"Innslagspunkt"</th>
+                                <th>"Sats"</th>
+                                <th>"Inntekt i trinnet"</th>
+                                <th>"Skatt"</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {brackets.iter().map(|bracket| view! {
+                                <tr class:bracket-row-active=marginal.map(|m| m.step) == Some(bracket.step)>
+                                    <td>{bracket.step}</td>
+                                    <td>{NorwegianTaxCalculator::format_nok(bracket.threshold)}</td>
+                                    <td>{format!("{:.1}", bracket.rate)}</td>
+                                    <td>{NorwegianTaxCalculator::format_nok(bracket.income_in_bracket)}</td>
+                                    <td>{NorwegianTaxCalculator::format_nok(bracket.tax)}</td>
+                                </tr>
+                            }).collect::<Vec<_>>()}
+                        </tbody>
+                    </table>
+                    <p class="chart-note">{marginal_text}</p>
+                }
+            }}
+        </div>
+    }
+}
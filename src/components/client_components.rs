@@ -0,0 +1,244 @@
+use leptos::*;
+use crate::clients::{delete_client, load_clients, save_client, ClientProfile};
+use crate::letters::{letter_values, render_template, DEFAULT_TEMPLATE, PLACEHOLDERS};
+use crate::storage;
+use crate::tax_calculator::{NorwegianTaxCalculator, TaxCalculationInput};
+
+fn persist(client: ClientProfile) {
+    spawn_local(async move {
+        if let Err(err) = save_client(&client).await {
+            logging::error!("Kunne ikke lagre klient: {:?}", err);
+        }
+    });
+}
+
+fn today() -> String {
+    let date = js_sys::Date::new_0();
+    format!("{:02}.{:02}.{}", date.get_date(), date.get_month() + 1, date.get_full_year())
+}
+
+// Only the letter is printed while the attribute is set, see the print
+// styles. window.print blocks until the dialog closes.
+fn print_letter() {
+    let Some(body) = document().body() else { return };
+    let _ = body.set_attribute("data-printing", "letter");
+    let _ = window().print();
+    let _ = body.remove_attribute("data-printing");
+}
+
+// A letter to the client from an editable template. The template is kept
+// between sessions, and the filled-in letter can be edited before printing.
+#[component]
+fn ClientLetter(#[prop(into)] client_name: Signal<String>, input: RwSignal<TaxCalculationInput>) -> impl IntoView {
+    let template = create_rw_signal(
+        storage::load::<String>(storage::LETTER_TEMPLATE_KEY).unwrap_or_else(|| DEFAULT_TEMPLATE.to_string()),
+    );
+    create_effect(move |_| storage::save(storage::LETTER_TEMPLATE_KEY, &template.get()));
+    let (letter, set_letter) = create_signal(String::new());
+
+    let fill_in = move |_| {
+        let letter = input.with_untracked(|input| {
+            let result = NorwegianTaxCalculator::calculate_tax(input);
+            let values = letter_values(&client_name.get_untracked(), &today(), input, &result);
+            render_template(&template.get_untracked(), &values)
+        });
+        set_letter.set(letter);
+    };
+    let placeholders = PLACEHOLDERS
+        .iter()
+        .map(|(name, label)| format!("{{{}}} ({})", name, label.to_lowercase()))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    view! {
+        <details class="client-letter">
+            <summary>"Brev til klienten"</summary>
+            <textarea
+                class="input-field client-letter-template"
+                aria-label="Brevmal"
+                prop:value=move || template.get()
+                on:change=move |ev| template.set(event_target_value(&ev))
+            />
+            <p class="chart-note">{format!("Flettefelt: {}", placeholders)}</p>
+            <div class="client-actions">
+                <button class="history-button" on:click=fill_in>"Lag brev"</button>
+                <button class="section-reset-button" on:click=move |_| template.set(DEFAULT_TEMPLATE.to_string())>
+                    "Tilbakestill malen"
+                </button>
+            </div>
+            <Show when=move || !letter.with(String::is_empty)>
+                <textarea
+                    class="input-field client-letter-text"
+                    aria-label="Brev til klienten"
+                    prop:value=move || letter.get()
+                    on:input=move |ev| set_letter.set(event_target_value(&ev))
+                />
+                <div class="client-actions">
+                    <button class="history-button" on:click=move |_| print_letter()>"Skriv ut"</button>
+                </div>
+                <div class="client-letter-print">{move || letter.get()}</div>
+            </Show>
+        </details>
+    }
+}
+
+#[component]
+pub fn ClientManager(input: RwSignal<TaxCalculationInput>) -> impl IntoView {
+    let clients = create_rw_signal(Vec::<ClientProfile>::new());
+    let active_id = create_rw_signal(None::<String>);
+    let (new_name, set_new_name) = create_signal(String::new());
+
+    spawn_local(async move {
+        match load_clients().await {
+            Ok(loaded) => clients.set(loaded),
+            Err(err) => logging::error!("Kunne ikke hente klienter: {:?}", err),
+        }
+    });
+
+    let active_client = move || {
+        let id = active_id.get()?;
+        clients.with(|list| list.iter().find(|client| client.id == id).cloned())
+    };
+
+    let update_active = move |update: &dyn Fn(&mut ClientProfile)| {
+        let Some(id) = active_id.get_untracked() else { return };
+        clients.update(|list| {
+            if let Some(client) = list.iter_mut().find(|client| client.id == id) {
+                update(client);
+                persist(client.clone());
+            }
+        });
+    };
+
+    let add_client = move |_| {
+        let name = new_name.get_untracked().trim().to_string();
+        let name = if name.is_empty() {
+            format!("Klient {}", clients.with_untracked(|list| list.len()) + 1)
+        } else {
+            name
+        };
+        let client = ClientProfile::new(name, input.get_untracked());
+        active_id.set(Some(client.id.clone()));
+        persist(client.clone());
+        clients.update(|list| list.push(client));
+        set_new_name.set(String::new());
+    };
+
+    let select_client = move |client: ClientProfile| {
+        active_id.set(Some(client.id));
+        input.set(client.input);
+    };
+
+    let remove_active = move |_| {
+        let Some(id) = active_id.get_untracked() else { return };
+        clients.update(|list| list.retain(|client| client.id != id));
+        active_id.set(None);
+        spawn_local(async move {
+            if let Err(err) = delete_client(&id).await {
+                logging::error!("Kunne ikke slette klient: {:?}", err);
+            }
+        });
+    };
+
+    let summaries = create_memo(move |_| {
+        clients.with(|list| {
+            let inputs = list.iter().map(|client| client.input.clone()).collect::<Vec<_>>();
+            list.iter()
+                .map(|client| (client.id.clone(), client.name.clone()))
+                .zip(NorwegianTaxCalculator::calculate_many(&inputs))
+                .collect::<Vec<_>>()
+        })
+    });
+
+    view! {
+        <div class="chart-container client-manager">
+            <aside class="client-sidebar" aria-label="Klienter">
+                <h4>"Klienter"</h4>
+                <ul class="client-list">
+                    {move || clients.get().into_iter().map(|client| {
+                        let id = client.id.clone();
+                        let name = client.name.clone();
+                        view! {
+                            <li>
+                                <button
+                                    class="client-list-item"
+                                    class:client-list-item-active=move || active_id.get().as_deref() == Some(id.as_str())
+                                    on:click=move |_| select_client(client.clone())
+                                >
+                                    {name}
+                                </button>
+                            </li>
+                        }
+                    }).collect::<Vec<_>>()}
+                </ul>
+                <div class="client-new">
+                    <input
+                        type="text"
+                        class="input-field"
+                        placeholder="Navn på klient"
+                        aria-label="Navn på ny klient"
+                        prop:value=move || new_name.get()
+                        on:input=move |ev| set_new_name.set(event_target_value(&ev))
+                    />
+                    <button class="history-button" on:click=add_client>"Ny klient"</button>
+                </div>
+            </aside>
+
+            <div class="client-dashboard">
+                {move || active_client().map(|client| view! {
+                    <div class="client-details">
+                        <h4>{client.name.clone()}</h4>
+                        <textarea
+                            class="input-field client-notes"
+                            placeholder="Notater"
+                            aria-label="Notater om klienten"
+                            prop:value=client.notes.clone()
+                            on:change=move |ev| {
+                                let notes = event_target_value(&ev);
+                                update_active(&|client| client.notes = notes.clone());
+                            }
+                        />
+                        <div class="client-actions">
+                            <button
+                                class="history-button"
+                                title="Lagre gjeldende inndata på klienten"
+                                on:click=move |_| update_active(&|client| client.input = input.get_untracked())
+                            >
+                                "Lagre inndata"
+                            </button>
+                            <button class="section-reset-button" on:click=remove_active>"Slett klient"</button>
+                        </div>
+                    </div>
+                })}
+
+                <Show when=move || active_id.with(Option::is_some)>
+                    <ClientLetter
+                        client_name=Signal::derive(move || active_client().map(|client| client.name).unwrap_or_default())
+                        input=input
+                    />
+                </Show>
+
+                <table class="analysis-table">
+                    <thead>
+                        <tr>
+                            <th>"Klient"</th>
+                            <th>"Total skatt"</th>
+                            <th>"Nettoinntekt"</th>
+                            <th>"Effektiv sats"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || summaries.get().into_iter().map(|((id, name), result)| view! {
+                            <tr class:analysis-baseline=move || active_id.get().as_deref() == Some(id.as_str())>
+                                <td>{name}</td>
+                                <td>{NorwegianTaxCalculator::format_nok(result.total_tax)}</td>
+                                <td>{NorwegianTaxCalculator::format_nok(result.net_income)}</td>
+                                <td>{format!("{:.1}", result.effective_tax_rate)}</td>
+                            </tr>
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,97 @@
+use leptos::*;
+use crate::currency::{CurrencySettings, DisplayCurrency};
+#[cfg(feature = "integrations")]
+use crate::currency::fetch_nok_rate;
+
+// Result components read the display currency from context so it doesn't
+// have to be threaded through every prop list
+pub fn use_currency() -> Signal<CurrencySettings> {
+    use_context::<RwSignal<CurrencySettings>>()
+        .map(Signal::from)
+        .unwrap_or_else(|| Signal::derive(CurrencySettings::default))
+}
+
+#[component]
+pub fn CurrencySelector(settings: RwSignal<CurrencySettings>) -> impl IntoView {
+    let is_nok = move || settings.with(|s| s.currency == DisplayCurrency::Nok);
+
+    // Without the integrations feature the rate can only be typed in
+    #[cfg(feature = "integrations")]
+    let fetch_rate = {
+        let (fetching, set_fetching) = create_signal(false);
+        let (fetch_error, set_fetch_error) = create_signal(false);
+        let fetch = move |_| {
+            let currency = settings.get_untracked().currency;
+            set_fetching.set(true);
+            spawn_local(async move {
+                match fetch_nok_rate(currency).await {
+                    Ok(rate) => {
+                        settings.update(|s| s.rate = rate);
+                        set_fetch_error.set(false);
+                    }
+                    Err(err) => {
+                        logging::warn!("Kunne ikke hente valutakurs: {:?}", err);
+                        set_fetch_error.set(true);
+                    }
+                }
+                set_fetching.set(false);
+            });
+        };
+        move || view! {
+            <button class="history-button" disabled=move || fetching.get() on:click=fetch>
+                {move || if fetching.get() { "Henter..." } else { "Hent kurs" }}
+            </button>
+            {move || fetch_error.get().then(|| view! {
+                <span class="batch-error" role="alert">"Kunne ikke hente kurs fra Norges Bank"</span>
+            })}
+        }
+    };
+    #[cfg(not(feature = "integrations"))]
+    let fetch_rate = || ();
+
+    view! {
+        <div class="currency-selector">
+            <label for="currency-select">"Vis beløp i"</label>
+            <select
+                id="currency-select"
+                class="input-field"
+                on:change=move |ev| {
+                    if let Some(currency) = DisplayCurrency::from_code(&event_target_value(&ev)) {
+                        settings.set(CurrencySettings {
+                            currency,
+                            rate: currency.default_rate(),
+                        });
+                    }
+                }
+            >
+                {DisplayCurrency::ALL.into_iter().map(|currency| view! {
+                    <option
+                        value=currency.code()
+                        selected=move || settings.with(|s| s.currency == currency)
+                    >
+                        {currency.code()}
+                    </option>
+                }).collect::<Vec<_>>()}
+            </select>
+            <Show when=move || !is_nok()>
+                <label for="currency-rate">{move || format!("NOK per {}", settings.with(|s| s.currency.code()))}</label>
+                <input
+                    id="currency-rate"
+                    type="number"
+                    class="input-field rate-field"
+                    step="0.01"
+                    min="0"
+                    prop:value=move || settings.with(|s| s.rate)
+                    on:input=move |ev| {
+                        if let Ok(rate) = event_target_value(&ev).parse::<f64>() {
+                            if rate > 0.0 {
+                                settings.update(|s| s.rate = rate);
+                            }
+                        }
+                    }
+                />
+                {fetch_rate}
+            </Show>
+        </div>
+    }
+}
@@ -0,0 +1,127 @@
+use leptos::*;
+use crate::custom_taxes::{CustomTax, CustomTaxBase, CustomTaxKind};
+use crate::money::{Money, Rate};
+use crate::tax_calculator::TaxCalculationInput;
+
+// The number in the amount field: NOK for a fixed amount, percent for a rate
+fn entered(kind: CustomTaxKind) -> f64 {
+    match kind {
+        CustomTaxKind::Fixed(amount) => amount.amount(),
+        CustomTaxKind::Rate(rate) => rate.percent(),
+    }
+}
+
+fn with_entered(kind: CustomTaxKind, number: f64) -> CustomTaxKind {
+    match kind {
+        CustomTaxKind::Fixed(_) => CustomTaxKind::Fixed(Money(number)),
+        CustomTaxKind::Rate(_) => CustomTaxKind::Rate(Rate::from_percent(number)),
+    }
+}
+
+// Each row reads and writes its own entry, and rows are only rebuilt when
+// the count changes, so typing doesn't lose focus
+#[component]
+pub fn CustomTaxEditor(input: RwSignal<TaxCalculationInput>) -> impl IntoView {
+    let count = create_memo(move |_| input.with(|input| input.custom_taxes.len()));
+    let read = move |index: usize| input.with(|input| input.custom_taxes.get(index).cloned().unwrap_or_default());
+    let update = move |index: usize, edit: &dyn Fn(&mut CustomTax)| {
+        input.update(|input| {
+            if let Some(tax) = input.custom_taxes.get_mut(index) {
+                edit(tax);
+            }
+        });
+    };
+
+    let row = move |index: usize| view! {
+        <div class="custom-tax-row">
+            <input
+                type="text"
+                class="input-field"
+                placeholder="Navn"
+                aria-label="Navn på posten"
+                prop:value=move || read(index).name
+                on:input=move |ev| {
+                    let name = event_target_value(&ev);
+                    update(index, &|tax| tax.name = name.clone());
+                }
+            />
+            <select
+                class="input-field"
+                aria-label="Type"
+                on:change=move |ev| {
+                    let position = event_target_value(&ev).parse::<usize>().unwrap_or(0);
+                    if let Some(kind) = CustomTaxKind::ALL.get(position) {
+                        update(index, &|tax| tax.kind = with_entered(*kind, entered(tax.kind)));
+                    }
+                }
+            >
+                {CustomTaxKind::ALL.into_iter().enumerate().map(|(position, kind)| view! {
+                    <option value=position selected=move || read(index).kind.is_rate() == kind.is_rate()>
+                        {kind.label()}
+                    </option>
+                }).collect::<Vec<_>>()}
+            </select>
+            <input
+                type="number"
+                class="input-field"
+                aria-label=move || match read(index).kind {
+                    CustomTaxKind::Fixed(_) => "Beløp (NOK)",
+                    CustomTaxKind::Rate(_) => "Sats (%)",
+                }
+                step=move || match read(index).kind {
+                    CustomTaxKind::Fixed(_) => "1000",
+                    CustomTaxKind::Rate(_) => "0.1",
+                }
+                prop:value=move || entered(read(index).kind)
+                on:input=move |ev| {
+                    if let Ok(number) = event_target_value(&ev).parse::<f64>() {
+                        update(index, &|tax| tax.kind = with_entered(tax.kind, number));
+                    }
+                }
+            />
+            <Show when=move || read(index).kind.is_rate()>
+                <select
+                    class="input-field"
+                    aria-label="Grunnlag"
+                    on:change=move |ev| {
+                        let position = event_target_value(&ev).parse::<usize>().unwrap_or(0);
+                        if let Some(base) = CustomTaxBase::ALL.get(position) {
+                            update(index, &|tax| tax.base = *base);
+                        }
+                    }
+                >
+                    {CustomTaxBase::ALL.into_iter().enumerate().map(|(position, base)| view! {
+                        <option value=position selected=move || read(index).base == base>{base.label()}</option>
+                    }).collect::<Vec<_>>()}
+                </select>
+            </Show>
+            <button
+                class="saved-scenario-delete"
+                aria-label="Fjern posten"
+                on:click=move |_| input.update(|input| {
+                    if index < input.custom_taxes.len() {
+                        input.custom_taxes.remove(index);
+                    }
+                })
+            >
+                "×"
+            </button>
+        </div>
+    };
+
+    view! {
+        <div class="custom-taxes">
+            <For each=move || 0..count.get() key=|index| *index children=row />
+            <button
+                class="history-button"
+                on:click=move |_| input.update(|input| input.custom_taxes.push(CustomTax::default()))
+            >
+                "Legg til post"
+            </button>
+            <p class="field-note">
+                "Poster du legger til her, regnes med i samlet skatt, for eksempel en avgift hos arbeidsgiveren \
+                 eller et politisk forslag. Satser regnes av grunnlaget fra beregningen; negative beløp trekkes fra."
+            </p>
+        </div>
+    }
+}
@@ -0,0 +1,64 @@
+use leptos::*;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{IntersectionObserver, IntersectionObserverEntry, IntersectionObserverInit};
+
+// Start mounting a little before the placeholder scrolls into view
+const PRELOAD_MARGIN: &str = "200px";
+
+#[component]
+pub fn LoadingIndicator(#[prop(into)] label: String) -> impl IntoView {
+    view! {
+        <div class="loading-indicator" role="status">
+            <span class="loading-spinner" aria-hidden="true"></span>
+            {label}
+        </div>
+    }
+}
+
+fn on_first_visible(element: &web_sys::Element, on_visible: impl Fn() + 'static) -> Result<(), JsValue> {
+    let callback = Closure::<dyn FnMut(js_sys::Array, IntersectionObserver)>::new(
+        move |entries: js_sys::Array, observer: IntersectionObserver| {
+            let visible = entries
+                .iter()
+                .filter_map(|entry| entry.dyn_into::<IntersectionObserverEntry>().ok())
+                .any(|entry| entry.is_intersecting());
+            if visible {
+                observer.disconnect();
+                on_visible();
+            }
+        },
+    );
+    let options = IntersectionObserverInit::new();
+    options.set_root_margin(PRELOAD_MARGIN);
+    let observer = IntersectionObserver::new_with_options(callback.into_js_value().unchecked_ref(), &options)?;
+    observer.observe(element);
+    Ok(())
+}
+
+// Mounts heavy panels only once they come near the viewport, so the first
+// render only builds what is on screen. Where IntersectionObserver is
+// missing, the children are mounted right away.
+#[component]
+pub fn Deferred(#[prop(into)] label: String, children: ChildrenFn) -> impl IntoView {
+    let (visible, set_visible) = create_signal(false);
+    let placeholder = create_node_ref::<html::Div>();
+    placeholder.on_load(move |element| {
+        if let Err(err) = on_first_visible(&element, move || set_visible.set(true)) {
+            logging::warn!("Viser innholdet med en gang: {:?}", err);
+            set_visible.set(true);
+        }
+    });
+
+    view! {
+        <Show
+            when=move || visible.get()
+            fallback=move || view! {
+                <div class="deferred-placeholder" node_ref=placeholder>
+                    <LoadingIndicator label=label.clone() />
+                </div>
+            }
+        >
+            {children()}
+        </Show>
+    }
+}
@@ -0,0 +1,69 @@
+use leptos::*;
+
+#[component]
+pub fn ConfirmDialog(
+    #[prop(into)] open: Signal<bool>,
+    #[prop(into)] message: Signal<String>,
+    on_confirm: impl Fn() + Copy + 'static,
+    on_cancel: impl Fn() + Copy + 'static,
+    confirm_label: &'static str,
+) -> impl IntoView {
+    view! {
+        <Show when=move || open.get()>
+            <div class="dialog-backdrop" on:click=move |_| on_cancel()>
+                <div
+                    class="dialog"
+                    role="alertdialog"
+                    aria-modal="true"
+                    on:click=|ev| ev.stop_propagation()
+                >
+                    <p class="dialog-message">{move || message.get()}</p>
+                    <div class="dialog-actions">
+                        <button class="history-button" on:click=move |_| on_cancel()>
+                            "Avbryt"
+                        </button>
+                        <button class="reset-button" on:click=move |_| on_confirm()>
+                            {confirm_label}
+                        </button>
+                    </div>
+                </div>
+            </div>
+        </Show>
+    }
+}
+
+#[component]
+pub fn ShortcutsOverlay(
+    #[prop(into)] open: Signal<bool>,
+    shortcuts: Vec<(String, &'static str)>,
+    on_close: impl Fn() + Copy + 'static,
+) -> impl IntoView {
+    let shortcuts = store_value(shortcuts);
+
+    view! {
+        <Show when=move || open.get()>
+            <div class="dialog-backdrop" on:click=move |_| on_close()>
+                <div
+                    class="dialog shortcuts-dialog"
+                    role="dialog"
+                    aria-modal="true"
+                    aria-labelledby="shortcuts-heading"
+                    on:click=|ev| ev.stop_propagation()
+                >
+                    <h4 id="shortcuts-heading">"Hurtigtaster"</h4>
+                    <dl class="shortcut-list">
+                        {shortcuts.get_value().into_iter().map(|(keys, description)| view! {
+                            <dt><kbd>{keys}</kbd></dt>
+                            <dd>{description}</dd>
+                        }).collect::<Vec<_>>()}
+                    </dl>
+                    <div class="dialog-actions">
+                        <button class="history-button" on:click=move |_| on_close()>
+                            "Lukk"
+                        </button>
+                    </div>
+                </div>
+            </div>
+        </Show>
+    }
+}
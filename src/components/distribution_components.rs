@@ -0,0 +1,155 @@
+use leptos::*;
+use crate::components::use_currency;
+use crate::distribution::{compare_distribution_policies, DistributionPlan, PolicyOutcome, MAX_PLAN_YEARS};
+use crate::money::{Money, Rate};
+use crate::tax_calculator::{NorwegianTaxCalculator, TaxCalculationInput};
+
+const CHART_WIDTH: f64 = 640.0;
+const CHART_HEIGHT: f64 = 240.0;
+const AXIS_WIDTH: f64 = 90.0;
+const AXIS_HEIGHT: f64 = 24.0;
+
+#[component]
+fn PolicyChart(policies: Vec<PolicyOutcome>) -> impl IntoView {
+    let years = policies.iter().map(|policy| policy.values.len() - 1).max().unwrap_or(1).max(1);
+    let max_value = policies.iter().flat_map(|policy| policy.values.iter().copied()).fold(Money(1.0), Money::max);
+    let plot_width = CHART_WIDTH - AXIS_WIDTH;
+    let plot_height = CHART_HEIGHT - AXIS_HEIGHT;
+    let x = move |year: usize| AXIS_WIDTH + year as f64 / years as f64 * plot_width;
+    let y = move |value: Money| plot_height - value / max_value * plot_height;
+
+    view! {
+        <svg
+            class="simulation-chart"
+            viewBox=format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT)
+            role="img"
+            aria-label="Verdi etter skatt per år for hver utbyttepolitikk"
+        >
+            {[0.0, 0.5, 1.0].into_iter().map(|fraction| {
+                let value = max_value * fraction;
+                view! {
+                    <g>
+                        <line class="simulation-grid" x1=AXIS_WIDTH x2=CHART_WIDTH y1=y(value) y2=y(value) />
+                        <text class="simulation-axis" x=AXIS_WIDTH - 6.0 y=y(value) + 4.0 text-anchor="end">
+                            {NorwegianTaxCalculator::format_currency(value)}
+                        </text>
+                    </g>
+                }
+            }).collect::<Vec<_>>()}
+            {policies.iter().enumerate().map(|(index, policy)| {
+                let points = policy.values.iter().enumerate()
+                    .map(|(year, value)| format!("{:.1},{:.1}", x(year), y(*value)))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                view! { <polyline class=format!("policy-line policy-line-{}", index) points=points /> }
+            }).collect::<Vec<_>>()}
+            {(0..=years).map(|year| view! {
+                <text class="simulation-axis" x=x(year) y=CHART_HEIGHT - 6.0 text-anchor="middle">
+                    {year}
+                </text>
+            }).collect::<Vec<_>>()}
+        </svg>
+    }
+}
+
+#[component]
+pub fn DistributionPlanner(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let plan = create_rw_signal(DistributionPlan::default());
+    // A memo so the per-year inputs are only rebuilt when the count changes
+    let years = create_memo(move |_| plan.with(DistributionPlan::years));
+    let policies = create_memo(move |_| input.with(|input| plan.with(|plan| compare_distribution_policies(input, plan))));
+    let currency = use_currency();
+
+    view! {
+        <div class="chart-container">
+            <h4>"Utbytte eller tilbakeholdt overskudd"</h4>
+            <div class="simulation-parameters">
+                <label class="simulation-parameter">
+                    "Antall år"
+                    <input
+                        type="number"
+                        class="input-field"
+                        min="1"
+                        max=MAX_PLAN_YEARS
+                        prop:value=move || years.get()
+                        on:input=move |ev| {
+                            if let Ok(years) = event_target_value(&ev).parse::<usize>() {
+                                plan.update(|plan| plan.set_years(years));
+                            }
+                        }
+                    />
+                </label>
+                <label class="simulation-parameter">
+                    "Avkastning (%)"
+                    <input
+                        type="number"
+                        class="input-field"
+                        step="0.5"
+                        prop:value=move || format!("{:.1}", plan.with(|plan| plan.annual_return.percent()))
+                        on:input=move |ev| {
+                            if let Ok(rate) = event_target_value(&ev).parse::<f64>() {
+                                plan.update(|plan| plan.annual_return = Rate::from_percent(rate));
+                            }
+                        }
+                    />
+                </label>
+                <label class="checkbox-label" title="Person → Holding AS → Driftsselskap AS">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || plan.with(|plan| plan.holding)
+                        on:change=move |ev| plan.update(|plan| plan.holding = event_target_checked(&ev))
+                    />
+                    "Holdingstruktur"
+                </label>
+            </div>
+            <div class="simulation-parameters">
+                {move || (0..years.get()).map(|year| view! {
+                    <label class="simulation-parameter">
+                        {format!("År {} beholdes (%)", year + 1)}
+                        <input
+                            type="number"
+                            class="input-field rate-field"
+                            min="0"
+                            max="100"
+                            step="10"
+                            prop:value=move || plan.with(|plan| {
+                                plan.retained_shares.get(year).map(|share| share.percent()).unwrap_or_default()
+                            })
+                            on:input=move |ev| {
+                                if let Ok(share) = event_target_value(&ev).parse::<f64>() {
+                                    plan.update(|plan| {
+                                        if let Some(slot) = plan.retained_shares.get_mut(year) {
+                                            *slot = Rate::from_percent(share).clamp(Rate::ZERO, Rate::from_percent(100.0));
+                                        }
+                                    });
+                                }
+                            }
+                        />
+                    </label>
+                }).collect::<Vec<_>>()}
+            </div>
+            {move || view! { <PolicyChart policies=policies.get() /> }}
+            <ul class="policy-legend">
+                {move || {
+                    let currency = currency.get();
+                    policies.get().into_iter().enumerate().map(|(index, policy)| view! {
+                        <li class=format!("policy-legend-{}", index)>
+                            {format!("{}: {}", policy.label, currency.format(policy.final_value()))}
+                        </li>
+                    }).collect::<Vec<_>>()
+                }}
+            </ul>
+            <p class="chart-note">
+                "Overskuddet er bruttoinntekt minus fradrag, etter selskapsskatt. Verdien er det eieren sitter igjen med \
+                 etter utbytteskatt om selskapet tømmes det året. Sparepengene plasseres i aksjer: i selskapet \
+                 gjelder fritaksmetoden, privat skattlegges avkastningen årlig som aksjeinntekt."
+            </p>
+            <Show when=move || plan.with(|plan| plan.holding)>
+                <p class="chart-note">
+                    "Med holdingselskap går utbyttet fra driftsselskapet til holdingselskapet etter fritaksmetoden, \
+                     og utbytteskatten betales først når du tar pengene ut av holdingselskapet."
+                </p>
+            </Show>
+        </div>
+    }
+}
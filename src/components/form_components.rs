@@ -0,0 +1,149 @@
+use leptos::*;
+use crate::components::{CheckboxField, InputField, SelectField, TaxRateField};
+use crate::constraints::{check_value, constraint, inconsistencies, Inconsistency};
+use crate::form_schema::{FieldKind, FieldSpec, FORM_FIELDS};
+use crate::money::{Money, Rate};
+use crate::tax_calculator::{InputSection, TaxCalculationInput};
+
+fn field_view(input: RwSignal<TaxCalculationInput>, edited: RwSignal<bool>, spec: &'static FieldSpec) -> View {
+    let focus_on_mount = spec.focus_when_revealed && edited.get_untracked();
+    let (min, max) = spec.key.map_or((None, None), |key| (constraint(key).min, constraint(key).max));
+    let update = move |apply: &dyn Fn(&mut TaxCalculationInput)| {
+        edited.set(true);
+        input.update(|input| apply(input));
+    };
+
+    match spec.kind {
+        FieldKind::Amount { get, set, step } => view! {
+            <InputField
+                label=spec.label
+                rule=spec.rule
+                value=Signal::derive(move || input.with(get).amount())
+                on_change=SignalSetter::map(move |value| update(&|input| set(input, Money(value))))
+                step=step
+                min=min
+                max=max
+                focus_on_mount=focus_on_mount
+            />
+        }.into_view(),
+        FieldKind::Rate { get, set } => view! {
+            <TaxRateField
+                label=spec.label
+                rule=spec.rule
+                value=Signal::derive(move || input.with(get))
+                on_change=SignalSetter::map(move |value| update(&|input| set(input, value)))
+                min=min
+                max=max
+                focus_on_mount=focus_on_mount
+            />
+        }.into_view(),
+        FieldKind::PerMille { get, set } => view! {
+            <InputField
+                label=spec.label
+                rule=spec.rule
+                value=Signal::derive(move || input.with(get).per_mille())
+                on_change=SignalSetter::map(move |value| update(&|input| set(input, Rate::from_per_mille(value))))
+                step=0.1
+                min=min
+                max=max
+                focus_on_mount=focus_on_mount
+            />
+        }.into_view(),
+        FieldKind::Quantity { get, set, step, .. } => view! {
+            <InputField
+                label=spec.label
+                rule=spec.rule
+                value=Signal::derive(move || input.with(get))
+                on_change=SignalSetter::map(move |value| update(&|input| set(input, value)))
+                step=step
+                min=min
+                max=max
+                focus_on_mount=focus_on_mount
+            />
+        }.into_view(),
+        FieldKind::Checkbox { get, set } => view! {
+            <CheckboxField
+                label=spec.label
+                rule=spec.rule
+                value=Signal::derive(move || input.with(get))
+                on_change=SignalSetter::map(move |value| update(&|input| set(input, value)))
+            />
+        }.into_view(),
+        FieldKind::Select { options, get, set } => view! {
+            <SelectField
+                label=spec.label
+                rule=spec.rule
+                options=options()
+                value=Signal::derive(move || input.with(get))
+                on_change=SignalSetter::map(move |index| update(&|input| set(input, index)))
+            />
+        }.into_view(),
+        FieldKind::Note(text) => view! {
+            <p class="field-note">{move || input.with(text)}</p>
+        }.into_view(),
+    }
+}
+
+// The engine's message for a number outside its range
+fn warning_view(input: RwSignal<TaxCalculationInput>, spec: &'static FieldSpec) -> Option<impl IntoView> {
+    let key = spec.key?;
+    if !matches!(spec.kind, FieldKind::Amount { .. } | FieldKind::Rate { .. } | FieldKind::PerMille { .. } | FieldKind::Quantity { .. }) {
+        return None;
+    }
+    let message = move || input.with(|input| check_value(key, spec.kind.number(input)?, input));
+    Some(move || message().map(|message| view! { <p class="field-warning" role="status">{message}</p> }))
+}
+
+// Renders one input section from the form schema. A field is mounted while
+// the engine reads its input for the current entity type and settings.
+#[component]
+pub fn DynamicForm(input: RwSignal<TaxCalculationInput>, section: InputSection) -> impl IntoView {
+    // Set by the first edit, so a field revealed by it can take focus
+    // without anything being focused on the initial render
+    let edited = create_rw_signal(false);
+
+    FORM_FIELDS
+        .iter()
+        .filter(|spec| spec.section == section)
+        .map(|spec| view! {
+            <Show when=move || input.with(|input| spec.is_visible(input))>
+                {field_view(input, edited, spec)}
+                {warning_view(input, spec)}
+            </Show>
+        })
+        .collect_view()
+}
+
+// Inputs that contradict each other, above the results they affect. A
+// dismissed warning stays hidden for the session, even if it goes away
+// and comes back.
+#[component]
+pub fn InconsistencyWarnings(input: RwSignal<TaxCalculationInput>) -> impl IntoView {
+    let dismissed = create_rw_signal(Vec::<Inconsistency>::new());
+    let visible = move || {
+        input.with(inconsistencies)
+            .into_iter()
+            .filter(|found| !dismissed.with(|dismissed| dismissed.contains(found)))
+            .collect::<Vec<_>>()
+    };
+
+    move || {
+        let warnings = visible();
+        (!warnings.is_empty()).then(|| view! {
+            <ul class="input-warnings" role="status">
+                {warnings.into_iter().map(|found| view! {
+                    <li>
+                        <span>{found.message()}</span>
+                        <button
+                            class="dismiss-warning"
+                            aria-label="Skjul advarselen"
+                            on:click=move |_| dismissed.update(|dismissed| dismissed.push(found))
+                        >
+                            "Skjul"
+                        </button>
+                    </li>
+                }).collect_view()}
+            </ul>
+        })
+    }
+}
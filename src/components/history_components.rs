@@ -0,0 +1,112 @@
+use leptos::*;
+use wasm_bindgen::JsValue;
+use crate::comparisons::entity_title;
+use crate::components::use_currency;
+use crate::download::download_bytes;
+use crate::history::{AuditLog, ResultSnapshot, SnapshotHistory};
+
+fn format_time(timestamp: f64) -> String {
+    js_sys::Date::new(&JsValue::from_f64(timestamp))
+        .to_locale_time_string("nb-NO")
+        .as_string()
+        .unwrap_or_default()
+}
+
+#[component]
+pub fn SnapshotHistoryPanel(
+    snapshots: RwSignal<SnapshotHistory>,
+    on_restore: impl Fn(ResultSnapshot) + Copy + 'static,
+) -> impl IntoView {
+    let currency = use_currency();
+
+    let export_json = move |_| {
+        let result = snapshots
+            .with_untracked(|history| serde_json::to_vec_pretty(history.entries()))
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| {
+                download_bytes("skatteberegninger.json", "application/json", &bytes).map_err(|err| format!("{:?}", err))
+            });
+        if let Err(err) = result {
+            logging::error!("Kunne ikke eksportere historikken: {}", err);
+        }
+    };
+
+    view! {
+        <details class="snapshot-history">
+            <summary>
+                {move || format!("Tidligere beregninger ({})", snapshots.with(|history| history.entries().len()))}
+            </summary>
+            <ol class="snapshot-list">
+                {move || {
+                    let currency = currency.get();
+                    snapshots.with(|history| history.entries().iter().cloned().map(|snapshot| {
+                        let summary = format!(
+                            "{} · {} · skatt {}",
+                            entity_title(snapshot.input.entity_type),
+                            currency.format(snapshot.input.gross_income),
+                            currency.format(snapshot.result.total_tax),
+                        );
+                        let time = format_time(snapshot.timestamp);
+                        view! {
+                            <li>
+                                <button
+                                    class="snapshot-restore"
+                                    title="Gå tilbake til denne beregningen"
+                                    on:click=move |_| on_restore(snapshot.clone())
+                                >
+                                    <span class="snapshot-time">{time}</span>
+                                    <span>{summary}</span>
+                                </button>
+                            </li>
+                        }
+                    }).collect::<Vec<_>>())
+                }}
+            </ol>
+            <button class="history-button" on:click=export_json>"Eksporter historikk (JSON)"</button>
+        </details>
+    }
+}
+
+#[component]
+pub fn AuditLogPanel(log: RwSignal<AuditLog>) -> impl IntoView {
+    let export_json = move |_| {
+        let result = log
+            .with_untracked(|log| serde_json::to_vec_pretty(log.entries()))
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| {
+                download_bytes("endringslogg.json", "application/json", &bytes).map_err(|err| format!("{:?}", err))
+            });
+        if let Err(err) = result {
+            logging::error!("Kunne ikke eksportere endringsloggen: {}", err);
+        }
+    };
+
+    view! {
+        <details class="snapshot-history audit-log">
+            <summary>
+                {move || format!("Endringslogg ({})", log.with(|log| log.entries().len()))}
+            </summary>
+            <table class="analysis-table">
+                <thead>
+                    <tr>
+                        <th>"Tid"</th>
+                        <th>"Felt"</th>
+                        <th>"Fra"</th>
+                        <th>"Til"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || log.with(|log| log.entries().iter().cloned().map(|change| view! {
+                        <tr>
+                            <td>{format_time(change.timestamp)}</td>
+                            <td>{change.field}</td>
+                            <td>{change.old}</td>
+                            <td>{change.new}</td>
+                        </tr>
+                    }).collect::<Vec<_>>())}
+                </tbody>
+            </table>
+            <button class="history-button" on:click=export_json>"Eksporter endringslogg (JSON)"</button>
+        </details>
+    }
+}
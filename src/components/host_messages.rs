@@ -0,0 +1,106 @@
+use leptos::*;
+use serde::Deserialize;
+use serde_json::Value;
+use wasm_bindgen::JsValue;
+use crate::tax_calculator::{TaxCalculationInput, TaxCalculationResult};
+
+// Origins allowed to drive the calculator when it runs in an iframe, read
+// from `<meta name="ntc-allowed-origins" content="https://example.no ...">`.
+// Without the tag the message API stays disabled; `*` allows any origin.
+const ALLOWED_ORIGINS_META: &str = "meta[name=\"ntc-allowed-origins\"]";
+
+pub const RESULT_MESSAGE: &str = "ntc:result";
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum HostCommand {
+    // Partial input; fields that are left out keep their current value
+    #[serde(rename = "ntc:set-input")]
+    SetInput { input: Value },
+    #[serde(rename = "ntc:get-result")]
+    GetResult,
+}
+
+fn allowed_origins() -> Vec<String> {
+    document()
+        .query_selector(ALLOWED_ORIGINS_META)
+        .ok()
+        .flatten()
+        .and_then(|meta| meta.get_attribute("content"))
+        .map(|content| content.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn is_allowed(allowed: &[String], origin: &str) -> bool {
+    allowed.iter().any(|allowed| allowed == "*" || allowed == origin)
+}
+
+pub fn in_iframe() -> bool {
+    let window = window();
+    window
+        .parent()
+        .ok()
+        .flatten()
+        .is_some_and(|parent| !js_sys::Object::is(&parent, &window))
+}
+
+fn merge_input(current: &TaxCalculationInput, changes: Value) -> Option<TaxCalculationInput> {
+    let mut merged = serde_json::to_value(current).ok()?;
+    let (Value::Object(target), Value::Object(changes)) = (&mut merged, changes) else {
+        return None;
+    };
+    target.extend(changes);
+    serde_json::from_value(merged).ok()
+}
+
+fn parse_command(data: JsValue) -> Option<HostCommand> {
+    let json = match data.as_string() {
+        Some(json) => json,
+        None => js_sys::JSON::stringify(&data).ok()?.as_string()?,
+    };
+    serde_json::from_str(&json).ok()
+}
+
+fn post_result(allowed: &[String], result: &TaxCalculationResult) {
+    let Ok(Some(parent)) = window().parent() else { return };
+    let message = serde_json::json!({ "type": RESULT_MESSAGE, "result": result }).to_string();
+    let Ok(message) = js_sys::JSON::parse(&message) else { return };
+    // The browser drops messages whose target origin doesn't match the parent
+    for origin in allowed {
+        let _ = parent.post_message(&message, origin);
+    }
+}
+
+pub fn use_host_messages(input: RwSignal<TaxCalculationInput>, result: Memo<TaxCalculationResult>) {
+    if !in_iframe() {
+        return;
+    }
+    let allowed = allowed_origins();
+    if allowed.is_empty() {
+        return;
+    }
+    let allowed = store_value(allowed);
+
+    let handle = window_event_listener(ev::message, move |ev| {
+        if !allowed.with_value(|allowed| is_allowed(allowed, &ev.origin())) {
+            return;
+        }
+        match parse_command(ev.data()) {
+            Some(HostCommand::SetInput { input: changes }) => {
+                if let Some(merged) = input.with_untracked(|current| merge_input(current, changes)) {
+                    input.set(merged);
+                }
+            }
+            Some(HostCommand::GetResult) => {
+                allowed.with_value(|allowed| post_result(allowed, &result.get_untracked()));
+            }
+            None => {}
+        }
+    });
+    on_cleanup(move || handle.remove());
+
+    create_effect(move |_| {
+        let result = result.get();
+        allowed.with_value(|allowed| post_result(allowed, &result));
+    });
+}
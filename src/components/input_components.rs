@@ -1,10 +1,43 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 use leptos::*;
-use crate::tax_calculator::EntityType;
+use crate::tax_calculator::{EntityType, InputSection, NorwegianTaxCalculator, TaxRule, TaxYear};
+use crate::components::InfoTooltip;
+use crate::money::Rate;
+use crate::presets::InputPreset;
+use crate::municipalities::Municipality;
+
+static NEXT_FIELD_ID: AtomicUsize = AtomicUsize::new(0);
+
+pub fn next_field_id() -> String {
+    format!("field-{}", NEXT_FIELD_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+fn focus_when_mounted(input_ref: NodeRef<html::Input>) {
+    input_ref.on_load(|input| {
+        request_animation_frame(move || {
+            let _ = input.focus();
+        });
+    });
+}
+
+pub fn entity_tab_id(entity_type: EntityType) -> &'static str {
+    match entity_type {
+        EntityType::Individual => "entity-tab-individual",
+        EntityType::Corporation => "entity-tab-corporation",
+        EntityType::Partnership => "entity-tab-partnership",
+        EntityType::SoleProprietorship => "entity-tab-enk",
+        EntityType::ForeignBranch => "entity-tab-nuf",
+        EntityType::Nonprofit => "entity-tab-nonprofit",
+    }
+}
+
+pub const ENTITY_PANEL_ID: &str = "entity-panel";
 
 #[component]
 pub fn EntityTab(
     entity_type: EntityType,
-    current_type: ReadSignal<EntityType>,
+    current_type: Signal<EntityType>,
     on_select: impl Fn(web_sys::MouseEvent) + 'static,
     label: &'static str,
 ) -> impl IntoView {
@@ -12,6 +45,11 @@ pub fn EntityTab(
     
     view! {
         <button
+            id=entity_tab_id(entity_type)
+            role="tab"
+            aria-selected=move || is_active().to_string()
+            aria-controls=ENTITY_PANEL_ID
+            tabindex=move || if is_active() { "0" } else { "-1" }
             class=move || if is_active() { "entity-tab entity-tab-active" } else { "entity-tab" }
             on:click=on_select
         >
@@ -23,18 +61,32 @@ pub fn EntityTab(
 #[component]
 pub fn InputField(
     label: &'static str,
-    value: ReadSignal<f64>,
-    on_change: WriteSignal<f64>,
+    value: Signal<f64>,
+    on_change: SignalSetter<f64>,
     step: f64,
-    min: f64,
+    #[prop(optional_no_strip)] min: Option<f64>,
+    #[prop(optional_no_strip)] max: Option<f64>,
+    #[prop(optional)] focus_on_mount: bool,
+    #[prop(optional_no_strip)] rule: Option<TaxRule>,
 ) -> impl IntoView {
+    let id = next_field_id();
+    let input_ref = create_node_ref::<html::Input>();
+    if focus_on_mount {
+        focus_when_mounted(input_ref);
+    }
+
     view! {
         <div class="form-group">
-            <label>{label}</label>
+            <label for=id.clone()>
+                {label}
+                {rule.map(|rule| view! { <InfoTooltip rule=rule /> })}
+            </label>
             <input
+                id=id
+                node_ref=input_ref
                 type="number"
                 class="input-field"
-                value=move || value.get()
+                prop:value=move || value.get()
                 on:input=move |ev| {
                     if let Ok(val) = event_target_value(&ev).parse::<f64>() {
                         on_change.set(val);
@@ -42,6 +94,7 @@ pub fn InputField(
                 }
                 step=step
                 min=min
+                max=max
             />
         </div>
     }
@@ -50,24 +103,39 @@ pub fn InputField(
 #[component]
 pub fn TaxRateField(
     label: &'static str,
-    value: ReadSignal<f64>,
-    on_change: WriteSignal<f64>,
+    value: Signal<Rate>,
+    on_change: SignalSetter<Rate>,
+    #[prop(optional_no_strip)] min: Option<f64>,
+    #[prop(optional_no_strip)] max: Option<f64>,
+    #[prop(optional)] focus_on_mount: bool,
+    #[prop(optional_no_strip)] rule: Option<TaxRule>,
 ) -> impl IntoView {
+    let id = next_field_id();
+    let input_ref = create_node_ref::<html::Input>();
+    if focus_on_mount {
+        focus_when_mounted(input_ref);
+    }
+
     view! {
         <div class="form-group">
-            <label>{label}</label>
+            <label for=id.clone()>
+                {label}
+                {rule.map(|rule| view! { <InfoTooltip rule=rule /> })}
+            </label>
             <input
+                id=id
+                node_ref=input_ref
                 type="number"
                 class="input-field rate-field"
-                value=move || value.get()
+                prop:value=move || value.get().percent()
                 on:input=move |ev| {
                     if let Ok(val) = event_target_value(&ev).parse::<f64>() {
-                        on_change.set(val);
+                        on_change.set(Rate::from_percent(val));
                     }
                 }
                 step="0.1"
-                min="0"
-                max="25"
+                min=min
+                max=max
             />
         </div>
     }
@@ -76,20 +144,183 @@ pub fn TaxRateField(
 #[component]
 pub fn CheckboxField(
     label: &'static str,
-    value: ReadSignal<bool>,
-    on_change: WriteSignal<bool>,
+    value: Signal<bool>,
+    on_change: SignalSetter<bool>,
+    #[prop(optional_no_strip)] rule: Option<TaxRule>,
 ) -> impl IntoView {
     view! {
         <div class="form-group">
             <label class="checkbox-label">
                 <input
                     type="checkbox"
-                    checked=move || value.get()
+                    prop:checked=move || value.get()
                     on:change=move |ev| on_change.set(event_target_checked(&ev))
                 />
                 <span class="checkmark"></span>
                 {label}
+                {rule.map(|rule| view! { <InfoTooltip rule=rule /> })}
+            </label>
+        </div>
+    }
+}
+
+#[component]
+pub fn PresetSelector(
+    presets: Vec<InputPreset>,
+    on_select: impl Fn(String) + 'static,
+) -> impl IntoView {
+    view! {
+        <div class="preset-selector">
+            <label for="preset-select">"Forhåndsvalg"</label>
+            <select
+                id="preset-select"
+                class="input-field"
+                on:change=move |ev| {
+                    let id = event_target_value(&ev);
+                    if !id.is_empty() {
+                        on_select(id);
+                    }
+                }
+            >
+                <option value="" selected=true>"Velg et eksempel..."</option>
+                {presets.into_iter().map(|preset| view! {
+                    <option value=preset.id>
+                        {format!("{} ({} NOK)", preset.label, NorwegianTaxCalculator::format_currency(preset.gross_income))}
+                    </option>
+                }).collect::<Vec<_>>()}
+            </select>
+        </div>
+    }
+}
+
+#[component]
+pub fn MunicipalitySelector(
+    municipalities: Signal<Vec<Municipality>>,
+    selected: Signal<Option<String>>,
+    on_select: impl Fn(Option<Municipality>) + 'static,
+) -> impl IntoView {
+    let property_tax = move || {
+        let number = selected.get()?;
+        municipalities.with(|list| {
+            list.iter()
+                .find(|municipality| municipality.number == number)
+                .map(|municipality| match (municipality.property_tax_rate, municipality.property_tax_bunnfradrag) {
+                    (Some(rate), Some(bunnfradrag)) => {
+                        format!("Eiendomsskatt: {:.1} ‰, bunnfradrag {}", rate.per_mille(), bunnfradrag)
+                    }
+                    (Some(rate), None) => format!("Eiendomsskatt: {:.1} ‰", rate.per_mille()),
+                    (None, _) => "Eiendomsskatt: ingen data".to_string(),
+                })
+        })
+    };
+
+    view! {
+        <div class="form-group">
+            <label for="municipality-select">"Kommune"</label>
+            <select
+                id="municipality-select"
+                class="input-field"
+                on:change=move |ev| {
+                    let number = event_target_value(&ev);
+                    on_select(municipalities.with_untracked(|list| {
+                        list.iter().find(|municipality| municipality.number == number).cloned()
+                    }));
+                }
+            >
+                <option value="" selected=move || selected.get().is_none()>"Egendefinert sats"</option>
+                {move || municipalities.get().into_iter().map(|municipality| {
+                    let number = municipality.number.clone();
+                    view! {
+                        <option
+                            value=municipality.number.clone()
+                            selected=move || selected.get().as_deref() == Some(number.as_str())
+                        >
+                            {format!("{} ({})", municipality.name, municipality.number)}
+                        </option>
+                    }
+                }).collect::<Vec<_>>()}
+            </select>
+            <span class="mode-toggle-hint">{property_tax}</span>
+        </div>
+    }
+}
+
+#[component]
+pub fn TaxYearSelector(value: Signal<TaxYear>, on_change: SignalSetter<TaxYear>) -> impl IntoView {
+    view! {
+        <div class="preset-selector">
+            <label for="tax-year-select">"Skatteår"</label>
+            <select
+                id="tax-year-select"
+                class="input-field"
+                on:change=move |ev| {
+                    if let Some(tax_year) = event_target_value(&ev).parse().ok().and_then(TaxYear::from_year) {
+                        on_change.set(tax_year);
+                    }
+                }
+            >
+                {TaxYear::ALL.into_iter().map(|tax_year| view! {
+                    <option value=tax_year.year() selected=move || value.get() == tax_year>
+                        {tax_year.year()}
+                    </option>
+                }).collect::<Vec<_>>()}
+            </select>
+        </div>
+    }
+}
+
+#[component]
+pub fn InputSectionGroup(
+    section: InputSection,
+    on_reset: impl Fn(InputSection) + 'static,
+    children: Children,
+) -> impl IntoView {
+    view! {
+        <section class="input-section">
+            <div class="input-section-header">
+                <h4>{section.label()}</h4>
+                <button class="section-reset-button" on:click=move |_| on_reset(section)>
+                    "Tilbakestill"
+                </button>
+            </div>
+            <div class="input-grid">
+                {children()}
+            </div>
+        </section>
+    }
+}
+
+#[component]
+pub fn SelectField(
+    label: &'static str,
+    options: Vec<&'static str>,
+    value: Signal<usize>,
+    on_change: SignalSetter<usize>,
+    #[prop(optional_no_strip)] rule: Option<TaxRule>,
+) -> impl IntoView {
+    let id = next_field_id();
+
+    view! {
+        <div class="form-group">
+            <label for=id.clone()>
+                {label}
+                {rule.map(|rule| view! { <InfoTooltip rule=rule /> })}
             </label>
+            <select
+                id=id
+                class="input-field"
+                on:change=move |ev| {
+                    if let Ok(index) = event_target_value(&ev).parse::<usize>() {
+                        on_change.set(index);
+                    }
+                }
+            >
+                {options.into_iter().enumerate().map(|(index, option)| view! {
+                    <option value=index selected=move || value.get() == index>
+                        {option}
+                    </option>
+                }).collect::<Vec<_>>()}
+            </select>
         </div>
     }
-}
\ No newline at end of file
+}
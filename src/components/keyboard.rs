@@ -0,0 +1,76 @@
+use std::rc::Rc;
+
+use leptos::*;
+use wasm_bindgen::JsCast;
+
+#[derive(Clone)]
+pub struct Shortcut {
+    pub key: &'static str,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub description: &'static str,
+    pub action: Rc<dyn Fn()>,
+}
+
+impl Shortcut {
+    pub fn new(key: &'static str, description: &'static str, action: impl Fn() + 'static) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            shift: false,
+            description,
+            action: Rc::new(action),
+        }
+    }
+
+    pub fn with_ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    pub fn with_shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub fn label(&self) -> String {
+        let mut parts = Vec::new();
+        if self.ctrl {
+            parts.push("Ctrl".to_string());
+        }
+        if self.shift {
+            parts.push("Shift".to_string());
+        }
+        parts.push(self.key.to_uppercase());
+        parts.join("+")
+    }
+
+    fn matches(&self, ev: &web_sys::KeyboardEvent) -> bool {
+        let ctrl = ev.ctrl_key() || ev.meta_key();
+        ev.key().eq_ignore_ascii_case(self.key) && ctrl == self.ctrl && (!self.shift || ev.shift_key())
+    }
+}
+
+fn is_editable_target(ev: &web_sys::KeyboardEvent) -> bool {
+    ev.target()
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        .map(|element| matches!(element.tag_name().as_str(), "INPUT" | "SELECT" | "TEXTAREA"))
+        .unwrap_or(false)
+}
+
+// Installs a window-level keydown handler for the given shortcuts. The first
+// matching shortcut wins, so list Shift variants before their plain forms.
+// Plain-key shortcuts are ignored while typing in form fields.
+pub fn use_keyboard_shortcuts(shortcuts: Vec<Shortcut>) {
+    let handle = window_event_listener(ev::keydown, move |ev| {
+        let editing = is_editable_target(&ev);
+        if let Some(shortcut) = shortcuts
+            .iter()
+            .find(|shortcut| shortcut.matches(&ev) && (shortcut.ctrl || !editing))
+        {
+            ev.prevent_default();
+            (shortcut.action)();
+        }
+    });
+    on_cleanup(move || handle.remove());
+}
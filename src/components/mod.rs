@@ -1,7 +1,65 @@
 pub mod tax_calculator_component;
 pub mod input_components;
+pub mod form_components;
 pub mod result_components;
+pub mod dialog_components;
+#[cfg(feature = "charts")]
+pub mod chart_components;
+pub mod analysis_components;
+#[cfg(feature = "charts")]
+pub mod simulation_components;
+#[cfg(feature = "employer-cost")]
+pub mod payroll_components;
+#[cfg(feature = "charts")]
+pub mod distribution_components;
+pub mod batch_components;
+pub mod benchmark_components;
+pub mod client_components;
+#[cfg(feature = "integrations")]
+pub mod skattemelding_components;
+pub mod currency_components;
+pub mod scenario_components;
+pub mod history_components;
+pub mod backup_components;
+pub mod custom_tax_components;
+pub mod deferred_components;
+pub mod policy_components;
+pub mod population_components;
+pub mod tooltip_components;
+pub mod keyboard;
+#[cfg(feature = "integrations")]
+pub mod host_messages;
+pub mod tween;
 
 pub use tax_calculator_component::*;
 pub use input_components::*;
-pub use result_components::*;
\ No newline at end of file
+pub use form_components::*;
+pub use result_components::*;
+pub use dialog_components::*;
+#[cfg(feature = "charts")]
+pub use chart_components::*;
+pub use analysis_components::*;
+#[cfg(feature = "charts")]
+pub use simulation_components::*;
+#[cfg(feature = "employer-cost")]
+pub use payroll_components::*;
+#[cfg(feature = "charts")]
+pub use distribution_components::*;
+pub use batch_components::*;
+pub use benchmark_components::*;
+pub use client_components::*;
+#[cfg(feature = "integrations")]
+pub use skattemelding_components::*;
+pub use currency_components::*;
+pub use scenario_components::*;
+pub use history_components::*;
+pub use backup_components::*;
+pub use custom_tax_components::*;
+pub use deferred_components::*;
+pub use policy_components::*;
+pub use population_components::*;
+pub use tooltip_components::*;
+pub use keyboard::*;
+#[cfg(feature = "integrations")]
+pub use host_messages::*;
+pub use tween::*;
\ No newline at end of file
@@ -0,0 +1,313 @@
+use leptos::*;
+use crate::download::download_bytes;
+use crate::components::use_currency;
+use crate::employer_zones::EmployerZone;
+use crate::money::{Money, Rate};
+use crate::payroll::{
+    bonus_effect, calculate_payroll, payroll_to_csv, project_pension, HolidayPayRate, PayrollInput, OTP_MAX_RATE, OTP_MIN_RATE,
+    PENSION_PAYOUT_YEARS,
+};
+use crate::tax_calculator::{NorwegianTaxCalculator, TaxCalculationInput};
+
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Des"];
+
+#[component]
+pub fn EmployerCostPanel(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let payroll = create_rw_signal(PayrollInput::default());
+    let summary = create_memo(move |_| calculate_payroll(&payroll.get(), &input.get()));
+    let (years_to_retirement, set_years_to_retirement) = create_signal(30u32);
+    let (pension_return, set_pension_return) = create_signal(Rate::from_percent(4.0));
+    let projection = move || {
+        project_pension(summary.with(|s| s.pension_contribution()), years_to_retirement.get(), pension_return.get())
+    };
+
+    let export_csv = move |_| {
+        let summary = summary.get_untracked();
+        let filename = format!("a-melding-{}.csv", summary.tax_year.year());
+        let result = payroll_to_csv(&summary)
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| download_bytes(&filename, "text/csv", &bytes).map_err(|err| format!("{:?}", err)));
+        if let Err(err) = result {
+            logging::error!("Kunne ikke eksportere lønnsdata: {}", err);
+        }
+    };
+
+    view! {
+        <div class="chart-container payroll-panel">
+            <h4>"Arbeidsgiverkostnad"</h4>
+            <div class="simulation-parameters">
+                <label class="simulation-parameter">
+                    "Årslønn"
+                    <input
+                        type="number"
+                        class="input-field"
+                        min="0"
+                        step="10000"
+                        prop:value=move || payroll.with(|p| p.annual_salary.amount())
+                        on:input=move |ev| {
+                            if let Ok(salary) = event_target_value(&ev).parse::<f64>() {
+                                payroll.update(|p| p.annual_salary = Money(salary).max(Money::ZERO));
+                            }
+                        }
+                    />
+                </label>
+                <label class="simulation-parameter">
+                    "Arbeidsgiveravgiftssone"
+                    <select
+                        class="input-field"
+                        on:change=move |ev| {
+                            if let Some(zone) = EmployerZone::from_code(&event_target_value(&ev)) {
+                                payroll.update(|p| p.zone = zone);
+                            }
+                        }
+                    >
+                        {EmployerZone::ALL.into_iter().map(|zone| view! {
+                            <option value=zone.code() selected=move || payroll.with(|p| p.zone == zone)>
+                                {format!("Sone {} ({:.1} %)", zone.code(), zone.rate().percent())}
+                            </option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </label>
+                <label class="simulation-parameter">
+                    "Feriepenger"
+                    <select
+                        class="input-field"
+                        on:change=move |ev| {
+                            let index = event_target_value(&ev).parse::<usize>().unwrap_or(0);
+                            if let Some(rate) = HolidayPayRate::ALL.get(index) {
+                                payroll.update(|p| p.holiday_pay_rate = *rate);
+                            }
+                        }
+                    >
+                        {HolidayPayRate::ALL.into_iter().enumerate().map(|(index, rate)| view! {
+                            <option value=index selected=move || payroll.with(|p| p.holiday_pay_rate == rate)>
+                                {rate.label()}
+                            </option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </label>
+                <label class="simulation-parameter">
+                    "Utbetales i"
+                    <select
+                        class="input-field"
+                        on:change=move |ev| {
+                            if let Ok(month) = event_target_value(&ev).parse::<u32>() {
+                                payroll.update(|p| p.holiday_pay_month = month.clamp(1, 12));
+                            }
+                        }
+                    >
+                        {(1..=12u32).map(|month| view! {
+                            <option value=month selected=move || payroll.with(|p| p.holiday_pay_month == month)>
+                                {MONTHS[(month - 1) as usize]}
+                            </option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </label>
+                <label class="simulation-parameter">
+                    "Fjorårets lønn"
+                    <input
+                        type="number"
+                        class="input-field"
+                        min="0"
+                        step="10000"
+                        prop:value=move || payroll.with(|p| p.holiday_pay_base().amount())
+                        on:input=move |ev| {
+                            if let Ok(salary) = event_target_value(&ev).parse::<f64>() {
+                                payroll.update(|p| p.previous_year_salary = Some(Money(salary).max(Money::ZERO)));
+                            }
+                        }
+                    />
+                </label>
+                <label class="simulation-parameter">
+                    "OTP (%)"
+                    <input
+                        type="number"
+                        class="input-field"
+                        min=OTP_MIN_RATE.percent()
+                        max=OTP_MAX_RATE.percent()
+                        step="0.5"
+                        prop:value=move || format!("{:.1}", payroll.with(|p| p.pension_rate.percent()))
+                        on:input=move |ev| {
+                            if let Ok(rate) = event_target_value(&ev).parse::<f64>() {
+                                payroll.update(|p| p.pension_rate = Rate::from_percent(rate).clamp(OTP_MIN_RATE, OTP_MAX_RATE));
+                            }
+                        }
+                    />
+                </label>
+            </div>
+            <p class="chart-note">
+                {move || summary.with(|summary| format!(
+                    "Samlet kostnad {}: lønn {}, feriepenger {}, OTP {} og arbeidsgiveravgift {}. \
+                     Forskuddstrekk {}, utbetalt netto {}. Opptjent feriepenger til utbetaling neste år {}.",
+                    NorwegianTaxCalculator::format_nok(summary.employer_cost()),
+                    NorwegianTaxCalculator::format_nok(summary.salary()),
+                    NorwegianTaxCalculator::format_nok(summary.holiday_pay()),
+                    NorwegianTaxCalculator::format_nok(summary.pension_contribution()),
+                    NorwegianTaxCalculator::format_nok(summary.employer_contribution()),
+                    NorwegianTaxCalculator::format_nok(summary.withholding()),
+                    NorwegianTaxCalculator::format_nok(summary.net_pay()),
+                    NorwegianTaxCalculator::format_nok(summary.holiday_pay_accrued()),
+                ))}
+            </p>
+            <table class="analysis-table">
+                <thead>
+                    <tr>
+                        <th>"Måned"</th>
+                        <th>"Lønn"</th>
+                        <th>"Feriepenger"</th>
+                        <th>"Forskuddstrekk"</th>
+                        <th>"OTP"</th>
+                        <th>"Arbeidsgiveravgift"</th>
+                        <th>"Ekstra avgift"</th>
+                        <th>"Kostnad"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || summary.get().months.into_iter().map(|month| view! {
+                        <tr>
+                            <td>{MONTHS[(month.month - 1) as usize]}</td>
+                            <td>{NorwegianTaxCalculator::format_nok(month.salary)}</td>
+                            <td>{NorwegianTaxCalculator::format_nok(month.holiday_pay)}</td>
+                            <td>{NorwegianTaxCalculator::format_nok(month.withholding)}</td>
+                            <td>{NorwegianTaxCalculator::format_nok(month.pension_contribution)}</td>
+                            <td>{NorwegianTaxCalculator::format_nok(month.employer_contribution)}</td>
+                            <td>{NorwegianTaxCalculator::format_nok(month.surcharge)}</td>
+                            <td>{NorwegianTaxCalculator::format_nok(month.employer_cost())}</td>
+                        </tr>
+                    }).collect::<Vec<_>>()}
+                </tbody>
+            </table>
+            <p class="chart-note">
+                "Lønnen i utbetalingsmåneden er redusert med ferietrekk. Forskuddstrekket følger tabelltrekket: \
+                 ingen trekk i måneden feriepenger utbetales og halv trekk i desember."
+            </p>
+            <h4>"Pensjonsfremskrivning"</h4>
+            <div class="simulation-parameters">
+                <label class="simulation-parameter">
+                    "År til pensjon"
+                    <input
+                        type="number"
+                        class="input-field"
+                        min="0"
+                        max="50"
+                        prop:value=move || years_to_retirement.get()
+                        on:input=move |ev| {
+                            if let Ok(years) = event_target_value(&ev).parse::<u32>() {
+                                set_years_to_retirement.set(years.min(50));
+                            }
+                        }
+                    />
+                </label>
+                <label class="simulation-parameter">
+                    "Avkastning (%)"
+                    <input
+                        type="number"
+                        class="input-field"
+                        step="0.5"
+                        prop:value=move || format!("{:.1}", pension_return.get().percent())
+                        on:input=move |ev| {
+                            if let Ok(rate) = event_target_value(&ev).parse::<f64>() {
+                                set_pension_return.set(Rate::from_percent(rate));
+                            }
+                        }
+                    />
+                </label>
+            </div>
+            <p class="chart-note">
+                {move || {
+                    let (balance, yearly_payout) = projection();
+                    format!(
+                        "Med dagens OTP-innskudd gir det en pensjonsbeholdning på {} ved pensjonering, \
+                         eller {} i året over {} år (før skatt og uten lønnsvekst).",
+                        NorwegianTaxCalculator::format_nok(balance),
+                        NorwegianTaxCalculator::format_nok(yearly_payout),
+                        PENSION_PAYOUT_YEARS,
+                    )
+                }}
+            </p>
+            <div class="batch-actions">
+                <button class="export-button" on:click=export_csv>"Eksporter a-melding (CSV)"</button>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+pub fn BonusPanel(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let (bonus, set_bonus) = create_signal(Money(50_000.0));
+    let effect = create_memo(move |_| input.with(|input| bonus_effect(input.gross_income, bonus.get(), input)));
+    let currency = use_currency();
+
+    view! {
+        <div class="chart-container">
+            <h4>"Bonus"</h4>
+            <div class="simulation-parameters">
+                <label class="simulation-parameter">
+                    "Bonus utbetalt i én måned"
+                    <input
+                        type="number"
+                        class="input-field"
+                        min="0"
+                        step="10000"
+                        prop:value=move || bonus.get().amount()
+                        on:input=move |ev| {
+                            if let Ok(amount) = event_target_value(&ev).parse::<f64>() {
+                                set_bonus.set(Money(amount).max(Money::ZERO));
+                            }
+                        }
+                    />
+                </label>
+            </div>
+            <table class="analysis-table">
+                <tbody>
+                    {move || {
+                        let currency = currency.get();
+                        let effect = effect.get();
+                        let share = |amount: Money| {
+                            let bonus = bonus.get();
+                            if bonus > Money::ZERO { format!("{:.1}", Rate::from_fraction(amount / bonus)) } else { String::new() }
+                        };
+                        view! {
+                            <tr>
+                                <td>"Forskuddstrekk i en vanlig måned"</td>
+                                <td>{currency.format(effect.regular_withholding)}</td>
+                                <td></td>
+                            </tr>
+                            <tr>
+                                <td>"Forskuddstrekk i bonusmåneden"</td>
+                                <td>{currency.format(effect.bonus_month_withholding)}</td>
+                                <td></td>
+                            </tr>
+                            <tr>
+                                <td>"Ekstra trekk på bonusen"</td>
+                                <td>{currency.format(effect.extra_withholding())}</td>
+                                <td>{share(effect.extra_withholding())}</td>
+                            </tr>
+                            <tr>
+                                <td>"Faktisk skatt på bonusen"</td>
+                                <td>{currency.format(effect.bonus_tax)}</td>
+                                <td>{share(effect.bonus_tax)}</td>
+                            </tr>
+                        }
+                    }}
+                </tbody>
+            </table>
+            <p class="chart-note">
+                {move || effect.with(|effect| {
+                    let currency = currency.get();
+                    let settlement = effect.settlement();
+                    let outcome = if settlement >= Money::ZERO {
+                        format!("Du får tilbake {} ved skatteoppgjøret.", currency.format(settlement))
+                    } else {
+                        format!("Du får {} i restskatt ved skatteoppgjøret.", currency.format(-settlement))
+                    };
+                    format!(
+                        "Trekktabellen behandler bonusmåneden som om lønnen var like høy hele året, \
+                         så bonusen trekkes med en høy marginalsats. {}",
+                        outcome,
+                    )
+                })}
+            </p>
+        </div>
+    }
+}
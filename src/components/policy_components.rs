@@ -0,0 +1,228 @@
+use leptos::*;
+use crate::components::use_currency;
+use crate::money::{Money, Rate};
+use crate::tax_calculator::{NorwegianTaxCalculator, PolicyChanges, TaxCalculationInput, TaxCalculationResult};
+
+// What a slider can move: the number on the range input, and how the value
+// is shown. Rates move in percent.
+trait SliderValue: Copy + 'static {
+    fn to_slider(self) -> f64;
+    fn from_slider(value: f64) -> Self;
+    fn label(self) -> String;
+}
+
+impl SliderValue for Rate {
+    fn to_slider(self) -> f64 {
+        self.percent()
+    }
+
+    fn from_slider(value: f64) -> Self {
+        Rate::from_percent(value)
+    }
+
+    fn label(self) -> String {
+        format!("{:.2}", self)
+    }
+}
+
+impl SliderValue for Money {
+    fn to_slider(self) -> f64 {
+        self.amount()
+    }
+
+    fn from_slider(value: f64) -> Self {
+        Money(value)
+    }
+
+    fn label(self) -> String {
+        NorwegianTaxCalculator::format_nok(self)
+    }
+}
+
+// A plain factor
+impl SliderValue for f64 {
+    fn to_slider(self) -> f64 {
+        self
+    }
+
+    fn from_slider(value: f64) -> Self {
+        value
+    }
+
+    fn label(self) -> String {
+        format!("{:.2}", self)
+    }
+}
+
+#[component]
+fn PolicySlider<T: SliderValue>(
+    label: String,
+    min: T,
+    max: T,
+    step: T,
+    value: Signal<T>,
+    baseline: Signal<T>,
+    on_change: impl Fn(T) + 'static,
+) -> impl IntoView {
+    view! {
+        <label class="policy-slider">
+            <span class="policy-slider-label">{label}</span>
+            <input
+                type="range"
+                min=min.to_slider()
+                max=max.to_slider()
+                step=step.to_slider()
+                prop:value=move || value.get().to_slider()
+                on:input=move |ev| {
+                    if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                        on_change(T::from_slider(value));
+                    }
+                }
+            />
+            <span class="policy-slider-value">
+                {move || value.get().label()}
+                <span class="policy-slider-baseline">{move || format!("(i dag {})", baseline.get().label())}</span>
+            </span>
+        </label>
+    }
+}
+
+type ResultField = fn(&TaxCalculationResult) -> Money;
+
+// One policy value as proposed and under the current law
+fn policy_field<T: SliderValue>(
+    changes: RwSignal<PolicyChanges>,
+    baseline: Memo<PolicyChanges>,
+    get: fn(&PolicyChanges) -> T,
+) -> (Signal<T>, Signal<T>) {
+    (Signal::derive(move || changes.with(get)), Signal::derive(move || baseline.with(get)))
+}
+
+// "Hva hvis politikken endres": the current input under changed rates, next
+// to the same input under the law for the chosen year
+#[component]
+pub fn PolicyPlayground(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let tax_year = Signal::derive(move || input.with(|input| input.tax_year));
+    let baseline_policy = create_memo(move |_| PolicyChanges::baseline(tax_year.get()));
+    let changes = create_rw_signal(baseline_policy.get_untracked());
+    // A new tax year brings its own law to start from
+    create_effect(move |_| changes.set(baseline_policy.get()));
+
+    let baseline = create_memo(move |_| input.with(NorwegianTaxCalculator::calculate_totals_only));
+    let proposal = create_memo(move |_| {
+        let policy = changes.get();
+        input.with(|input| NorwegianTaxCalculator::calculate_totals_only(&TaxCalculationInput {
+            policy: Some(policy),
+            ..input.clone()
+        }))
+    });
+    let currency = use_currency();
+    let steps = create_memo(move |_| baseline_policy.with(|policy| policy.state_tax_rates.len()));
+
+    let (wealth_rate, wealth_rate_baseline) = policy_field(changes, baseline_policy, |policy| policy.wealth_tax_rate);
+    let (wealth_threshold, wealth_threshold_baseline) =
+        policy_field(changes, baseline_policy, |policy| policy.wealth_tax_threshold);
+    let (dividend_factor, dividend_factor_baseline) = policy_field(changes, baseline_policy, |policy| policy.dividend_factor);
+
+    let rows: [(&str, ResultField); 5] = [
+        ("Trinnskatt", |result| result.state_tax),
+        ("Skatt på aksjeinntekt", |result| result.investment_tax),
+        ("Formueskatt", |result| result.wealth_tax),
+        ("Samlet skatt", |result| result.total_tax),
+        ("Nettoinntekt", |result| result.net_income),
+    ];
+
+    view! {
+        <div class="chart-container policy-playground">
+            <h4>"Hva hvis politikken endres"</h4>
+            <div class="policy-sliders">
+                {move || (0..steps.get()).map(|step| {
+                    let rate = move |policy: &PolicyChanges| policy.state_tax_rates.get(step).copied().unwrap_or_default();
+                    view! {
+                        <PolicySlider
+                            label=format!("Trinnskatt trinn {}", step + 1)
+                            min=Rate::ZERO
+                            max=Rate::from_percent(30.0)
+                            step=Rate::from_percent(0.1)
+                            value=Signal::derive(move || changes.with(rate))
+                            baseline=Signal::derive(move || baseline_policy.with(rate))
+                            on_change=move |value| changes.update(|policy| {
+                                if let Some(slot) = policy.state_tax_rates.get_mut(step) {
+                                    *slot = value;
+                                }
+                            })
+                        />
+                    }
+                }).collect::<Vec<_>>()}
+                <PolicySlider
+                    label="Formueskatt".to_string()
+                    min=Rate::ZERO
+                    max=Rate::from_percent(2.0)
+                    step=Rate::from_percent(0.05)
+                    value=wealth_rate
+                    baseline=wealth_rate_baseline
+                    on_change=move |value| changes.update(|policy| policy.wealth_tax_rate = value)
+                />
+                <PolicySlider
+                    label="Bunnfradrag i formueskatten".to_string()
+                    min=Money::ZERO
+                    max=Money(5_000_000.0)
+                    step=Money(50_000.0)
+                    value=wealth_threshold
+                    baseline=wealth_threshold_baseline
+                    on_change=move |value| changes.update(|policy| policy.wealth_tax_threshold = value)
+                />
+                <PolicySlider
+                    label="Oppjusteringsfaktor for utbytte".to_string()
+                    min=1.0
+                    max=2.0
+                    step=0.01
+                    value=dividend_factor
+                    baseline=dividend_factor_baseline
+                    on_change=move |value| changes.update(|policy| policy.dividend_factor = value)
+                />
+            </div>
+            <button class="history-button" on:click=move |_| changes.set(baseline_policy.get_untracked())>
+                "Tilbake til gjeldende regler"
+            </button>
+            <table class="analysis-table">
+                <thead>
+                    <tr>
+                        <th></th>
+                        <th>"Gjeldende regler"</th>
+                        <th>"Forslag"</th>
+                        <th>"Endring"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || {
+                        let currency = currency.get();
+                        let baseline = baseline.get();
+                        let proposal = proposal.get();
+                        let mut rows = rows.iter().map(|(label, value)| view! {
+                            <tr>
+                                <td>{*label}</td>
+                                <td>{currency.format(value(&baseline))}</td>
+                                <td>{currency.format(value(&proposal))}</td>
+                                <td>{currency.format(value(&proposal) - value(&baseline))}</td>
+                            </tr>
+                        }).collect::<Vec<_>>();
+                        rows.push(view! {
+                            <tr>
+                                <td>"Effektiv skattesats"</td>
+                                <td>{format!("{:.1}", baseline.effective_tax_rate)}</td>
+                                <td>{format!("{:.1}", proposal.effective_tax_rate)}</td>
+                                <td>{format!("{:+.1} pp", (proposal.effective_tax_rate - baseline.effective_tax_rate).percent())}</td>
+                            </tr>
+                        });
+                        rows
+                    }}
+                </tbody>
+            </table>
+            <p class="chart-note">
+                "Trinnene beholder dagens innslagspunkter. Oppjusteringsfaktoren ganges med 22 % og gir skattesatsen \
+                 på utbytte og aksjegevinster. Endringene gjelder bare denne sammenligningen."
+            </p>
+        </div>
+    }
+}
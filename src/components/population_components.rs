@@ -0,0 +1,116 @@
+use leptos::*;
+use crate::batch::BatchRow;
+use crate::components::use_currency;
+use crate::money::{Money, Rate};
+use crate::population::{population_stats, PopulationStats};
+
+const CHART_SIZE: f64 = 320.0;
+const AXIS: f64 = 36.0;
+
+fn curve_points(curve: &[(f64, f64)]) -> String {
+    let plot = CHART_SIZE - AXIS;
+    curve
+        .iter()
+        .map(|(population, income)| format!("{:.1},{:.1}", AXIS + population * plot, plot - income * plot))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[component]
+fn LorenzChart(stats: PopulationStats) -> impl IntoView {
+    let plot = CHART_SIZE - AXIS;
+
+    view! {
+        <svg
+            class="simulation-chart lorenz-chart"
+            viewBox=format!("0 0 {} {}", CHART_SIZE, CHART_SIZE)
+            role="img"
+            aria-label="Lorenzkurve for inntekt før og etter skatt"
+        >
+            {[0.0, 0.5, 1.0].into_iter().map(|share| view! {
+                <g>
+                    <line class="simulation-grid" x1=AXIS x2=CHART_SIZE y1=plot - share * plot y2=plot - share * plot />
+                    <text class="simulation-axis" x=AXIS - 6.0 y=plot - share * plot + 4.0 text-anchor="end">
+                        {format!("{:.0}", Rate::from_fraction(share))}
+                    </text>
+                    <text class="simulation-axis" x=AXIS + share * plot y=CHART_SIZE - 6.0 text-anchor="middle">
+                        {format!("{:.0}", Rate::from_fraction(share))}
+                    </text>
+                </g>
+            }).collect::<Vec<_>>()}
+            <line class="lorenz-equality" x1=AXIS y1=plot x2=CHART_SIZE y2=0.0 />
+            <polyline class="policy-line policy-line-1" points=curve_points(&stats.lorenz_gross) />
+            <polyline class="policy-line policy-line-0" points=curve_points(&stats.lorenz_net) />
+        </svg>
+    }
+}
+
+// Distribution across an uploaded population, for reading the batch as a
+// sample rather than a client list
+#[component]
+pub fn PopulationStatsPanel(#[prop(into)] rows: Signal<Vec<BatchRow>>) -> impl IntoView {
+    let stats = create_memo(move |_| rows.with(|rows| population_stats(rows)));
+    let currency = use_currency();
+
+    move || stats.get().map(|stats| {
+        let currency = currency.get();
+        view! {
+            <details class="population-stats" open>
+                <summary>"Fordeling i utvalget"</summary>
+                <dl class="population-summary">
+                    <dt>"Personer og selskaper"</dt>
+                    <dd>{stats.clients}</dd>
+                    <dt>"Samlet skatteinngang"</dt>
+                    <dd>{currency.format(stats.total_tax)}</dd>
+                    <dt>"Gjennomsnittlig skattesats"</dt>
+                    <dd>{format!("{:.1}", stats.average_rate)}</dd>
+                    <dt>"Median skattesats"</dt>
+                    <dd>{format!("{:.1}", stats.median_rate)}</dd>
+                    <dt>"Gini før / etter skatt"</dt>
+                    <dd>{format!("{:.3} / {:.3}", stats.gini_gross, stats.gini_net)}</dd>
+                </dl>
+                <table class="analysis-table">
+                    <thead>
+                        <tr>
+                            <th>"Desil"</th>
+                            <th>"Antall"</th>
+                            <th>"Bruttoinntekt"</th>
+                            <th>"Skatt"</th>
+                            <th>"Andel av skatten"</th>
+                            <th>"Gj.snittlig sats"</th>
+                            <th>"Median sats"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {stats.deciles.iter().map(|decile| {
+                            let share = if stats.total_tax != Money::ZERO { Rate::from_fraction(decile.total_tax / stats.total_tax) } else { Rate::ZERO };
+                            view! {
+                                <tr>
+                                    <td>{decile.decile}</td>
+                                    <td>{decile.clients}</td>
+                                    <td>
+                                        {format!("{} – {}", currency.format(decile.lowest_income), currency.format(decile.highest_income))}
+                                    </td>
+                                    <td>{currency.format(decile.total_tax)}</td>
+                                    <td>{format!("{:.1}", share)}</td>
+                                    <td>{format!("{:.1}", decile.average_rate)}</td>
+                                    <td>{format!("{:.1}", decile.median_rate)}</td>
+                                </tr>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+                <LorenzChart stats=stats.clone() />
+                <ul class="policy-legend">
+                    <li class="policy-legend-1">"Før skatt"</li>
+                    <li class="policy-legend-0">"Etter skatt"</li>
+                </ul>
+                <p class="chart-note">
+                    "Desilene deler utvalget i ti like store grupper etter bruttoinntekt. Lorenzkurven viser hvor stor \
+                     andel av inntekten de fattigste har: jo nærmere diagonalen, jo jevnere fordeling. Utvalget er bare \
+                     så representativt som filen du lastet opp."
+                </p>
+            </details>
+        }
+    })
+}
@@ -1,82 +1,470 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
 use leptos::*;
-use crate::tax_calculator::{TaxCalculationResult, NorwegianTaxCalculator};
+use crate::tax_calculator::{BreakdownCategory, TaxBreakdownItem, TaxCalculationResult, TaxRule, TaxYear, NorwegianTaxCalculator};
+use crate::report::generate_pdf_report;
+use crate::download::download_bytes;
+use crate::comparisons::{default_comparisons, entity_title, toggle_comparison, Comparison, ENTITY_COMPARISONS};
+use crate::scenarios::{SavedScenario, ScenarioNotes};
+use crate::components::{use_currency, use_tween, InfoTooltip};
+use crate::money::Money;
+use crate::analysis::{tax_calendar, WORKING_DAYS_PER_YEAR};
+
+pub const TWEEN_DURATION_MS: f64 = 400.0;
 
 #[component]
-pub fn TaxResults(result: Memo<TaxCalculationResult>) -> impl IntoView {
+pub fn TaxResults(
+    result: Memo<TaxCalculationResult>,
+    #[prop(into)] comparisons: Signal<Vec<(String, TaxCalculationResult)>>,
+    notes: RwSignal<ScenarioNotes>,
+) -> impl IntoView {
+    // The report is built on the next tick so the button can show that
+    // it's working first; building it blocks the page for a moment
+    let (generating_pdf, set_generating_pdf) = create_signal(false);
+    let download_pdf = move |_| {
+        set_generating_pdf.set(true);
+        set_timeout(move || {
+            let report = generate_pdf_report(&result.get_untracked(), &comparisons.get_untracked(), &notes.get_untracked());
+            match report {
+                Ok(bytes) => {
+                    if let Err(err) = download_bytes("skatteberegning.pdf", "application/pdf", &bytes) {
+                        logging::error!("Kunne ikke laste ned PDF: {:?}", err);
+                    }
+                }
+                Err(err) => logging::error!("Kunne ikke lage PDF: {}", err),
+            }
+            set_generating_pdf.set(false);
+        }, Duration::ZERO);
+    };
+
+    let collapsed = create_rw_signal(HashSet::<BreakdownCategory>::new());
+    let currency = use_currency();
+    let animated_net_income = use_tween(Signal::derive(move || result.get().net_income.amount()), TWEEN_DURATION_MS);
+    let animated_effective_rate = use_tween(Signal::derive(move || result.get().effective_tax_rate.percent()), TWEEN_DURATION_MS);
+    // Wealth tax and one-off gains can make the headline rate say little
+    // about the tax on pay, so either can be left out of a second figure
+    let (exclude_wealth_tax, set_exclude_wealth_tax) = create_signal(false);
+    let (exclude_capital, set_exclude_capital) = create_signal(false);
+
+    let announcement = move || {
+        let res = result.get();
+        let currency = currency.get();
+        format!(
+            "Total skatt {}, nettoinntekt {}, effektiv skattesats {:.1} prosent",
+            currency.format(res.total_tax),
+            currency.format(res.net_income),
+            res.effective_tax_rate.percent(),
+        )
+    };
+
     view! {
-        <div class="results-container">
-            <h3>"Skatteberegning"</h3>
+        <div class="results-container" role="region" aria-labelledby="results-heading">
+            <div class="sr-only" aria-live="polite" aria-atomic="true">
+                {announcement}
+            </div>
+            <div class="results-header">
+                <h3 id="results-heading">"Skatteberegning"</h3>
+                <button class="export-button" disabled=move || generating_pdf.get() on:click=download_pdf>
+                    {move || if generating_pdf.get() { "Lager PDF …" } else { "Last ned PDF" }}
+                </button>
+            </div>
             
             <div class="result-item gross-income">
                 <span class="result-label">"Bruttoinntekt:"</span>
                 <span class="result-value income">
-                    {move || format!("{} NOK", NorwegianTaxCalculator::format_currency(result.get().gross_income))}
+                    {move || currency.get().format(result.get().gross_income)}
                 </span>
             </div>
+
+            <Show when=move || result.with(|res| res.personinntekt > Money::ZERO)>
+                <div class="result-item income-base">
+                    <span class="result-label">
+                        "Personinntekt:"
+                        <InfoTooltip rule=TaxRule::Personinntekt />
+                    </span>
+                    <span class="result-value">
+                        {move || currency.get().format(result.get().personinntekt)}
+                    </span>
+                </div>
+            </Show>
+            <div class="result-item income-base">
+                <span class="result-label">
+                    "Alminnelig inntekt:"
+                    <InfoTooltip rule=TaxRule::AlminneligInntekt />
+                </span>
+                <span class="result-value">
+                    {move || currency.get().format(result.get().alminnelig_inntekt)}
+                </span>
+            </div>
+
+            {move || result.with(|res| {
+                (!res.assumptions.is_empty()).then(|| view! {
+                    <details class="result-assumptions" open=true>
+                        <summary>"Forutsetninger"</summary>
+                        <ul>
+                            {res.assumptions.iter().map(|assumption| view! { <li>{assumption.clone()}</li> }).collect::<Vec<_>>()}
+                        </ul>
+                    </details>
+                })
+            })}
+
+            {move || result.with(|res| {
+                (!res.warnings.is_empty()).then(|| view! {
+                    <ul class="result-warnings" role="status">
+                        {res.warnings.iter().map(|warning| view! { <li>{warning.message()}</li> }).collect::<Vec<_>>()}
+                    </ul>
+                })
+            })}
             
             {move || {
                 let res = result.get();
-                res.breakdown.iter().map(|item| {
-                    let amount_str = if item.amount < 0.0 {
-                        format!("-{} NOK", NorwegianTaxCalculator::format_currency(-item.amount))
-                    } else {
-                        format!("{} NOK", NorwegianTaxCalculator::format_currency(item.amount))
-                    };
-                    
-                    let rate_str = item.rate.map(|rate| format!(" ({:.1}%)", rate)).unwrap_or_default();
-                    let is_deduction = item.amount < 0.0;
-                    let is_tax = item.amount > 0.0 && !item.description.contains("ENK") && !item.description.contains("Deltakerlignet");
-                    
-                    view! {
-                        <div class=format!("result-item {}", 
-                            if is_deduction { "deduction" } 
-                            else if is_tax { "tax" } 
-                            else { "" }
-                        )>
-                            <span class="result-label">{&item.description}{&rate_str}</span>
-                            <span class="result-value">{amount_str}</span>
-                        </div>
-                    }
+                BreakdownCategory::ALL.iter().filter_map(|&category| {
+                    let items = res.breakdown.iter()
+                        .filter(|item| item.category == category)
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    (!items.is_empty()).then(|| view! {
+                        <BreakdownSection category=category items=items collapsed=collapsed notes=notes />
+                    })
                 }).collect::<Vec<_>>()
             }}
             
             <div class="result-item net-income">
                 <span class="result-label">"Nettoinntekt:"</span>
                 <span class="result-value income">
-                    {move || format!("{} NOK", NorwegianTaxCalculator::format_currency(result.get().net_income))}
+                    {move || currency.get().format(animated_net_income.get())}
                 </span>
             </div>
             
             <div class="result-item effective-rate">
                 <span class="result-label">"Effektiv skattesats:"</span>
                 <span class="result-value rate">
-                    {move || format!("{:.1}%", result.get().effective_tax_rate)}
+                    {move || format!("{:.1}%", animated_effective_rate.get())}
                 </span>
             </div>
+
+            <Show when=move || result.with(|res| res.wealth_tax > Money::ZERO || res.capital_income != Money::ZERO)>
+                <div class="effective-rate-options">
+                    <label class="checkbox-label">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || exclude_wealth_tax.get()
+                            on:change=move |ev| set_exclude_wealth_tax.set(event_target_checked(&ev))
+                        />
+                        "Uten formueskatt"
+                    </label>
+                    <label class="checkbox-label">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || exclude_capital.get()
+                            on:change=move |ev| set_exclude_capital.set(event_target_checked(&ev))
+                        />
+                        "Uten utbytte og gevinster"
+                    </label>
+                </div>
+                <Show when=move || exclude_wealth_tax.get() || exclude_capital.get()>
+                    <div class="result-item effective-rate-adjusted">
+                        <span class="result-label">"Justert effektiv skattesats:"</span>
+                        <span class="result-value rate">
+                            {move || format!(
+                                "{:.1}",
+                                result.with(|res| res.effective_tax_rate_excluding(exclude_wealth_tax.get(), exclude_capital.get()))
+                            )}
+                        </span>
+                    </div>
+                </Show>
+            </Show>
+
+            <Show when=move || result.with(|res| res.pension_accrual > Money::ZERO)>
+                <div class="result-item pension-accrual">
+                    <span class="result-label">
+                        "Pensjonsopptjening (estimat):"
+                        <InfoTooltip rule=TaxRule::PensionAccrual />
+                    </span>
+                    <span class="result-value">
+                        {move || currency.get().format(result.get().pension_accrual)}
+                    </span>
+                </div>
+            </Show>
+        </div>
+    }
+}
+
+// The yearly tax put in terms that are easier to picture than a total
+#[component]
+pub fn TaxCalendarCard(result: Memo<TaxCalculationResult>) -> impl IntoView {
+    let calendar = Signal::derive(move || result.with(tax_calendar));
+    let currency = use_currency();
+
+    view! {
+        <div class="tax-calendar" role="region" aria-label="Skatten fordelt over året">
+            <div class="tax-calendar-item">
+                <span class="tax-calendar-label">"Skatt per måned"</span>
+                <span class="tax-calendar-value">{move || currency.get().format(calendar.get().per_month)}</span>
+            </div>
+            <div class="tax-calendar-item">
+                <span class="tax-calendar-label">"Skatt per arbeidsdag"</span>
+                <span class="tax-calendar-value">{move || currency.get().format(calendar.get().per_working_day)}</span>
+            </div>
+            <div class="tax-calendar-item">
+                <span class="tax-calendar-label">"Skattefri fra"</span>
+                <span class="tax-calendar-value">{move || calendar.get().freedom_day_label()}</span>
+            </div>
+            <p class="chart-note">
+                {format!(
+                    "Om all skatten for året ble betalt først, ville du fra denne datoen jobbe for deg selv. \
+                     Skatt per arbeidsdag regner med {} arbeidsdager i året.",
+                    WORKING_DAYS_PER_YEAR
+                )}
+            </p>
         </div>
     }
 }
 
+#[component]
+fn BreakdownSection(
+    category: BreakdownCategory,
+    items: Vec<TaxBreakdownItem>,
+    collapsed: RwSignal<HashSet<BreakdownCategory>>,
+    notes: RwSignal<ScenarioNotes>,
+) -> impl IntoView {
+    let is_open = move || !collapsed.with(|set| set.contains(&category));
+    let toggle = move |_| collapsed.update(|set| {
+        if !set.remove(&category) {
+            set.insert(category);
+        }
+    });
+    let subtotal: Money = items.iter().map(|item| item.amount).sum();
+    let currency = use_currency();
+    let section_id = format!("breakdown-{:?}", category).to_lowercase();
+
+    view! {
+        <div class="breakdown-section">
+            <button
+                class="breakdown-section-header"
+                aria-expanded=move || is_open().to_string()
+                aria-controls=section_id.clone()
+                on:click=toggle
+            >
+                <span class="breakdown-section-title">
+                    <span class="breakdown-chevron">{move || if is_open() { "▾" } else { "▸" }}</span>
+                    {category.label()}
+                </span>
+                {(category != BreakdownCategory::Info).then(|| view! {
+                    <span class="breakdown-subtotal">{move || currency.get().format(subtotal)}</span>
+                })}
+            </button>
+            <div id=section_id class="breakdown-section-items" hidden=move || !is_open()>
+                {items.into_iter().map(|item| view! { <BreakdownRow item=item notes=notes /> }).collect::<Vec<_>>()}
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn BreakdownRow(item: TaxBreakdownItem, notes: RwSignal<ScenarioNotes>) -> impl IntoView {
+    let rate_str = item.rate.map(|rate| format!(" ({:.1})", rate)).unwrap_or_default();
+    let is_deduction = item.amount < Money::ZERO;
+    let is_tax = item.amount > Money::ZERO && item.category != BreakdownCategory::Info;
+    let amount = item.amount;
+    let is_estimate = item.is_estimate;
+    let currency = use_currency();
+    let id = item.id.clone();
+    let note = Signal::derive(move || notes.with(|notes| notes.line(&id).unwrap_or_default().to_string()));
+    let (editing_note, set_editing_note) = create_signal(false);
+    let show_note = move || editing_note.get() || !note.with(String::is_empty);
+    let note_id = store_value(item.id.clone());
+    let save_note = move |ev| {
+        let text = event_target_value(&ev);
+        note_id.with_value(|id| notes.update(|notes| notes.set_line(id, &text)));
+        set_editing_note.set(false);
+    };
+
+    view! {
+        <div data-id=item.id class=format!("result-item {}{}", 
+            if is_deduction { "deduction" } 
+            else if is_tax { "tax" } 
+            else { "" },
+            if is_estimate { " estimate" } else { "" }
+        )>
+            <span class="result-label">
+                {item.description}{rate_str}
+                {is_estimate.then(|| view! {
+                    <span
+                        class="estimate-marker"
+                        title="Anslag: regelen er forenklet, og beløpet kan avvike fra skatteoppgjøret"
+                    >
+                        "≈"
+                    </span>
+                })}
+                <InfoTooltip rule=item.rule />
+                <button
+                    class="breakdown-note-button"
+                    title="Notat til posten"
+                    aria-label="Notat til posten"
+                    aria-pressed=move || show_note().to_string()
+                    on:click=move |_| set_editing_note.update(|editing| *editing = !*editing)
+                >
+                    "✎"
+                </button>
+            </span>
+            <span class="result-value">{move || currency.get().format(amount)}</span>
+        </div>
+        <Show when=show_note>
+            <input
+                type="text"
+                class="input-field breakdown-note"
+                aria-label="Notat til posten"
+                placeholder="For eksempel: sjekk fradraget med regnskapsfører"
+                prop:value=move || note.get()
+                on:change=save_note
+            />
+        </Show>
+    }
+}
+
 #[component]
 pub fn ComparisonCard(
-    title: String, 
-    result: Memo<TaxCalculationResult>
+    #[prop(into)] title: Signal<String>,
+    #[prop(into)] result: Signal<TaxCalculationResult>,
+    #[prop(into)] is_active: Signal<bool>,
+    on_select: impl Fn() + Copy + 'static,
+    on_adopt: impl Fn() + 'static,
+    #[prop(optional)] children: Option<Children>,
 ) -> impl IntoView {
+    let currency = use_currency();
+
     view! {
-        <div class="comparison-card">
-            <h3>{title}</h3>
+        <div
+            class=move || if is_active.get() { "comparison-card comparison-card-active" } else { "comparison-card" }
+            on:click=move |_| on_select()
+        >
+            <h3>
+                <button
+                    class="comparison-card-select"
+                    aria-pressed=move || is_active.get().to_string()
+                    on:click=move |ev| {
+                        ev.stop_propagation();
+                        on_select();
+                    }
+                >
+                    {move || title.get()}
+                </button>
+            </h3>
             <div class="result-row">
                 <span>"Total skatt:"</span>
-                <span>{move || format!("{} NOK", NorwegianTaxCalculator::format_currency(result.get().total_tax))}</span>
+                <span>{move || currency.get().format(result.get().total_tax)}</span>
             </div>
             <div class="result-row">
                 <span>"Nettoinntekt:"</span>
-                <span class="nok">{move || format!("{} NOK", NorwegianTaxCalculator::format_currency(result.get().net_income))}</span>
+                <span class="nok">{move || currency.get().format(result.get().net_income)}</span>
             </div>
             <div class="result-row">
                 <span>"Effektiv skattesats:"</span>
-                <span>{move || format!("{:.1}%", result.get().effective_tax_rate)}</span>
+                <span>{move || format!("{:.1}", result.get().effective_tax_rate)}</span>
             </div>
+            <button
+                class="comparison-card-adopt"
+                title="Bytt til dette alternativet og bruk verdiene fra sammenligningen"
+                on:click=move |ev| {
+                    ev.stop_propagation();
+                    on_adopt();
+                }
+            >
+                "Bytt og bruk verdiene"
+            </button>
+            {children.map(|children| children())}
         </div>
     }
+}
+
+#[component]
+pub fn ComparisonSettings(
+    comparisons: RwSignal<Vec<Comparison>>,
+    #[prop(into)] scenarios: Signal<Vec<SavedScenario>>,
+) -> impl IntoView {
+    // Saved scenarios plus any shown scenario that has since been deleted,
+    // so it can still be switched off
+    let scenario_options = move || {
+        let mut options = scenarios.get();
+        comparisons.with(|list| {
+            for comparison in list {
+                if let Comparison::Scenario(scenario) = comparison {
+                    if !options.contains(scenario) {
+                        options.push(scenario.as_ref().clone());
+                    }
+                }
+            }
+        });
+        options
+    };
+
+    let option = move |label: String, comparison: Comparison| {
+        let toggled = comparison.clone();
+        view! {
+            <label class="checkbox-label">
+                <input
+                    type="checkbox"
+                    prop:checked=move || comparisons.with(|list| list.contains(&comparison))
+                    on:change=move |_| comparisons.update(|list| toggle_comparison(list, toggled.clone()))
+                />
+                {label}
+            </label>
+        }
+    };
+
+    view! {
+        <details class="comparison-settings">
+            <summary>"Tilpass sammenligning"</summary>
+            <div class="comparison-settings-panel">
+                <strong>"Selskapsformer"</strong>
+                {ENTITY_COMPARISONS.into_iter()
+                    .map(|entity| option(entity_title(entity).to_string(), Comparison::Entity(entity)))
+                    .collect::<Vec<_>>()}
+                <strong>"Egne scenarioer"</strong>
+                {move || {
+                    let options = scenario_options();
+                    if options.is_empty() {
+                        view! { <span class="chart-note">"Lagre et scenario for å sammenligne det her."</span> }.into_view()
+                    } else {
+                        options.into_iter()
+                            .map(|scenario| option(scenario.name.clone(), Comparison::Scenario(Box::new(scenario))))
+                            .collect::<Vec<_>>()
+                            .into_view()
+                    }
+                }}
+                <button class="history-button" on:click=move |_| comparisons.set(default_comparisons())>
+                    "Tilbakestill"
+                </button>
+            </div>
+        </details>
+    }
+}
+
+#[component]
+pub fn RulesetFooter(#[prop(into)] tax_year: Signal<TaxYear>) -> impl IntoView {
+    view! {
+        <footer class="calculator-footer">
+            <span>
+                {move || format!(
+                    "Beregnet med regelverk {} for inntektsåret {} (G = {})",
+                    NorwegianTaxCalculator::ruleset_version(tax_year.get()),
+                    tax_year.get().year(),
+                    NorwegianTaxCalculator::format_nok(tax_year.get().rules().grunnbeloep.amount()),
+                )}
+            </span>
+            <details class="ruleset-changes">
+                <summary>"Hva er endret"</summary>
+                {TaxYear::ALL.into_iter().rev().map(|year| view! {
+                    <div class="ruleset-change">
+                        <strong>{NorwegianTaxCalculator::ruleset_version(year)}</strong>
+                        <ul>
+                            {year.rules().changes.iter().map(|change| view! { <li>{*change}</li> }).collect::<Vec<_>>()}
+                        </ul>
+                    </div>
+                }).collect::<Vec<_>>()}
+            </details>
+        </footer>
+    }
 }
\ No newline at end of file
@@ -0,0 +1,142 @@
+use leptos::*;
+use crate::comparisons::{entity_title, ENTITY_COMPARISONS};
+use crate::constraints::{applies, InputKey};
+use crate::money::Money;
+use crate::scenarios::{SavedScenario, ScenarioNotes};
+use crate::tax_calculator::TaxCalculationInput;
+
+#[component]
+pub fn SavedScenarioList(
+    scenarios: RwSignal<Vec<SavedScenario>>,
+    // Notes on the current calculation, saved with the next scenario
+    notes: RwSignal<ScenarioNotes>,
+    on_save: impl Fn() + 'static,
+    on_load: impl Fn(SavedScenario) + Copy + 'static,
+) -> impl IntoView {
+    view! {
+        <div class="saved-scenarios">
+            <button class="history-button" title="Lagre scenario (S)" on:click=move |_| on_save()>
+                "Lagre scenario"
+            </button>
+            {move || scenarios.get().into_iter().enumerate().map(|(index, scenario)| {
+                let name = scenario.name.clone();
+                let notes = scenario.notes.text.clone();
+                view! {
+                    <span class="saved-scenario">
+                        <button
+                            class="saved-scenario-load"
+                            title=(!notes.is_empty()).then_some(notes)
+                            on:click=move |_| on_load(scenario.clone())
+                        >
+                            {name}
+                        </button>
+                        <button
+                            class="saved-scenario-delete"
+                            aria-label="Slett scenario"
+                            on:click=move |_| scenarios.update(|list| { list.remove(index); })
+                        >
+                            "×"
+                        </button>
+                    </span>
+                }
+            }).collect::<Vec<_>>()}
+            <details class="scenario-notes" open=notes.with_untracked(|notes| !notes.text.is_empty())>
+                <summary>"Notater"</summary>
+                <textarea
+                    class="input-field scenario-notes-text"
+                    aria-label="Notater til beregningen"
+                    placeholder="Notater til beregningen. De lagres med scenarioet."
+                    prop:value=move || notes.with(|notes| notes.text.clone())
+                    on:change=move |ev| notes.update(|notes| notes.text = event_target_value(&ev))
+                />
+            </details>
+        </div>
+    }
+}
+
+// Inline editor for a scenario comparison card; changes apply to the card
+// only, never to the input being edited above
+#[component]
+pub fn ScenarioEditor(
+    #[prop(into)] scenario: Signal<SavedScenario>,
+    on_change: impl Fn(SavedScenario) + Copy + 'static,
+    on_remove: impl Fn() + 'static,
+) -> impl IntoView {
+    let update = move |edit: &dyn Fn(&mut SavedScenario)| {
+        let mut edited = scenario.get_untracked();
+        edit(&mut edited);
+        on_change(edited);
+    };
+    let amount_field = move |label: &'static str, get: fn(&TaxCalculationInput) -> Money, set: fn(&mut TaxCalculationInput, Money)| {
+        view! {
+            <label class="simulation-parameter">
+                {label}
+                <input
+                    type="number"
+                    class="input-field"
+                    min="0"
+                    step="10000"
+                    prop:value=move || scenario.with(|s| get(&s.input).amount())
+                    on:input=move |ev| {
+                        if let Ok(amount) = event_target_value(&ev).parse::<f64>() {
+                            update(&|s| set(&mut s.input, Money(amount.max(0.0))));
+                        }
+                    }
+                />
+            </label>
+        }
+    };
+
+    view! {
+        <details class="scenario-editor" on:click=|ev| ev.stop_propagation()>
+            <summary>"Rediger"</summary>
+            <label class="simulation-parameter">
+                "Navn"
+                <input
+                    type="text"
+                    class="input-field"
+                    prop:value=move || scenario.with(|s| s.name.clone())
+                    on:input=move |ev| {
+                        let name = event_target_value(&ev);
+                        update(&|s| s.name = name.clone());
+                    }
+                />
+            </label>
+            <label class="simulation-parameter">
+                "Selskapsform"
+                <select
+                    class="input-field"
+                    on:change=move |ev| {
+                        let index = event_target_value(&ev).parse::<usize>().unwrap_or(0);
+                        if let Some(&entity) = ENTITY_COMPARISONS.get(index) {
+                            update(&|s| s.input = s.input.comparison_variant(entity));
+                        }
+                    }
+                >
+                    {ENTITY_COMPARISONS.into_iter().enumerate().map(|(index, entity)| view! {
+                        <option value=index selected=move || scenario.with(|s| s.input.entity_type == entity)>
+                            {entity_title(entity)}
+                        </option>
+                    }).collect::<Vec<_>>()}
+                </select>
+            </label>
+            <label class="simulation-parameter">
+                "Notater"
+                <textarea
+                    class="input-field scenario-notes-text"
+                    prop:value=move || scenario.with(|s| s.notes.text.clone())
+                    on:change=move |ev| {
+                        let text = event_target_value(&ev);
+                        update(&|s| s.notes.text = text.clone());
+                    }
+                />
+            </label>
+            {amount_field("Bruttoinntekt", |i| i.gross_income, |i, v| i.gross_income = v)}
+            {amount_field("Fradrag", |i| i.allowable_deductions, |i, v| i.allowable_deductions = v)}
+            {move || scenario.with(|s| applies(InputKey::BusinessExpenses, &s.input)).then(|| {
+                amount_field("Driftskostnader", |i| i.business_expenses, |i, v| i.business_expenses = v)
+            })}
+            <button class="saved-scenario-delete" on:click=move |_| on_remove()>"Fjern scenario"</button>
+        </details>
+    }
+}
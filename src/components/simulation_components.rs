@@ -0,0 +1,165 @@
+use leptos::*;
+use crate::money::{Money, Rate};
+use crate::simulation::{simulate_investment_outcomes, PercentileBand, SimulationParameters, SimulationResult};
+use crate::tax_calculator::{NorwegianTaxCalculator, TaxCalculationInput};
+
+const CHART_WIDTH: f64 = 640.0;
+const CHART_HEIGHT: f64 = 240.0;
+const AXIS_WIDTH: f64 = 90.0;
+const AXIS_HEIGHT: f64 = 24.0;
+
+fn band_polygon(
+    result: &SimulationResult,
+    x: impl Fn(usize) -> f64,
+    y: impl Fn(Money) -> f64,
+    lower: fn(&PercentileBand) -> Money,
+    upper: fn(&PercentileBand) -> Money,
+) -> String {
+    let upper_points = result.bands.iter().map(|band| format!("{:.1},{:.1}", x(band.year), y(upper(band))));
+    let lower_points = result.bands.iter().rev().map(|band| format!("{:.1},{:.1}", x(band.year), y(lower(band))));
+    upper_points.chain(lower_points).collect::<Vec<_>>().join(" ")
+}
+
+#[component]
+fn SimulationChart(result: SimulationResult) -> impl IntoView {
+    let max_year = result.bands.last().map(|band| band.year).unwrap_or(1).max(1);
+    let max_value = result.bands.iter().map(|band| band.p90).fold(Money(1.0), Money::max);
+    let plot_width = CHART_WIDTH - AXIS_WIDTH;
+    let plot_height = CHART_HEIGHT - AXIS_HEIGHT;
+    let x = move |year: usize| AXIS_WIDTH + year as f64 / max_year as f64 * plot_width;
+    let y = move |value: Money| plot_height - value / max_value * plot_height;
+
+    let outer = band_polygon(&result, x, y, |band| band.p10, |band| band.p90);
+    let inner = band_polygon(&result, x, y, |band| band.p25, |band| band.p75);
+    let median = result.bands.iter()
+        .map(|band| format!("{:.1},{:.1}", x(band.year), y(band.p50)))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    view! {
+        <svg
+            class="simulation-chart"
+            viewBox=format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT)
+            role="img"
+            aria-label="Persentilbånd for formue etter skatt per år"
+        >
+            {[0.0, 0.5, 1.0].into_iter().map(|fraction| {
+                let value = max_value * fraction;
+                view! {
+                    <g>
+                        <line class="simulation-grid" x1=AXIS_WIDTH x2=CHART_WIDTH y1=y(value) y2=y(value) />
+                        <text class="simulation-axis" x=AXIS_WIDTH - 6.0 y=y(value) + 4.0 text-anchor="end">
+                            {NorwegianTaxCalculator::format_currency(value)}
+                        </text>
+                    </g>
+                }
+            }).collect::<Vec<_>>()}
+            <polygon class="simulation-band-outer" points=outer />
+            <polygon class="simulation-band-inner" points=inner />
+            <polyline class="simulation-median" points=median />
+            {result.bands.iter().map(|band| view! {
+                <text class="simulation-axis" x=x(band.year) y=CHART_HEIGHT - 6.0 text-anchor="middle">
+                    {band.year}
+                </text>
+            }).collect::<Vec<_>>()}
+        </svg>
+    }
+}
+
+#[component]
+pub fn MonteCarloPanel(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let (enabled, set_enabled) = create_signal(false);
+    let parameters = create_rw_signal(SimulationParameters::default());
+
+    let result = create_memo(move |_| {
+        enabled.get().then(|| simulate_investment_outcomes(&input.get(), &parameters.get()))
+    });
+
+    let percent_input = move |label: &'static str, get: fn(&SimulationParameters) -> Rate, set: fn(&mut SimulationParameters, Rate)| view! {
+        <label class="simulation-parameter">
+            {label}
+            <input
+                type="number"
+                class="input-field"
+                step="0.5"
+                prop:value=move || format!("{:.1}", parameters.with(get).percent())
+                on:input=move |ev| {
+                    if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                        parameters.update(|p| set(p, Rate::from_percent(value)));
+                    }
+                }
+            />
+        </label>
+    };
+
+    view! {
+        <div class="chart-container">
+            <h4>"Simulering av investeringer"</h4>
+            <label class="checkbox-label">
+                <input
+                    type="checkbox"
+                    prop:checked=move || enabled.get()
+                    on:change=move |ev| set_enabled.set(event_target_checked(&ev))
+                />
+                "Stokastisk simulering av formue etter skatt"
+            </label>
+            <Show when=move || enabled.get()>
+                <div class="simulation-parameters">
+                    <label class="simulation-parameter">
+                        "År"
+                        <input
+                            type="number"
+                            class="input-field"
+                            min="1"
+                            max="50"
+                            prop:value=move || parameters.with(|p| p.years)
+                            on:input=move |ev| {
+                                if let Ok(years) = event_target_value(&ev).parse::<usize>() {
+                                    parameters.update(|p| p.years = years.clamp(1, 50));
+                                }
+                            }
+                        />
+                    </label>
+                    <label class="simulation-parameter">
+                        "Antall baner"
+                        <input
+                            type="number"
+                            class="input-field"
+                            min="10"
+                            max="5000"
+                            step="100"
+                            prop:value=move || parameters.with(|p| p.paths)
+                            on:input=move |ev| {
+                                if let Ok(paths) = event_target_value(&ev).parse::<usize>() {
+                                    parameters.update(|p| p.paths = paths.clamp(10, 5000));
+                                }
+                            }
+                        />
+                    </label>
+                    {percent_input("Forventet avkastning (%)", |p| p.expected_return, |p, v| p.expected_return = v)}
+                    {percent_input("Volatilitet (%)", |p| p.volatility, |p, v| p.volatility = v)}
+                    {percent_input("Utbytteandel (%)", |p| p.dividend_yield, |p, v| p.dividend_yield = v)}
+                </div>
+                {move || result.get().map(|result| {
+                    let last = result.bands.last().cloned();
+                    let median_total_tax = result.median_total_tax;
+                    view! {
+                        <SimulationChart result=result />
+                        {last.map(|band| view! {
+                            <p class="chart-note">
+                                {format!(
+                                    "Etter {} år: median {} (10.–90. persentil {} – {}). Median samlet skatt {}.",
+                                    band.year,
+                                    NorwegianTaxCalculator::format_nok(band.p50),
+                                    NorwegianTaxCalculator::format_nok(band.p10),
+                                    NorwegianTaxCalculator::format_nok(band.p90),
+                                    NorwegianTaxCalculator::format_nok(median_total_tax),
+                                )}
+                            </p>
+                        })}
+                    }
+                })}
+            </Show>
+        </div>
+    }
+}
@@ -0,0 +1,115 @@
+use leptos::*;
+use crate::download::download_bytes;
+use crate::skattemelding::{export_posts, import_skattemelding, posts_to_csv, posts_to_json, supports_export, SkattemeldingImport};
+use crate::tax_calculator::{NorwegianTaxCalculator, TaxCalculationInput};
+use crate::upload::{read_file_text, selected_file};
+
+#[component]
+pub fn SkattemeldingImporter(
+    #[prop(into)] base: Signal<TaxCalculationInput>,
+    on_import: impl Fn(TaxCalculationInput) + Copy + 'static,
+) -> impl IntoView {
+    let (summary, set_summary) = create_signal(None::<Result<SkattemeldingImport, String>>);
+
+    let on_file = move |ev: ev::Event| {
+        let Some(file) = selected_file(&ev) else { return };
+
+        spawn_local(async move {
+            let imported = read_file_text(file)
+                .await
+                .and_then(|text| import_skattemelding(&text, &base.get_untracked()).map_err(|err| err.to_string()));
+            if let Ok(import) = &imported {
+                on_import(import.input.clone());
+            }
+            set_summary.set(Some(imported));
+        });
+    };
+
+    view! {
+        <div class="preset-selector skattemelding-import">
+            <label for="skattemelding-file">"Importer skattemelding (JSON/XML)"</label>
+            <input id="skattemelding-file" type="file" accept=".json,.xml,application/json,text/xml" on:change=on_file />
+            {move || summary.get().map(|summary| match summary {
+                Ok(import) => view! {
+                    <div class="import-summary" role="status">
+                        <ul>
+                            {import.applied.into_iter().map(|post| view! {
+                                <li>{format!("Post {} {}: {}", post.post, post.label, NorwegianTaxCalculator::format_nok(post.amount))}</li>
+                            }).collect::<Vec<_>>()}
+                        </ul>
+                        {(!import.ignored.is_empty()).then(|| view! {
+                            <p class="chart-note">{format!("Ikke brukt i beregningen: {}", import.ignored.join(", "))}</p>
+                        })}
+                    </div>
+                }.into_view(),
+                Err(message) => view! { <p class="batch-error" role="alert">{message}</p> }.into_view(),
+            })}
+        </div>
+    }
+}
+
+#[component]
+pub fn SkattemeldingExport(#[prop(into)] input: Signal<TaxCalculationInput>) -> impl IntoView {
+    let posts = create_memo(move |_| input.with(export_posts));
+    let supported = move || input.with(|i| supports_export(i.entity_type));
+
+    let download = move |filename: &'static str, mime_type: &'static str, bytes: Result<Vec<u8>, String>| {
+        let result = bytes.and_then(|bytes| download_bytes(filename, mime_type, &bytes).map_err(|err| format!("{:?}", err)));
+        if let Err(err) = result {
+            logging::error!("Kunne ikke eksportere poster: {}", err);
+        }
+    };
+
+    view! {
+        <div class="chart-container">
+            <h4>"Poster i skattemeldingen"</h4>
+            <Show
+                when=supported
+                fallback=|| view! {
+                    <p class="chart-note">"Eksport til skattemeldingen er bare tilgjengelig for personer."</p>
+                }
+            >
+                <table class="analysis-table">
+                    <thead>
+                        <tr>
+                            <th>"Post"</th>
+                            <th>"Beskrivelse"</th>
+                            <th>"Beløp"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || posts.get().into_iter().map(|post| view! {
+                            <tr>
+                                <td>{post.post}</td>
+                                <td>{post.label}</td>
+                                <td>{NorwegianTaxCalculator::format_nok(post.amount)}</td>
+                            </tr>
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+                <div class="batch-actions">
+                    <button
+                        class="export-button"
+                        on:click=move |_| download(
+                            "skattemelding-poster.json",
+                            "application/json",
+                            posts_to_json(&input.get_untracked()).map_err(|err| err.to_string()),
+                        )
+                    >
+                        "Last ned JSON"
+                    </button>
+                    <button
+                        class="export-button"
+                        on:click=move |_| download(
+                            "skattemelding-poster.csv",
+                            "text/csv",
+                            posts_to_csv(&input.get_untracked()).map_err(|err| err.to_string()),
+                        )
+                    >
+                        "Last ned CSV"
+                    </button>
+                </div>
+            </Show>
+        </div>
+    }
+}
\ No newline at end of file
@@ -1,123 +1,477 @@
 use leptos::*;
+use wasm_bindgen::JsCast;
 use crate::tax_calculator::*;
-use crate::components::{EntityTab, InputField, TaxRateField, CheckboxField, TaxResults, ComparisonCard};
+use crate::presets::{presets, find_preset};
+use crate::history::{AuditLog, InputHistory, ResultSnapshot, SnapshotHistory};
+use crate::storage;
+use crate::routing::{sync_url_route, use_url_route, Route};
+use crate::municipalities::{cached_or_bundled, Municipality};
+#[cfg(feature = "integrations")]
+use crate::municipalities::{refresh_municipalities, ONLINE_MODE_KEY};
+#[cfg(feature = "integrations")]
+use crate::error_reports;
+use crate::currency::{CurrencySettings, CURRENCY_KEY};
+use crate::money::Money;
+use crate::scenarios::{load_notes, load_scenarios, next_scenario_name, save_notes, save_scenarios, SavedScenario};
+use crate::comparisons::{add_scenario, load_comparisons, save_comparisons, Comparison};
+use crate::components::{
+    entity_tab_id, use_keyboard_shortcuts, use_tween, TWEEN_DURATION_MS, ENTITY_PANEL_ID, EntityTab, DynamicForm, InconsistencyWarnings,
+    PresetSelector, InputSectionGroup, ConfirmDialog, ShortcutsOverlay, SavedScenarioList, Shortcut, TaxResults, TaxCalendarCard,
+    ComparisonCard, ComparisonSettings, ScenarioEditor, SensitivityTable,
+    TaxYearSelector, YearComparisonTable, LastYearComparisonPanel, MunicipalWealthTaxComparison, SpouseWealthAllocationPanel, IpsComparisonPanel, RulesetFooter, AccountantBatchPanel,
+    StudentPanel, DualEmploymentPanel, UnemploymentPanel, ParentalLeavePanel,
+    ClientManager, MunicipalitySelector, CurrencySelector,
+    EntityBenefitComparison, TargetNetIncomePanel,
+    SalaryNegotiationPanel, IncomeSmoothingPanel,
+    SnapshotHistoryPanel, AuditLogPanel, BackupPanel, CustomTaxEditor, PolicyPlayground, IncomeBenchmarkPanel, Deferred,
+};
+#[cfg(feature = "vat")]
+use crate::components::HourlyRatePanel;
+#[cfg(feature = "employer-cost")]
+use crate::components::{BonusPanel, EmployerCostPanel};
+#[cfg(feature = "charts")]
+use crate::components::{BracketVisualizer, DistributionPlanner, MonteCarloPanel, WaterfallChart};
+#[cfg(feature = "integrations")]
+use crate::components::{use_host_messages, SkattemeldingExport, SkattemeldingImporter};
 
+const HISTORY_LIMIT: usize = 100;
+const SNAPSHOT_LIMIT: usize = 20;
+const AUDIT_LOG_LIMIT: usize = 500;
+
+const ENTITY_TABS: [EntityType; 6] = [
+    EntityType::Individual,
+    EntityType::Corporation,
+    EntityType::Partnership,
+    EntityType::SoleProprietorship,
+    EntityType::ForeignBranch,
+    EntityType::Nonprofit,
+];
+
+fn focus_element(id: &str) {
+    if let Some(element) = document().get_element_by_id(id) {
+        if let Ok(element) = element.dyn_into::<web_sys::HtmlElement>() {
+            let _ = element.focus();
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ResetTarget {
+    All,
+    Section(InputSection),
+}
+
+// When embedded in another page the calculator starts from the given input
+// and leaves the host's URL, storage and keyboard alone.
 #[component]
-pub fn TaxCalculator() -> impl IntoView {
-    let (gross_income, set_gross_income) = create_signal(600000.0);
-    let (entity_type, set_entity_type) = create_signal(EntityType::Individual);
-    let (municipal_tax_rate, set_municipal_tax_rate) = create_signal(10.0);
-    let (county_tax_rate, set_county_tax_rate) = create_signal(11.4);
-    let (church_tax_rate, set_church_tax_rate) = create_signal(1.3);
-    let (is_church_member, set_is_church_member) = create_signal(true);
-    let (allowable_deductions, set_allowable_deductions) = create_signal(0.0);
-    let (active_tab, set_active_tab) = create_signal(EntityType::Individual);
-    
-    // Investment and business fields
-    let (dividend_income, set_dividend_income) = create_signal(0.0);
-    let (capital_gains, set_capital_gains) = create_signal(0.0);
-    let (investment_wealth, set_investment_wealth) = create_signal(0.0);
-    let (business_expenses, set_business_expenses) = create_signal(0.0);
+pub fn TaxCalculator(
+    #[prop(optional)] embedded: bool,
+    #[prop(optional)] initial_input: Option<TaxCalculationInput>,
+    #[prop(optional, into)] on_calculate: Option<Callback<TaxCalculationResult>>,
+) -> impl IntoView {
+    let restored_input = if embedded { None } else { storage::load::<TaxCalculationInput>(storage::SESSION_KEY) };
+    let (session_restored, set_session_restored) = create_signal(restored_input.is_some());
+    let mut initial_input = restored_input.or(initial_input).unwrap_or_default();
+    let url_route = (!embedded).then(use_url_route);
+    // A deep link decides the initial tab, overriding the restored session
+    if let Some(Route::Entity(route_entity)) = url_route.and_then(|route| route.get_untracked()) {
+        initial_input.entity_type = route_entity;
+    }
+    let input = create_rw_signal(initial_input);
+    let history = create_rw_signal(InputHistory::new(input.get_untracked(), HISTORY_LIMIT));
 
-    let calculation_result = create_memo(move |_| {
-        let input = TaxCalculationInput {
-            gross_income: gross_income.get(),
-            entity_type: entity_type.get(),
-            municipal_tax_rate: municipal_tax_rate.get(),
-            county_tax_rate: county_tax_rate.get(),
-            church_tax_rate: church_tax_rate.get(),
-            is_church_member: is_church_member.get(),
-            allowable_deductions: allowable_deductions.get(),
-            dividend_income: dividend_income.get(),
-            capital_gains: capital_gains.get(),
-            investment_wealth: investment_wealth.get(),
-            business_expenses: business_expenses.get(),
+    let (entity_type, set_entity_type) = create_slice(input, |i| i.entity_type, |i, v| i.entity_type = v);
+    let (tax_year, set_tax_year) = create_slice(input, |i| i.tax_year, |i, v| i.tax_year = v);
+
+    if let Some(url_route) = url_route {
+        sync_url_route(move || Route::Entity(entity_type.get()));
+        create_effect(move |_| match url_route.get() {
+            Some(Route::Entity(route_entity)) => set_entity_type.set(route_entity),
+            None => {}
+        });
+    }
+
+
+    let on_tab_keydown = move |ev: web_sys::KeyboardEvent| {
+        let current = ENTITY_TABS
+            .iter()
+            .position(|&tab| tab == entity_type.get_untracked())
+            .unwrap_or(0);
+        let next = match ev.key().as_str() {
+            "ArrowRight" => (current + 1) % ENTITY_TABS.len(),
+            "ArrowLeft" => (current + ENTITY_TABS.len() - 1) % ENTITY_TABS.len(),
+            "Home" => 0,
+            "End" => ENTITY_TABS.len() - 1,
+            _ => return,
         };
-        NorwegianTaxCalculator::calculate_tax(&input)
+        ev.prevent_default();
+        set_entity_type.set(ENTITY_TABS[next]);
+        focus_element(entity_tab_id(ENTITY_TABS[next]));
+    };
+
+    // Every distinct input state becomes an undo step
+    create_effect(move |_| {
+        let current = input.get();
+        history.update(|h| h.record(current));
     });
 
-    // Comparison calculations for different entity types
-    let individual_result = create_memo(move |_| {
-        let input = TaxCalculationInput {
-            gross_income: gross_income.get(),
-            entity_type: EntityType::Individual,
-            municipal_tax_rate: municipal_tax_rate.get(),
-            county_tax_rate: county_tax_rate.get(),
-            church_tax_rate: church_tax_rate.get(),
-            is_church_member: is_church_member.get(),
-            allowable_deductions: allowable_deductions.get(),
-            dividend_income: dividend_income.get(),
-            capital_gains: capital_gains.get(),
-            investment_wealth: investment_wealth.get(),
-            business_expenses: 0.0,
-        };
-        NorwegianTaxCalculator::calculate_tax(&input)
+    let audit_log = create_rw_signal(AuditLog::new(AUDIT_LOG_LIMIT));
+    create_effect(move |previous: Option<TaxCalculationInput>| {
+        let current = input.get();
+        if let Some(previous) = previous {
+            audit_log.update(|log| log.record(js_sys::Date::now(), &previous, &current));
+        }
+        current
     });
 
-    let corporate_result = create_memo(move |_| {
-        let input = TaxCalculationInput {
-            gross_income: gross_income.get(),
-            entity_type: EntityType::Corporation,
-            municipal_tax_rate: municipal_tax_rate.get(),
-            county_tax_rate: county_tax_rate.get(),
-            church_tax_rate: church_tax_rate.get(),
-            is_church_member: false,
-            allowable_deductions: allowable_deductions.get(),
-            dividend_income: dividend_income.get(),
-            capital_gains: capital_gains.get(),
-            investment_wealth: 0.0,
-            business_expenses: 0.0,
-        };
-        NorwegianTaxCalculator::calculate_tax(&input)
+    // Autosave so an accidental refresh doesn't lose the scenario
+    if !embedded {
+        create_effect(move |_| {
+            input.with(|current| storage::save(storage::SESSION_KEY, current));
+        });
+    }
+
+    let advanced_mode = create_rw_signal(storage::load::<bool>(storage::ADVANCED_MODE_KEY).unwrap_or(false));
+    create_effect(move |_| storage::save(storage::ADVANCED_MODE_KEY, &advanced_mode.get()));
+
+    // Online mode refreshes the kommune list; the bundled list is the fallback
+    let municipalities = create_rw_signal(cached_or_bundled());
+    #[cfg(feature = "integrations")]
+    let online_mode_toggle = {
+        let online_mode = create_rw_signal(storage::load::<bool>(ONLINE_MODE_KEY).unwrap_or(false));
+        create_effect(move |_| {
+            let enabled = online_mode.get();
+            storage::save(ONLINE_MODE_KEY, &enabled);
+            if enabled {
+                spawn_local(async move {
+                    match refresh_municipalities().await {
+                        Ok(list) => municipalities.set(list),
+                        Err(err) => logging::warn!("Kunne ikke hente kommunedata: {:?}", err),
+                    }
+                });
+            }
+        });
+        view! {
+            <label class="checkbox-label" title="Henter oppdatert kommuneliste fra SSB">
+                <input
+                    type="checkbox"
+                    role="switch"
+                    prop:checked=move || online_mode.get()
+                    on:change=move |ev| online_mode.set(event_target_checked(&ev))
+                />
+                "Hent kommunedata"
+            </label>
+        }
+    };
+    #[cfg(not(feature = "integrations"))]
+    let online_mode_toggle = ();
+    let municipality = Signal::derive(move || input.with(|i| i.municipality.clone()));
+    let select_municipality = move |selected: Option<Municipality>| input.update(|i| {
+        if let Some(selected) = &selected {
+            i.municipal_tax_rate = selected.municipal_tax_rate;
+            i.municipal_wealth_tax_rate = selected.wealth_tax_rate;
+            // Without data the user's own figures are kept
+            if let Some(rate) = selected.property_tax_rate {
+                i.property_tax.rate = rate;
+                i.property_tax.bunnfradrag = selected.property_tax_bunnfradrag.unwrap_or(Money::ZERO);
+            }
+        }
+        i.municipality = selected.map(|selected| selected.number);
     });
 
-    let partnership_result = create_memo(move |_| {
-        let input = TaxCalculationInput {
-            gross_income: gross_income.get(),
-            entity_type: EntityType::Partnership,
-            municipal_tax_rate: municipal_tax_rate.get(),
-            county_tax_rate: county_tax_rate.get(),
-            church_tax_rate: church_tax_rate.get(),
-            is_church_member: is_church_member.get(),
-            allowable_deductions: allowable_deductions.get(),
-            dividend_income: dividend_income.get(),
-            capital_gains: capital_gains.get(),
-            investment_wealth: investment_wealth.get(),
-            business_expenses: 0.0,
-        };
-        NorwegianTaxCalculator::calculate_tax(&input)
+    let currency = create_rw_signal(storage::load::<CurrencySettings>(CURRENCY_KEY).unwrap_or_default());
+    create_effect(move |_| currency.with(|settings| storage::save(CURRENCY_KEY, settings)));
+    provide_context(currency);
+
+    let accountant_mode = create_rw_signal(storage::load::<bool>(storage::ACCOUNTANT_MODE_KEY).unwrap_or(false));
+    create_effect(move |_| storage::save(storage::ACCOUNTANT_MODE_KEY, &accountant_mode.get()));
+
+    let target_net_mode = create_rw_signal(storage::load::<bool>(storage::TARGET_NET_MODE_KEY).unwrap_or(false));
+    create_effect(move |_| storage::save(storage::TARGET_NET_MODE_KEY, &target_net_mode.get()));
+
+    let policy_mode = create_rw_signal(storage::load::<bool>(storage::POLICY_MODE_KEY).unwrap_or(false));
+    create_effect(move |_| storage::save(storage::POLICY_MODE_KEY, &policy_mode.get()));
+
+    let student_mode = create_rw_signal(storage::load::<bool>(storage::STUDENT_MODE_KEY).unwrap_or(false));
+    create_effect(move |_| storage::save(storage::STUDENT_MODE_KEY, &student_mode.get()));
+
+    let start_fresh = move |_| {
+        storage::remove(storage::SESSION_KEY);
+        let fresh = TaxCalculationInput::default();
+        history.set(InputHistory::new(fresh.clone(), HISTORY_LIMIT));
+        input.set(fresh);
+        set_session_restored.set(false);
+    };
+
+    let undo = move || {
+        if let Some(previous) = history.try_update(|h| h.undo()).flatten() {
+            input.set(previous);
+        }
+    };
+
+    let redo = move || {
+        if let Some(next) = history.try_update(|h| h.redo()).flatten() {
+            input.set(next);
+        }
+    };
+
+    let calculation_result = create_memo(move |_| {
+        input.with(|input| {
+            // Before calculating, so a panic in the engine is reported with
+            // the input that caused it
+            #[cfg(feature = "integrations")]
+            error_reports::set_current_input(input);
+            NorwegianTaxCalculator::calculate_tax(input)
+        })
+    });
+    if let Some(on_calculate) = on_calculate {
+        create_effect(move |_| on_calculate.call(calculation_result.get()));
+    }
+    #[cfg(feature = "integrations")]
+    use_host_messages(input, calculation_result);
+    #[cfg(feature = "integrations")]
+    create_effect(move |_| {
+        calculation_result.with(|result| input.with_untracked(|input| error_reports::check_result(input, result)));
     });
 
-    let enk_result = create_memo(move |_| {
-        let input = TaxCalculationInput {
-            gross_income: gross_income.get(),
-            entity_type: EntityType::SoleProprietorship,
-            municipal_tax_rate: municipal_tax_rate.get(),
-            county_tax_rate: county_tax_rate.get(),
-            church_tax_rate: church_tax_rate.get(),
-            is_church_member: is_church_member.get(),
-            allowable_deductions: allowable_deductions.get(),
-            dividend_income: dividend_income.get(),
-            capital_gains: capital_gains.get(),
-            investment_wealth: investment_wealth.get(),
-            business_expenses: business_expenses.get(),
-        };
-        NorwegianTaxCalculator::calculate_tax(&input)
+    // Which comparison cards are shown, chosen in the comparison settings
+    let comparisons = create_rw_signal(load_comparisons());
+    create_effect(move |_| comparisons.with(|list| save_comparisons(list)));
+    let add_scenario_comparison = move || {
+        let current = input.get_untracked();
+        comparisons.update(|list| add_scenario(list, &current));
+    };
+    // Keyed by position and kind so editing a scenario card updates it in
+    // place instead of rebuilding every card
+    let comparison_entries = move || comparisons.get().into_iter().enumerate().collect::<Vec<_>>();
+
+    let snapshots = create_rw_signal(SnapshotHistory::new(SNAPSHOT_LIMIT));
+    create_effect(move |_| {
+        let result = calculation_result.get();
+        snapshots.update(|history| history.record(ResultSnapshot {
+            timestamp: js_sys::Date::now(),
+            input: input.get_untracked(),
+            result,
+        }));
+    });
+    let restore_snapshot = move |snapshot: ResultSnapshot| input.set(snapshot.input);
+
+    let animated_total_tax = use_tween(Signal::derive(move || calculation_result.get().total_tax.amount()), TWEEN_DURATION_MS);
+    let animated_effective_rate = use_tween(Signal::derive(move || calculation_result.get().effective_tax_rate.percent()), TWEEN_DURATION_MS);
+
+    let comparison_results = Signal::derive(move || {
+        let current = input.get();
+        comparisons.with(|list| {
+            list.iter()
+                .map(|comparison| {
+                    (comparison.title(), NorwegianTaxCalculator::calculate_tax(&comparison.input(&current)))
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+
+    let (pending_reset, set_pending_reset) = create_signal(None::<ResetTarget>);
+
+    let confirm_reset = move || {
+        match pending_reset.get_untracked() {
+            Some(ResetTarget::All) => input.set(TaxCalculationInput::default()),
+            Some(ResetTarget::Section(section)) => input.update(|i| i.reset_section(section)),
+            None => {}
+        }
+        set_pending_reset.set(None);
+    };
+
+    let reset_message = Signal::derive(move || match pending_reset.get() {
+        Some(ResetTarget::Section(section)) => {
+            format!("Vil du tilbakestille feltene under «{}»?", section.label())
+        }
+        _ => "Vil du tilbakestille alle feltene til standardverdier?".to_string(),
     });
 
-    let reset_calculator = move |_| {
-        set_gross_income.set(600000.0);
-        set_entity_type.set(EntityType::Individual);
-        set_active_tab.set(EntityType::Individual);
-        set_municipal_tax_rate.set(10.0);
-        set_county_tax_rate.set(11.4);
-        set_church_tax_rate.set(1.3);
-        set_is_church_member.set(true);
-        set_allowable_deductions.set(0.0);
-        set_dividend_income.set(0.0);
-        set_capital_gains.set(0.0);
-        set_investment_wealth.set(0.0);
-        set_business_expenses.set(0.0);
+    let reset_section = move |section: InputSection| set_pending_reset.set(Some(ResetTarget::Section(section)));
+
+    let apply_preset = move |id: String| {
+        if let Some(preset) = find_preset(&id) {
+            input.set(preset.to_input());
+        }
+    };
+
+    // Imported investment figures live in the advanced sections, so reveal them
+    #[cfg(feature = "integrations")]
+    let apply_import = move |imported: TaxCalculationInput| {
+        if imported.dividend_income > Money::ZERO || imported.capital_gains > Money::ZERO || imported.investment_wealth > Money::ZERO {
+            advanced_mode.set(true);
+        }
+        input.set(imported);
+    };
+
+    let scenarios = create_rw_signal(load_scenarios());
+    create_effect(move |_| scenarios.with(|list| save_scenarios(list)));
+    let notes = create_rw_signal(load_notes());
+    create_effect(move |_| notes.with(save_notes));
+
+    let save_scenario = move || {
+        let current = input.get_untracked();
+        let current_notes = notes.get_untracked();
+        scenarios.update(|list| {
+            let name = next_scenario_name(list);
+            list.push(SavedScenario { name, input: current, notes: current_notes });
+        });
+    };
+
+    let load_scenario = move |scenario: SavedScenario| {
+        input.set(scenario.input);
+        notes.set(scenario.notes);
+    };
+
+    let (shortcuts_open, set_shortcuts_open) = create_signal(false);
+
+    let mut shortcuts = vec![
+        Shortcut::new("z", "Gjør om", redo).with_ctrl().with_shift(),
+        Shortcut::new("z", "Angre", undo).with_ctrl(),
+        Shortcut::new("y", "Gjør om", redo).with_ctrl(),
+    ];
+    for (index, tab) in ENTITY_TABS.into_iter().enumerate() {
+        let description = match tab {
+            EntityType::Individual => "Vis Person",
+            EntityType::Corporation => "Vis Aksjeselskap (AS)",
+            EntityType::Partnership => "Vis Deltakerlignet selskap",
+            EntityType::SoleProprietorship => "Vis ENK",
+            EntityType::ForeignBranch => "Vis NUF",
+            EntityType::Nonprofit => "Vis Ideell organisasjon",
+        };
+        let key = ["1", "2", "3", "4", "5", "6"][index];
+        shortcuts.push(Shortcut::new(key, description, move || set_entity_type.set(tab)));
+    }
+    shortcuts.extend([
+        Shortcut::new("r", "Tilbakestill alle felter", move || set_pending_reset.set(Some(ResetTarget::All))),
+        Shortcut::new("s", "Lagre scenario", save_scenario),
+        Shortcut::new("?", "Vis hurtigtaster", move || set_shortcuts_open.update(|open| *open = !*open)),
+        Shortcut::new("Escape", "Lukk dialog", move || {
+            set_shortcuts_open.set(false);
+            set_pending_reset.set(None);
+        }),
+    ]);
+    let shortcut_help = shortcuts
+        .iter()
+        .filter(|shortcut| shortcut.key != "Escape")
+        .map(|shortcut| (shortcut.label(), shortcut.description))
+        .collect::<Vec<_>>();
+    if !embedded {
+        use_keyboard_shortcuts(shortcuts);
+    }
+
+    // Panels from optional features render nothing when the feature is off
+    #[cfg(feature = "integrations")]
+    let (skattemelding_importer, skattemelding_export) = (
+        move || view! { <SkattemeldingImporter base=input on_import=apply_import /> },
+        move || view! { <SkattemeldingExport input=input /> },
+    );
+    #[cfg(not(feature = "integrations"))]
+    let (skattemelding_importer, skattemelding_export) = (|| (), || ());
+
+    #[cfg(feature = "employer-cost")]
+    let (bonus_panel, employer_cost_panel) = (
+        move || view! { <BonusPanel input=input /> },
+        move || view! {
+            <Show when=move || matches!(entity_type.get(), EntityType::Corporation | EntityType::SoleProprietorship | EntityType::ForeignBranch)>
+                <Deferred label="Laster lønnskostnader">
+                    <EmployerCostPanel input=input />
+                </Deferred>
+            </Show>
+        },
+    );
+    #[cfg(not(feature = "employer-cost"))]
+    let (bonus_panel, employer_cost_panel) = (|| (), || ());
+
+    #[cfg(feature = "vat")]
+    let hourly_rate_panel = move || view! { <HourlyRatePanel input=input /> };
+    #[cfg(not(feature = "vat"))]
+    let hourly_rate_panel = || ();
+
+    #[cfg(feature = "charts")]
+    let show_simulation = move || input.with(|i| !i.entity_type.is_company() && i.investment_wealth > Money::ZERO);
+    #[cfg(feature = "charts")]
+    let (waterfall_chart, bracket_visualizer, simulation_panel, distribution_planner) = (
+        move || view! { <WaterfallChart result=calculation_result /> },
+        move || view! {
+            <BracketVisualizer
+                personal_income=Signal::derive(move || calculation_result.with(|r| r.personinntekt))
+                tax_year=tax_year
+            />
+        },
+        move || view! {
+            <Show when=show_simulation>
+                <Deferred label="Laster simulering">
+                    <MonteCarloPanel input=input />
+                </Deferred>
+            </Show>
+        },
+        move || view! {
+            <Show when=move || entity_type.get() == EntityType::Corporation>
+                <Deferred label="Laster utbytteplanlegger">
+                    <DistributionPlanner input=input />
+                </Deferred>
+            </Show>
+        },
+    );
+    #[cfg(not(feature = "charts"))]
+    let (waterfall_chart, bracket_visualizer, simulation_panel, distribution_planner) = (|| (), || (), || (), || ());
+
+    // Entity cards switch tabs; scenario cards are active when the input
+    // matches the scenario and carry an editor for their own input
+    let comparison_card = move |(index, initial): (usize, Comparison)| {
+        let comparison = create_memo(move |_| {
+            comparisons.with(|list| list.get(index).cloned()).unwrap_or_else(|| initial.clone())
+        });
+        let result = create_memo(move |_| {
+            let variant = input.with(|i| comparison.with(|c| c.input(i)));
+            NorwegianTaxCalculator::calculate_tax(&variant)
+        });
+        let is_active = Signal::derive(move || comparison.with(|c| match c {
+            Comparison::Entity(entity) => entity_type.get() == *entity,
+            Comparison::Scenario(scenario) => input.with(|i| *i == scenario.input),
+        }));
+        let on_select = move || match comparison.get_untracked() {
+            Comparison::Entity(entity) => set_entity_type.set(entity),
+            Comparison::Scenario(scenario) => input.set(scenario.input),
+        };
+        let on_adopt = move || {
+            let adopted = input.with_untracked(|i| comparison.with_untracked(|c| c.input(i)));
+            input.set(adopted);
+        };
+        let scenario = match comparison.get_untracked() {
+            Comparison::Scenario(initial) => Some(Signal::derive(move || match comparison.get() {
+                Comparison::Scenario(scenario) => *scenario,
+                Comparison::Entity(_) => initial.as_ref().clone(),
+            })),
+            Comparison::Entity(_) => None,
+        };
+        view! {
+            <ComparisonCard
+                title=Signal::derive(move || comparison.with(Comparison::title))
+                result=result
+                is_active=is_active
+                on_select=on_select
+                on_adopt=on_adopt
+            >
+                {scenario.map(|scenario| view! {
+                    <ScenarioEditor
+                        scenario=scenario
+                        on_change=move |edited| comparisons.update(|list| {
+                            if let Some(slot) = list.get_mut(index) {
+                                *slot = Comparison::Scenario(Box::new(edited));
+                            }
+                        })
+                        on_remove=move || comparisons.update(|list| {
+                            if index < list.len() {
+                                list.remove(index);
+                            }
+                        })
+                    />
+                })}
+            </ComparisonCard>
+        }
     };
 
     view! {
@@ -126,173 +480,315 @@ pub fn TaxCalculator() -> impl IntoView {
                 <h1>"Norsk Skattekalkulator"</h1>
                 <div class="calculator-info">
                     <div class="tax-counter">
-                        "Total skatt: " {move || format!("{} NOK", NorwegianTaxCalculator::format_currency(calculation_result.get().total_tax))}
+                        "Total skatt: " {move || currency.get().format(animated_total_tax.get())}
+                    </div>
+                    <div class="history-buttons">
+                        <button
+                            class="history-button"
+                            title="Angre (Ctrl+Z)"
+                            disabled=move || !history.with(|h| h.can_undo())
+                            on:click=move |_| undo()
+                        >
+                            "Angre"
+                        </button>
+                        <button
+                            class="history-button"
+                            title="Gjør om (Ctrl+Y)"
+                            disabled=move || !history.with(|h| h.can_redo())
+                            on:click=move |_| redo()
+                        >
+                            "Gjør om"
+                        </button>
+                        <button
+                            class="history-button"
+                            title="Hurtigtaster (?)"
+                            aria-label="Vis hurtigtaster"
+                            on:click=move |_| set_shortcuts_open.set(true)
+                        >
+                            "?"
+                        </button>
+                        <button class="reset-button" on:click=move |_| set_pending_reset.set(Some(ResetTarget::All))>
+                            "Tilbakestill"
+                        </button>
                     </div>
-                    <button class="reset-button" on:click=reset_calculator>
-                        "Tilbakestill"
-                    </button>
                     <div class="status">
-                        {move || format!("Effektiv sats: {:.1}%", calculation_result.get().effective_tax_rate)}
+                        {move || format!("Effektiv sats: {:.1}%", animated_effective_rate.get())}
                     </div>
                 </div>
             </div>
 
-            <div class="entity-selector">
+            {move || session_restored.get().then(|| view! {
+                <div class="session-notice">
+                    <span>"Forrige økt ble gjenopprettet."</span>
+                    <button class="history-button" on:click=start_fresh>
+                        "Start på nytt"
+                    </button>
+                </div>
+            })}
+
+            <PresetSelector presets=presets() on_select=apply_preset />
+
+            <TaxYearSelector value=tax_year on_change=set_tax_year />
+
+            {skattemelding_importer}
+
+            <SavedScenarioList scenarios=scenarios notes=notes on_save=save_scenario on_load=load_scenario />
+
+            <div
+                class="entity-selector"
+                role="tablist"
+                aria-label="Selskapsform"
+                on:keydown=on_tab_keydown
+            >
                 <EntityTab 
                     entity_type=EntityType::Individual
                     current_type=entity_type
-                    on_select=move |_| {
-                        set_entity_type.set(EntityType::Individual);
-                        set_active_tab.set(EntityType::Individual);
-                    }
+                    on_select=move |_| set_entity_type.set(EntityType::Individual)
                     label="Person"
                 />
                 <EntityTab 
                     entity_type=EntityType::Corporation
                     current_type=entity_type
-                    on_select=move |_| {
-                        set_entity_type.set(EntityType::Corporation);
-                        set_active_tab.set(EntityType::Corporation);
-                    }
+                    on_select=move |_| set_entity_type.set(EntityType::Corporation)
                     label="Aksjeselskap (AS)"
                 />
                 <EntityTab 
                     entity_type=EntityType::Partnership
                     current_type=entity_type
-                    on_select=move |_| {
-                        set_entity_type.set(EntityType::Partnership);
-                        set_active_tab.set(EntityType::Partnership);
-                    }
+                    on_select=move |_| set_entity_type.set(EntityType::Partnership)
                     label="Deltakerlignet selskap"
                 />
                 <EntityTab 
                     entity_type=EntityType::SoleProprietorship
                     current_type=entity_type
-                    on_select=move |_| {
-                        set_entity_type.set(EntityType::SoleProprietorship);
-                        set_active_tab.set(EntityType::SoleProprietorship);
-                    }
+                    on_select=move |_| set_entity_type.set(EntityType::SoleProprietorship)
                     label="ENK"
                 />
-            </div>
-
-            <div class="input-grid">
-                <InputField
-                    label="Bruttoinntekt (NOK)"
-                    value=gross_income
-                    on_change=set_gross_income
-                    step=1000.0
-                    min=0.0
+                <EntityTab 
+                    entity_type=EntityType::ForeignBranch
+                    current_type=entity_type
+                    on_select=move |_| set_entity_type.set(EntityType::ForeignBranch)
+                    label="NUF"
                 />
-                
-                <InputField
-                    label="Fradrag (NOK)"
-                    value=allowable_deductions
-                    on_change=set_allowable_deductions
-                    step=1000.0
-                    min=0.0
+                <EntityTab 
+                    entity_type=EntityType::Nonprofit
+                    current_type=entity_type
+                    on_select=move |_| set_entity_type.set(EntityType::Nonprofit)
+                    label="Ideell"
                 />
+            </div>
 
-                {move || match active_tab.get() {
-                    EntityType::SoleProprietorship => view! {
-                        <InputField
-                            label="Driftskostnader (NOK)"
-                            value=business_expenses
-                            on_change=set_business_expenses
-                            step=1000.0
-                            min=0.0
-                        />
-                    }.into_view(),
-                    _ => view! { <div></div> }.into_view()
-                }}
-
-                <InputField
-                    label="Aksjeutbytte (NOK)"
-                    value=dividend_income
-                    on_change=set_dividend_income
-                    step=1000.0
-                    min=0.0
-                />
+            <div class="mode-toggle">
+                <label class="checkbox-label">
+                    <input
+                        type="checkbox"
+                        role="switch"
+                        prop:checked=move || advanced_mode.get()
+                        on:change=move |ev| advanced_mode.set(event_target_checked(&ev))
+                    />
+                    "Avansert"
+                </label>
+                <span class="mode-toggle-hint">
+                    {move || if advanced_mode.get() {
+                        "Viser investeringer, formue og skattesatser"
+                    } else {
+                        "Viser bare inntekt og fradrag"
+                    }}
+                </span>
+                <label class="checkbox-label">
+                    <input
+                        type="checkbox"
+                        role="switch"
+                        prop:checked=move || accountant_mode.get()
+                        on:change=move |ev| accountant_mode.set(event_target_checked(&ev))
+                    />
+                    "Regnskapsfører"
+                </label>
+                <label class="checkbox-label" title="Finn hva du må tjene for å sitte igjen med et ønsket beløp">
+                    <input
+                        type="checkbox"
+                        role="switch"
+                        prop:checked=move || target_net_mode.get()
+                        on:change=move |ev| target_net_mode.set(event_target_checked(&ev))
+                    />
+                    "Ønsket netto"
+                </label>
+                <label class="checkbox-label" title="Endre satsene og se effekten mot gjeldende regler">
+                    <input
+                        type="checkbox"
+                        role="switch"
+                        prop:checked=move || policy_mode.get()
+                        on:change=move |ev| policy_mode.set(event_target_checked(&ev))
+                    />
+                    "Hva hvis"
+                </label>
+                <label class="checkbox-label" title="Deltidsjobb ved siden av studiene, med frikort">
+                    <input
+                        type="checkbox"
+                        role="switch"
+                        prop:checked=move || student_mode.get()
+                        on:change=move |ev| {
+                            let enabled = event_target_checked(&ev);
+                            student_mode.set(enabled);
+                            if enabled {
+                                set_entity_type.set(EntityType::Individual);
+                            }
+                        }
+                    />
+                    "Student"
+                </label>
+                {online_mode_toggle}
+            </div>
 
-                <InputField
-                    label="Aksjegevinst (NOK)"
-                    value=capital_gains
-                    on_change=set_capital_gains
-                    step=1000.0
-                    min=0.0
-                />
+            <Show when=move || accountant_mode.get()>
+                <ClientManager input=input />
+                <Deferred label="Laster massberegning">
+                    <AccountantBatchPanel />
+                </Deferred>
+            </Show>
 
-                {move || match active_tab.get() {
-                    EntityType::Corporation => view! { <div></div> }.into_view(),
-                    _ => view! {
-                        <InputField
-                            label="Aksjeverdi for formueskatt (NOK)"
-                            value=investment_wealth
-                            on_change=set_investment_wealth
-                            step=10000.0
-                            min=0.0
-                        />
-                    }.into_view()
-                }}
+            <div
+                class="input-sections"
+                id=ENTITY_PANEL_ID
+                role="tabpanel"
+                aria-labelledby=move || entity_tab_id(entity_type.get())
+            >
+                <InputSectionGroup section=InputSection::Income on_reset=reset_section>
+                    <DynamicForm input=input section=InputSection::Income />
+                </InputSectionGroup>
 
-                <TaxRateField
-                    label="Kommuneskatt (%)"
-                    value=municipal_tax_rate
-                    on_change=set_municipal_tax_rate
-                />
+                <InputSectionGroup section=InputSection::Deductions on_reset=reset_section>
+                    <DynamicForm input=input section=InputSection::Deductions />
+                </InputSectionGroup>
 
-                <TaxRateField
-                    label="Fylkeskatt (%)"
-                    value=county_tax_rate
-                    on_change=set_county_tax_rate
-                />
+                <Show when=move || advanced_mode.get()>
+                    <InputSectionGroup section=InputSection::Investments on_reset=reset_section>
+                        <DynamicForm input=input section=InputSection::Investments />
+                    </InputSectionGroup>
 
-                {move || match active_tab.get() {
-                    EntityType::Corporation => view! { <div></div> }.into_view(),
-                    _ => view! {
-                        <CheckboxField
-                            label="Medlem av Den norske kirke"
-                            value=is_church_member
-                            on_change=set_is_church_member
-                        />
-                    }.into_view()
-                }}
-
-                {move || if active_tab.get() != EntityType::Corporation && is_church_member.get() {
-                    view! {
-                        <TaxRateField
-                            label="Kirkeskatt (%)"
-                            value=church_tax_rate
-                            on_change=set_church_tax_rate
+                    <InputSectionGroup section=InputSection::Rates on_reset=reset_section>
+                        <MunicipalitySelector
+                            municipalities=municipalities.into()
+                            selected=municipality
+                            on_select=select_municipality
                         />
-                    }.into_view()
-                } else {
-                    view! { <div></div> }.into_view()
-                }}
+
+                        <DynamicForm input=input section=InputSection::Rates />
+                    </InputSectionGroup>
+
+                    <InputSectionGroup section=InputSection::CustomTaxes on_reset=reset_section>
+                        <CustomTaxEditor input=input />
+                    </InputSectionGroup>
+                </Show>
             </div>
 
+            <ConfirmDialog
+                open=Signal::derive(move || pending_reset.get().is_some())
+                message=reset_message
+                on_confirm=confirm_reset
+                on_cancel=move || set_pending_reset.set(None)
+                confirm_label="Tilbakestill"
+            />
+
+            <ShortcutsOverlay
+                open=shortcuts_open
+                shortcuts=shortcut_help
+                on_close=move || set_shortcuts_open.set(false)
+            />
+
+            <CurrencySelector settings=currency />
+            {(!embedded).then(|| view! { <BackupPanel /> })}
+
             <div class="results-display">
-                <TaxResults result=calculation_result />
+                <InconsistencyWarnings input=input />
+                <TaxResults result=calculation_result comparisons=comparison_results notes=notes />
+                <Show when=move || !entity_type.get().is_company()>
+                    <TaxCalendarCard result=calculation_result />
+                </Show>
+                <SnapshotHistoryPanel snapshots=snapshots on_restore=restore_snapshot />
+                <AuditLogPanel log=audit_log />
+            </div>
+
+            <Show when=move || student_mode.get() && entity_type.get() == EntityType::Individual>
+                <StudentPanel input=input />
+            </Show>
+
+            <Show when=move || target_net_mode.get()>
+                <TargetNetIncomePanel input=input />
+            </Show>
+
+            <Show when=move || policy_mode.get()>
+                <PolicyPlayground input=input />
+            </Show>
+
+            {skattemelding_export}
+
+            <Deferred label="Laster analyser">
+                <SensitivityTable input=input />
+
+                <Show when=move || entity_type.get() == EntityType::Individual>
+                    <SalaryNegotiationPanel input=input />
+                    <DualEmploymentPanel input=input />
+                    <UnemploymentPanel input=input />
+                    <ParentalLeavePanel input=input />
+                    {bonus_panel}
+                </Show>
+
+                <YearComparisonTable input=input />
+                <LastYearComparisonPanel input=input />
+
+                <Show when=move || input.with(|i| !i.entity_type.is_company())>
+                    <IpsComparisonPanel input=input />
+                </Show>
+
+                <Show when=move || input.with(|i| !i.entity_type.is_company() && i.investment_wealth > Money::ZERO)>
+                    <MunicipalWealthTaxComparison input=input municipalities=municipalities />
+                    <SpouseWealthAllocationPanel input=input />
+                </Show>
+            </Deferred>
+
+            <Deferred label="Laster diagrammer">
+                {waterfall_chart}
+
+                <Show when=move || !entity_type.get().is_company()>
+                    {bracket_visualizer}
+                    <IncomeBenchmarkPanel result=calculation_result />
+                </Show>
+            </Deferred>
+
+            {simulation_panel}
+
+            <Show when=move || entity_type.get() == EntityType::SoleProprietorship>
+                <Deferred label="Laster ENK-verktøy">
+                    {hourly_rate_panel}
+                    <IncomeSmoothingPanel input=input />
+                </Deferred>
+            </Show>
+
+            {distribution_planner}
+
+            {employer_cost_panel}
+
+            <div class="comparison-toolbar">
+                <button class="history-button" on:click=move |_| add_scenario_comparison()>
+                    "Legg til scenario"
+                </button>
+                <ComparisonSettings comparisons=comparisons scenarios=scenarios />
             </div>
 
             <div class="comparison">
-                <ComparisonCard 
-                    title="Person".to_string()
-                    result=individual_result
-                />
-                <ComparisonCard 
-                    title="Aksjeselskap (AS)".to_string()
-                    result=corporate_result
-                />
-                <ComparisonCard 
-                    title="Deltakerlignet selskap".to_string()
-                    result=partnership_result
-                />
-                <ComparisonCard 
-                    title="ENK (Enkeltpersonforetak)".to_string()
-                    result=enk_result
+                <For
+                    each=comparison_entries
+                    key=|(index, comparison)| (*index, comparison.kind())
+                    children=comparison_card
                 />
             </div>
+
+            <Deferred label="Laster sammenligning">
+                <EntityBenefitComparison input=input />
+            </Deferred>
+
+            <RulesetFooter tax_year=tax_year />
         </div>
     }
 }
\ No newline at end of file
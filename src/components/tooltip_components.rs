@@ -0,0 +1,40 @@
+use leptos::*;
+use crate::tax_calculator::TaxRule;
+use crate::components::next_field_id;
+
+#[component]
+pub fn InfoTooltip(rule: TaxRule) -> impl IntoView {
+    let info = rule.info();
+    let bases = rule.deduction_bases();
+    let reduces = (!bases.is_empty()).then(|| {
+        let labels = bases.iter().map(|base| base.label()).collect::<Vec<_>>();
+        format!("Reduserer {}", labels.join(" og "))
+    });
+    let id = next_field_id();
+    let (open, set_open) = create_signal(false);
+
+    view! {
+        <span class="info-tooltip" class:info-tooltip-open=move || open.get()>
+            <button
+                type="button"
+                class="info-icon"
+                aria-label="Forklaring"
+                aria-describedby=id.clone()
+                aria-expanded=move || open.get().to_string()
+                on:click=move |ev| {
+                    ev.prevent_default();
+                    ev.stop_propagation();
+                    set_open.update(|open| *open = !*open);
+                }
+                on:blur=move |_| set_open.set(false)
+            >
+                "i"
+            </button>
+            <span id=id role="tooltip" class="tooltip-content">
+                <span class="tooltip-explanation">{info.explanation}</span>
+                {reduces.map(|reduces| view! { <span class="tooltip-explanation">{reduces}</span> })}
+                <span class="tooltip-reference">{info.reference}</span>
+            </span>
+        </span>
+    }
+}
@@ -0,0 +1,76 @@
+use leptos::*;
+
+fn now() -> f64 {
+    window().performance().map(|performance| performance.now()).unwrap_or(0.0)
+}
+
+fn prefers_reduced_motion() -> bool {
+    window()
+        .match_media("(prefers-reduced-motion: reduce)")
+        .ok()
+        .flatten()
+        .map(|query| query.matches())
+        .unwrap_or(false)
+}
+
+fn ease_out_cubic(t: f64) -> f64 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+#[derive(Clone, Copy)]
+struct Tween {
+    from: f64,
+    to: f64,
+    started_at: f64,
+    duration_ms: f64,
+    generation: u64,
+}
+
+fn schedule_frame(tween: Tween, current_generation: StoredValue<u64>, set_value: WriteSignal<f64>) {
+    request_animation_frame(move || {
+        // A newer target has taken over this animation
+        if current_generation.get_value() != tween.generation {
+            return;
+        }
+
+        let progress = ((now() - tween.started_at) / tween.duration_ms).clamp(0.0, 1.0);
+        set_value.set(tween.from + (tween.to - tween.from) * ease_out_cubic(progress));
+
+        if progress < 1.0 {
+            schedule_frame(tween, current_generation, set_value);
+        }
+    });
+}
+
+// Follows `target`, animating from the currently displayed value to each new
+// target over `duration_ms`. Interrupted animations continue from wherever
+// they were, so rapid input changes never jump.
+pub fn use_tween(target: Signal<f64>, duration_ms: f64) -> Signal<f64> {
+    let (value, set_value) = create_signal(target.get_untracked());
+    let current_generation = store_value(0_u64);
+
+    create_effect(move |_| {
+        let to = target.get();
+        let from = value.get_untracked();
+        current_generation.update_value(|generation| *generation += 1);
+
+        if from == to || !to.is_finite() || prefers_reduced_motion() {
+            set_value.set(to);
+            return;
+        }
+
+        schedule_frame(
+            Tween {
+                from,
+                to,
+                started_at: now(),
+                duration_ms,
+                generation: current_generation.get_value(),
+            },
+            current_generation,
+            set_value,
+        );
+    });
+
+    value.into()
+}
@@ -0,0 +1,395 @@
+use crate::comparisons::entity_title;
+use crate::industries::ResourceRentActivity;
+use crate::money::Money;
+use crate::tax_calculator::{EntityType, NorwegianTaxCalculator, TaxCalculationInput};
+
+// Inputs the engine publishes constraints for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputKey {
+    GrossIncome,
+    SickPay,
+    DisabilityBenefit,
+    BusinessExpenses,
+    SickPayInsurance,
+    ResourceRentActivity,
+    ResourceRentIncome,
+    ResourceRentCosts,
+    Petroleum,
+    PetroleumInvestments,
+    BranchPayroll,
+    CharitablePurpose,
+    Deductions,
+    CommuteDistance,
+    CommuteWorkdays,
+    NightsAway,
+    Lodging,
+    LodgingDocumented,
+    Kitchen,
+    HomeVisits,
+    HomeVisitDistance,
+    Donations,
+    IpsContribution,
+    SicknessCostsTransitional,
+    SicknessCosts,
+    Occupation,
+    DividendIncome,
+    CapitalGains,
+    InvestmentWealth,
+    MunicipalTaxRate,
+    CountyTaxRate,
+    Tiltakssone,
+    ChurchMember,
+    HistoricalChurchTax,
+    ChurchTaxRate,
+    PropertyTax,
+    HomeValue,
+    PropertyTaxPerMille,
+    PropertyTaxBunnfradrag,
+}
+
+impl InputKey {
+    pub fn label(&self) -> &'static str {
+        match self {
+            InputKey::GrossIncome => "Bruttoinntekt",
+            InputKey::SickPay => "Sykepenger",
+            InputKey::DisabilityBenefit => "Uføretrygd",
+            InputKey::BusinessExpenses => "Driftskostnader",
+            InputKey::SickPayInsurance => "Tilleggstrygd for sykepenger",
+            InputKey::ResourceRentActivity => "Grunnrenteskattepliktig virksomhet",
+            InputKey::ResourceRentIncome => "Grunnrenteinntekt",
+            InputKey::ResourceRentCosts => "Kostnader i grunnrentevirksomheten",
+            InputKey::Petroleum => "Petroleumsvirksomhet",
+            InputKey::PetroleumInvestments => "Investeringer i petroleumsvirksomheten",
+            InputKey::BranchPayroll => "Lønn til ansatte i Norge",
+            InputKey::CharitablePurpose => "Veldedig eller allmennyttig formål",
+            InputKey::Deductions => "Fradrag",
+            InputKey::CommuteDistance => "Avstand hjem–arbeid",
+            InputKey::CommuteWorkdays => "Arbeidsdager med reise",
+            InputKey::NightsAway => "Døgn borte fra hjemmet",
+            InputKey::Lodging => "Losji",
+            InputKey::LodgingDocumented => "Dokumenterte losjiutgifter",
+            InputKey::Kitchen => "Kokemulighet",
+            InputKey::HomeVisits => "Besøksreiser hjem",
+            InputKey::HomeVisitDistance => "Avstand til hjemmet",
+            InputKey::Donations => "Gaver til frivillige organisasjoner",
+            InputKey::IpsContribution => "Innskudd på IPS",
+            InputKey::SicknessCostsTransitional => "Særfradrag for store sykdomskostnader etter overgangsregelen",
+            InputKey::SicknessCosts => "Sykdomskostnader",
+            InputKey::Occupation => "Yrke med særskilt fradrag",
+            InputKey::DividendIncome => "Aksjeutbytte",
+            InputKey::CapitalGains => "Aksjegevinst",
+            InputKey::InvestmentWealth => "Aksjeverdi",
+            InputKey::MunicipalTaxRate => "Kommuneskatt",
+            InputKey::CountyTaxRate => "Fylkeskatt",
+            InputKey::Tiltakssone => "Tiltakssonen",
+            InputKey::ChurchMember => "Medlem av Den norske kirke",
+            InputKey::HistoricalChurchTax => "Historisk modell med kirkeskatt",
+            InputKey::ChurchTaxRate => "Kirkeskatt",
+            InputKey::PropertyTax => "Eiendomsskatt på boligen",
+            InputKey::HomeValue => "Eiendomsskattegrunnlag for boligen",
+            InputKey::PropertyTaxPerMille => "Eiendomsskattesats",
+            InputKey::PropertyTaxBunnfradrag => "Bunnfradrag i eiendomsskatten",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputUnit {
+    Nok,
+    Percent,
+    PerMille,
+    Km,
+    Days,
+    Count,
+    // Choices and flags, which have no range
+    Choice,
+}
+
+pub struct InputConstraint {
+    pub key: InputKey,
+    pub unit: InputUnit,
+    // Entity types whose calculation reads the input
+    pub entities: &'static [EntityType],
+    // Other inputs it depends on, such as the church tax rate only counting
+    // for members on the historical model
+    pub requires: Option<fn(&TaxCalculationInput) -> bool>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    // Values outside this range are accepted but probably a typo
+    pub plausible: Option<(f64, f64)>,
+    // Rules across inputs, giving a message when they don't hold
+    pub check: Option<fn(&TaxCalculationInput) -> Option<String>>,
+}
+
+const ALL_ENTITIES: &[EntityType] = &[
+    EntityType::Individual,
+    EntityType::Corporation,
+    EntityType::Partnership,
+    EntityType::SoleProprietorship,
+    EntityType::ForeignBranch,
+    EntityType::Nonprofit,
+];
+const PERSONAL: &[EntityType] = &[EntityType::Individual, EntityType::Partnership, EntityType::SoleProprietorship];
+
+impl InputConstraint {
+    const fn new(key: InputKey, unit: InputUnit, entities: &'static [EntityType]) -> Self {
+        let min = match unit {
+            InputUnit::Choice => None,
+            InputUnit::Nok | InputUnit::Percent | InputUnit::PerMille | InputUnit::Km | InputUnit::Days | InputUnit::Count => Some(0.0),
+        };
+        InputConstraint {
+            key,
+            unit,
+            entities,
+            requires: None,
+            min,
+            max: None,
+            plausible: None,
+            check: None,
+        }
+    }
+
+    const fn rate(key: InputKey, entities: &'static [EntityType]) -> Self {
+        InputConstraint {
+            max: Some(25.0),
+            ..Self::new(key, InputUnit::Percent, entities)
+        }
+    }
+
+    const fn with_requirement(self, requires: fn(&TaxCalculationInput) -> bool) -> Self {
+        InputConstraint { requires: Some(requires), ..self }
+    }
+
+    const fn with_plausible(self, plausible: (f64, f64)) -> Self {
+        InputConstraint { plausible: Some(plausible), ..self }
+    }
+
+    const fn with_check(self, check: fn(&TaxCalculationInput) -> Option<String>) -> Self {
+        InputConstraint { check: Some(check), ..self }
+    }
+
+    fn format(&self, value: f64) -> String {
+        match self.unit {
+            InputUnit::Nok => NorwegianTaxCalculator::format_nok(value),
+            InputUnit::Percent | InputUnit::Choice => format!("{} %", value),
+            InputUnit::PerMille => format!("{} ‰", value),
+            InputUnit::Km => format!("{} km", value),
+            InputUnit::Days => format!("{} dager", value),
+            InputUnit::Count => value.to_string(),
+        }
+    }
+}
+
+// The engine gives fellesskatt whatever the two local rates leave of the
+// rate on alminnelig inntekt, and cuts the local rates to fit that total
+fn local_rates_within_total(input: &TaxCalculationInput) -> Option<String> {
+    NorwegianTaxCalculator::local_rate_cuts(input).map(|cuts| cuts.describe())
+}
+
+// Gifts above the year's cap are accepted, but give no deduction
+fn donations_within_cap(input: &TaxCalculationInput) -> Option<String> {
+    let cap = input.rules().donation_deduction_max;
+    (input.donations > cap).then(|| {
+        format!(
+            "Bare {} av gavene gir fradrag i {}",
+            NorwegianTaxCalculator::format_nok(cap),
+            input.tax_year.year()
+        )
+    })
+}
+
+fn ips_within_cap(input: &TaxCalculationInput) -> Option<String> {
+    let cap = input.rules().ips_deduction_max;
+    (input.ips_contribution > cap).then(|| {
+        format!(
+            "Bare {} av innskuddet gir fradrag i {}",
+            NorwegianTaxCalculator::format_nok(cap),
+            input.tax_year.year()
+        )
+    })
+}
+
+// Plausible ranges are loose bounds around real figures, wide enough for
+// what-if scenarios but tight enough to catch a misplaced zero
+const CONSTRAINTS: &[InputConstraint] = &[
+    InputConstraint::new(InputKey::GrossIncome, InputUnit::Nok, ALL_ENTITIES).with_plausible((0.0, 100_000_000.0)),
+    InputConstraint::new(InputKey::SickPay, InputUnit::Nok, &[EntityType::Individual, EntityType::Partnership]),
+    InputConstraint::new(InputKey::DisabilityBenefit, InputUnit::Nok, &[EntityType::Individual, EntityType::Partnership]),
+    InputConstraint::new(
+        InputKey::BusinessExpenses,
+        InputUnit::Nok,
+        &[EntityType::SoleProprietorship, EntityType::Nonprofit],
+    ),
+    InputConstraint::new(InputKey::SickPayInsurance, InputUnit::Choice, &[EntityType::SoleProprietorship]),
+    InputConstraint::new(InputKey::ResourceRentActivity, InputUnit::Choice, &[EntityType::Corporation]),
+    InputConstraint::new(InputKey::ResourceRentIncome, InputUnit::Nok, &[EntityType::Corporation])
+        .with_requirement(|i| i.resource_rent.activity != ResourceRentActivity::None),
+    InputConstraint::new(InputKey::ResourceRentCosts, InputUnit::Nok, &[EntityType::Corporation])
+        .with_requirement(|i| i.resource_rent.activity != ResourceRentActivity::None),
+    InputConstraint::new(InputKey::Petroleum, InputUnit::Choice, &[EntityType::Corporation]),
+    InputConstraint::new(InputKey::PetroleumInvestments, InputUnit::Nok, &[EntityType::Corporation])
+        .with_requirement(|i| i.petroleum.enabled),
+    InputConstraint::new(InputKey::BranchPayroll, InputUnit::Nok, &[EntityType::ForeignBranch]),
+    InputConstraint::new(InputKey::CharitablePurpose, InputUnit::Choice, &[EntityType::Nonprofit]),
+    InputConstraint::new(InputKey::Deductions, InputUnit::Nok, ALL_ENTITIES),
+    InputConstraint::new(InputKey::CommuteDistance, InputUnit::Km, PERSONAL).with_plausible((0.0, 300.0)),
+    InputConstraint {
+        max: Some(366.0),
+        ..InputConstraint::new(InputKey::CommuteWorkdays, InputUnit::Days, PERSONAL)
+    }
+    .with_requirement(|i| i.commute.distance_km > 0.0)
+    .with_plausible((0.0, 260.0)),
+    InputConstraint {
+        max: Some(366.0),
+        ..InputConstraint::new(InputKey::NightsAway, InputUnit::Days, PERSONAL)
+    },
+    InputConstraint::new(InputKey::Lodging, InputUnit::Nok, PERSONAL)
+        .with_requirement(|i| i.commute.nights_away > 0.0 && i.commute.lodging_documented),
+    InputConstraint::new(InputKey::LodgingDocumented, InputUnit::Choice, PERSONAL)
+        .with_requirement(|i| i.commute.nights_away > 0.0),
+    InputConstraint::new(InputKey::Kitchen, InputUnit::Choice, PERSONAL).with_requirement(|i| i.commute.nights_away > 0.0),
+    InputConstraint::new(InputKey::HomeVisits, InputUnit::Count, PERSONAL)
+        .with_requirement(|i| i.commute.nights_away > 0.0)
+        .with_plausible((0.0, 60.0)),
+    InputConstraint::new(InputKey::HomeVisitDistance, InputUnit::Km, PERSONAL)
+        .with_requirement(|i| i.commute.nights_away > 0.0 && i.commute.home_visits > 0.0)
+        .with_plausible((0.0, 2_000.0)),
+    InputConstraint::new(InputKey::Donations, InputUnit::Nok, PERSONAL).with_check(donations_within_cap),
+    InputConstraint::new(InputKey::IpsContribution, InputUnit::Nok, PERSONAL).with_check(ips_within_cap),
+    InputConstraint::new(InputKey::SicknessCostsTransitional, InputUnit::Choice, PERSONAL),
+    InputConstraint::new(InputKey::SicknessCosts, InputUnit::Nok, PERSONAL)
+        .with_requirement(|i| i.special_deductions.sickness_costs_transitional),
+    InputConstraint::new(InputKey::Occupation, InputUnit::Choice, PERSONAL),
+    InputConstraint::new(InputKey::DividendIncome, InputUnit::Nok, ALL_ENTITIES),
+    InputConstraint::new(InputKey::CapitalGains, InputUnit::Nok, ALL_ENTITIES),
+    InputConstraint::new(InputKey::InvestmentWealth, InputUnit::Nok, PERSONAL),
+    InputConstraint::rate(InputKey::MunicipalTaxRate, ALL_ENTITIES)
+        .with_plausible((5.0, 12.0))
+        .with_check(local_rates_within_total),
+    InputConstraint::rate(InputKey::CountyTaxRate, ALL_ENTITIES).with_check(local_rates_within_total),
+    InputConstraint::new(InputKey::Tiltakssone, InputUnit::Choice, PERSONAL),
+    InputConstraint::new(InputKey::ChurchMember, InputUnit::Choice, PERSONAL),
+    InputConstraint::new(InputKey::HistoricalChurchTax, InputUnit::Choice, PERSONAL),
+    InputConstraint::rate(InputKey::ChurchTaxRate, PERSONAL)
+        .with_plausible((0.0, 2.0))
+        .with_requirement(|i| i.is_church_member && i.historical_church_tax),
+    InputConstraint::new(InputKey::PropertyTax, InputUnit::Choice, PERSONAL),
+    InputConstraint::new(InputKey::HomeValue, InputUnit::Nok, PERSONAL)
+        .with_requirement(|i| i.property_tax.include)
+        .with_plausible((100_000.0, 50_000_000.0)),
+    // Eiendomsskatt on dwellings is capped at 4 promille
+    InputConstraint {
+        max: Some(4.0),
+        ..InputConstraint::new(InputKey::PropertyTaxPerMille, InputUnit::PerMille, PERSONAL)
+    }
+    .with_requirement(|i| i.property_tax.include),
+    InputConstraint::new(InputKey::PropertyTaxBunnfradrag, InputUnit::Nok, PERSONAL)
+        .with_requirement(|i| i.property_tax.include),
+];
+
+pub fn constraint(key: InputKey) -> &'static InputConstraint {
+    CONSTRAINTS
+        .iter()
+        .find(|constraint| constraint.key == key)
+        .expect("every input key has a constraint")
+}
+
+// Whether the calculation reads the input, given the entity type and the
+// inputs it depends on
+pub fn applies(key: InputKey, input: &TaxCalculationInput) -> bool {
+    let constraint = constraint(key);
+    constraint.entities.contains(&input.entity_type) && constraint.requires.is_none_or(|requires| requires(input))
+}
+
+// A message for a value outside the input's range or breaking a rule across
+// inputs, or None when it is fine
+pub fn check_value(key: InputKey, value: f64, input: &TaxCalculationInput) -> Option<String> {
+    let constraint = constraint(key);
+    if let Some(min) = constraint.min.filter(|min| value < *min) {
+        return Some(format!("Må være minst {}", constraint.format(min)));
+    }
+    if let Some(max) = constraint.max.filter(|max| value > *max) {
+        return Some(format!("Kan ikke være over {}", constraint.format(max)));
+    }
+    if let Some((low, high)) = constraint.plausible.filter(|(low, high)| value < *low || value > *high) {
+        return Some(format!(
+            "Uvanlig verdi; vanligvis mellom {} og {}. Sjekk at den er riktig.",
+            constraint.format(low),
+            constraint.format(high)
+        ));
+    }
+    constraint.check.and_then(|check| check(input))
+}
+
+// Inputs that disagree with each other. Unlike TaxWarning these are found
+// before calculating, and point at what the user probably meant to change.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Inconsistency {
+    // An amount left over from another entity type, which this one ignores
+    UnusedInput { key: InputKey, entity_type: EntityType },
+    // Skjerming is calculated from the share value, so a value below the
+    // dividends paid on it is probably missing or mistyped
+    WealthBelowDividends,
+    DeductionsExceedIncome,
+}
+
+impl Inconsistency {
+    pub fn message(&self) -> String {
+        match self {
+            Inconsistency::UnusedInput { key, entity_type } => format!(
+                "{} er fylt inn, men brukes ikke for {}",
+                key.label(),
+                entity_title(*entity_type)
+            ),
+            Inconsistency::WealthBelowDividends => "Aksjeverdien er lavere enn utbyttet. Skjermingsfradraget regnes av \
+                                                    aksjeverdien, så sjekk at den er riktig."
+                .to_string(),
+            Inconsistency::DeductionsExceedIncome => {
+                "Fradragene er større enn inntektene, så en del av dem gir ikke lavere skatt i år".to_string()
+            }
+        }
+    }
+}
+
+type Amount = fn(&TaxCalculationInput) -> Money;
+
+// Amounts the form keeps when switching entity type
+const ENTITY_SPECIFIC_AMOUNTS: [(InputKey, Amount); 8] = [
+    (InputKey::SickPay, |i| i.sick_pay),
+    (InputKey::DisabilityBenefit, |i| i.disability_benefit),
+    (InputKey::BusinessExpenses, |i| i.business_expenses),
+    (InputKey::ResourceRentIncome, |i| i.resource_rent.income),
+    (InputKey::ResourceRentCosts, |i| i.resource_rent.costs),
+    (InputKey::PetroleumInvestments, |i| i.petroleum.investments),
+    (InputKey::BranchPayroll, |i| i.branch_payroll),
+    (InputKey::InvestmentWealth, |i| i.investment_wealth),
+];
+
+pub fn inconsistencies(input: &TaxCalculationInput) -> Vec<Inconsistency> {
+    let mut found = ENTITY_SPECIFIC_AMOUNTS
+        .iter()
+        .filter(|(key, value)| value(input) != Money::ZERO && !constraint(*key).entities.contains(&input.entity_type))
+        .map(|(key, _)| Inconsistency::UnusedInput { key: *key, entity_type: input.entity_type })
+        .collect::<Vec<_>>();
+
+    if applies(InputKey::InvestmentWealth, input)
+        && input.dividend_income > Money::ZERO
+        && input.investment_wealth < input.dividend_income
+    {
+        found.push(Inconsistency::WealthBelowDividends);
+    }
+
+    let mut income = input.gross_income + input.dividend_income + input.capital_gains;
+    if applies(InputKey::SickPay, input) {
+        income += input.sick_pay + input.disability_benefit;
+    }
+    let deductions = if applies(InputKey::CommuteDistance, input) {
+        NorwegianTaxCalculator::personal_deductions_total(input)
+    } else {
+        input.allowable_deductions
+    };
+    if deductions > Money::ZERO && deductions > income {
+        found.push(Inconsistency::DeductionsExceedIncome);
+    }
+    found
+}
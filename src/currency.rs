@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "integrations")]
+use serde_json::Value;
+#[cfg(feature = "integrations")]
+use wasm_bindgen::JsValue;
+
+#[cfg(feature = "integrations")]
+use crate::fetch::fetch_text;
+use crate::money::Money;
+use crate::tax_calculator::NorwegianTaxCalculator;
+
+pub const CURRENCY_KEY: &str = "ntc.currency";
+
+// Norges Bank's daily exchange rates, as NOK per unit of the base currency
+#[cfg(feature = "integrations")]
+const NORGES_BANK_EXR_URL: &str = "https://data.norges-bank.no/api/data/EXR/B";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisplayCurrency {
+    #[default]
+    Nok,
+    Eur,
+    Usd,
+}
+
+impl DisplayCurrency {
+    pub const ALL: [DisplayCurrency; 3] = [DisplayCurrency::Nok, DisplayCurrency::Eur, DisplayCurrency::Usd];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            DisplayCurrency::Nok => "NOK",
+            DisplayCurrency::Eur => "EUR",
+            DisplayCurrency::Usd => "USD",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<DisplayCurrency> {
+        Self::ALL.into_iter().find(|currency| currency.code() == code)
+    }
+
+    // Used until the user sets a rate or one is fetched
+    pub fn default_rate(&self) -> f64 {
+        match self {
+            DisplayCurrency::Nok => 1.0,
+            DisplayCurrency::Eur => 11.5,
+            DisplayCurrency::Usd => 10.5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CurrencySettings {
+    pub currency: DisplayCurrency,
+    // NOK per unit of the display currency
+    pub rate: f64,
+}
+
+impl Default for CurrencySettings {
+    fn default() -> Self {
+        Self {
+            currency: DisplayCurrency::Nok,
+            rate: 1.0,
+        }
+    }
+}
+
+impl CurrencySettings {
+    // The amount in the display currency, which is no longer NOK
+    pub fn convert(&self, nok: Money) -> f64 {
+        match self.currency {
+            DisplayCurrency::Nok => nok.amount(),
+            _ if self.rate > 0.0 => nok.amount() / self.rate,
+            _ => nok.amount(),
+        }
+    }
+
+    // Amounts are always computed in NOK and only converted for display
+    pub fn format(&self, nok: impl Into<Money>) -> String {
+        let amount = self.convert(nok.into());
+        let formatted = NorwegianTaxCalculator::format_currency(amount.abs());
+        let sign = if amount < 0.0 { "-" } else { "" };
+        format!("{}{} {}", sign, formatted, self.currency.code())
+    }
+}
+
+#[cfg(feature = "integrations")]
+pub async fn fetch_nok_rate(currency: DisplayCurrency) -> Result<f64, JsValue> {
+    if currency == DisplayCurrency::Nok {
+        return Ok(1.0);
+    }
+
+    let url = format!(
+        "{}.{}.NOK.SP?format=sdmx-json&lastNObservations=1&locale=en",
+        NORGES_BANK_EXR_URL,
+        currency.code()
+    );
+    let text = fetch_text(&url).await?;
+    let json: Value = serde_json::from_str(&text).map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+    // SDMX-JSON: the single series holds one observation with the rate as a string
+    json.pointer("/data/dataSets/0/series")
+        .and_then(Value::as_object)
+        .and_then(|series| series.values().next())
+        .and_then(|series| series.get("observations")?.as_object()?.values().next_back()?.get(0)?.as_str())
+        .and_then(|rate| rate.parse::<f64>().ok())
+        .ok_or_else(|| JsValue::from_str("unexpected exchange rate response"))
+}
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+
+use crate::money::{Money, Rate};
+use crate::tax_calculator::{BreakdownCategory, TaxBreakdownItem, TaxCalculationResult, TaxRule};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CustomTaxKind {
+    Fixed(Money),
+    // Of the item's base
+    Rate(Rate),
+}
+
+impl Default for CustomTaxKind {
+    fn default() -> Self {
+        CustomTaxKind::Fixed(Money::ZERO)
+    }
+}
+
+impl CustomTaxKind {
+    pub const ALL: [CustomTaxKind; 2] = [CustomTaxKind::Fixed(Money::ZERO), CustomTaxKind::Rate(Rate::ZERO)];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CustomTaxKind::Fixed(_) => "Fast beløp",
+            CustomTaxKind::Rate(_) => "Sats",
+        }
+    }
+
+    pub fn is_rate(&self) -> bool {
+        matches!(self, CustomTaxKind::Rate(_))
+    }
+}
+
+// Items saved before the amount moved into the kind have the kind as a tag
+// and the amount next to it, in NOK or percent
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredKind {
+    Current(CustomTaxKind),
+    Legacy(LegacyKind),
+}
+
+#[derive(Deserialize)]
+enum LegacyKind {
+    Fixed,
+    Rate,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct StoredCustomTax {
+    name: String,
+    kind: Option<StoredKind>,
+    amount: f64,
+    base: CustomTaxBase,
+}
+
+impl From<StoredCustomTax> for CustomTax {
+    fn from(stored: StoredCustomTax) -> CustomTax {
+        let kind = match stored.kind {
+            Some(StoredKind::Current(kind)) => kind,
+            Some(StoredKind::Legacy(LegacyKind::Rate)) => CustomTaxKind::Rate(Rate::from_percent(stored.amount)),
+            Some(StoredKind::Legacy(LegacyKind::Fixed)) | None => CustomTaxKind::Fixed(Money(stored.amount)),
+        };
+        CustomTax {
+            name: stored.name,
+            kind,
+            base: stored.base,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CustomTaxBase {
+    #[default]
+    GrossIncome,
+    Personinntekt,
+    AlminneligInntekt,
+    // The tax under the ordinary rules, for surtaxes and rebates
+    TotalTax,
+}
+
+impl CustomTaxBase {
+    pub const ALL: [CustomTaxBase; 4] = [
+        CustomTaxBase::GrossIncome,
+        CustomTaxBase::Personinntekt,
+        CustomTaxBase::AlminneligInntekt,
+        CustomTaxBase::TotalTax,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CustomTaxBase::GrossIncome => "Bruttoinntekt",
+            CustomTaxBase::Personinntekt => "Personinntekt",
+            CustomTaxBase::AlminneligInntekt => "Alminnelig inntekt",
+            CustomTaxBase::TotalTax => "Samlet skatt",
+        }
+    }
+}
+
+// An extra line item on top of the statutory taxes, e.g. an employer-specific
+// charge or a policy proposal. Negative amounts work as credits. Embedders
+// pass these as JSON, like `{"name": "Avgift", "kind": {"Rate": 1.5},
+// "base": "Personinntekt"}` with the rate in percent.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "StoredCustomTax")]
+pub struct CustomTax {
+    pub name: String,
+    pub kind: CustomTaxKind,
+    // Only used for rates
+    pub base: CustomTaxBase,
+}
+
+impl CustomTax {
+    fn description(&self) -> String {
+        match self.name.trim() {
+            "" => "Egendefinert post".to_string(),
+            name => name.to_string(),
+        }
+    }
+}
+
+// Adds the custom items to a finished result. Rates on the total tax use the
+// tax before any custom items, so the order of the items doesn't matter.
+pub fn apply_custom_taxes(result: &mut TaxCalculationResult, taxes: &[CustomTax], detailed: bool) {
+    let statutory_tax = result.total_tax;
+    let mut custom_total = Money::ZERO;
+
+    for (index, tax) in taxes.iter().enumerate() {
+        let (amount, rate) = match tax.kind {
+            CustomTaxKind::Fixed(amount) => (amount, None),
+            CustomTaxKind::Rate(rate) => {
+                let base = match tax.base {
+                    CustomTaxBase::GrossIncome => result.gross_income,
+                    CustomTaxBase::Personinntekt => result.personinntekt,
+                    CustomTaxBase::AlminneligInntekt => result.alminnelig_inntekt,
+                    CustomTaxBase::TotalTax => statutory_tax,
+                };
+                (base * rate, Some(rate))
+            }
+        };
+        if !amount.amount().is_finite() || amount == Money::ZERO {
+            continue;
+        }
+
+        custom_total += amount;
+        if !detailed {
+            continue;
+        }
+        // By position in the list, since names are free text and may repeat
+        result.breakdown.push(TaxBreakdownItem {
+            id: format!("custom-{}", index + 1).into(),
+            description: tax.description().into(),
+            amount,
+            rate,
+            category: BreakdownCategory::Custom,
+            rule: TaxRule::CustomTax,
+            is_estimate: false,
+        });
+    }
+
+    result.total_tax += custom_total;
+    result.net_income -= custom_total;
+    result.effective_tax_rate = if result.gross_income > Money::ZERO {
+        Rate::from_fraction(result.total_tax / result.gross_income)
+    } else {
+        Rate::ZERO
+    };
+}
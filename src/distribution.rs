@@ -0,0 +1,169 @@
+use crate::commuter::CommuteInput;
+use crate::money::{Money, Rate};
+use crate::special_deductions::SpecialDeductionsInput;
+use crate::tax_calculator::{
+    EntityType, NorwegianTaxCalculator, TaxCalculationInput, PARTICIPATION_EXEMPTION_TAXABLE_SHARE,
+};
+
+pub const MAX_PLAN_YEARS: usize = 20;
+const ALL_RETAINED: Rate = Rate::from_percent(100.0);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistributionPlan {
+    // Share of each year's profit after corporate tax kept in the company
+    pub retained_shares: Vec<Rate>,
+    // Pre-tax return on savings invested in shares, whether held by the
+    // company or the owner
+    pub annual_return: Rate,
+    // Also show the plan with a holding company between the owner and the
+    // operating company
+    pub holding: bool,
+}
+
+impl Default for DistributionPlan {
+    fn default() -> Self {
+        Self {
+            retained_shares: vec![Rate::from_percent(50.0); 10],
+            annual_return: Rate::from_percent(5.0),
+            holding: false,
+        }
+    }
+}
+
+impl DistributionPlan {
+    pub fn years(&self) -> usize {
+        self.retained_shares.len()
+    }
+
+    pub fn set_years(&mut self, years: usize) {
+        let last = self.retained_shares.last().copied().unwrap_or(Rate::from_percent(50.0));
+        self.retained_shares.resize(years.clamp(1, MAX_PLAN_YEARS), last);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PolicyOutcome {
+    pub label: &'static str,
+    // The owner's value after all taxes at the end of each year, as if the
+    // company were emptied then; the first entry is the starting point
+    pub values: Vec<Money>,
+}
+
+impl PolicyOutcome {
+    pub fn final_value(&self) -> Money {
+        self.values.last().copied().unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Ownership {
+    Direct,
+    // Person → Holding AS → Operating AS
+    Holding,
+}
+
+// What the holding company keeps of a dividend from the operating company
+fn holding_dividend(base: &TaxCalculationInput, dividend: Money) -> Money {
+    if dividend <= Money::ZERO {
+        return Money::ZERO;
+    }
+    let holding = TaxCalculationInput {
+        gross_income: Money::ZERO,
+        allowable_deductions: Money::ZERO,
+        commute: CommuteInput::default(),
+        donations: Money::ZERO,
+        ips_contribution: Money::ZERO,
+        special_deductions: SpecialDeductionsInput::default(),
+        dividend_income: dividend,
+        capital_gains: Money::ZERO,
+        investment_wealth: Money::ZERO,
+        ..base.comparison_variant(EntityType::Corporation)
+    };
+    dividend - NorwegianTaxCalculator::calculate_totals_only(&holding).total_tax
+}
+
+// The owner's share of a dividend after shareholder tax
+pub fn net_dividend(base: &TaxCalculationInput, dividend: Money) -> Money {
+    if dividend <= Money::ZERO {
+        return Money::ZERO;
+    }
+    let owner = TaxCalculationInput {
+        gross_income: Money::ZERO,
+        allowable_deductions: Money::ZERO,
+        commute: CommuteInput::default(),
+        donations: Money::ZERO,
+        ips_contribution: Money::ZERO,
+        special_deductions: SpecialDeductionsInput::default(),
+        dividend_income: dividend,
+        capital_gains: Money::ZERO,
+        investment_wealth: Money::ZERO,
+        ..base.comparison_variant(EntityType::Individual)
+    };
+    NorwegianTaxCalculator::calculate_totals_only(&owner).net_income
+}
+
+fn simulate_policy(
+    base: &TaxCalculationInput,
+    annual_return: Rate,
+    retained_shares: &[Rate],
+    ownership: Ownership,
+    label: &'static str,
+) -> PolicyOutcome {
+    let rules = base.tax_year.rules();
+    let company = NorwegianTaxCalculator::calculate_totals_only(&TaxCalculationInput {
+        dividend_income: Money::ZERO,
+        capital_gains: Money::ZERO,
+        investment_wealth: Money::ZERO,
+        ..base.comparison_variant(EntityType::Corporation)
+    });
+    let profit_after_tax = company.taxable_income - company.corporate_tax;
+    // Share returns are nearly tax-free in the company under fritaksmetoden,
+    // while the owner pays shareholder tax on them
+    let company_return = annual_return * (1.0 - (rules.corporate_tax_rate * PARTICIPATION_EXEMPTION_TAXABLE_SHARE).fraction());
+    let private_return = annual_return * (1.0 - rules.investment_tax_rate.fraction());
+
+    // With a holding company the payouts stop there, and the owner is only
+    // taxed when the holding company pays out
+    let mut retained = Money::ZERO;
+    let mut holding = Money::ZERO;
+    let mut private = Money::ZERO;
+    let mut values = vec![Money::ZERO];
+    for &share in retained_shares {
+        let distributed = profit_after_tax * (ALL_RETAINED - share.clamp(Rate::ZERO, ALL_RETAINED));
+        retained = retained + retained * company_return + profit_after_tax - distributed;
+        match ownership {
+            Ownership::Direct => {
+                private = private + private * private_return + net_dividend(base, distributed);
+            }
+            Ownership::Holding => {
+                holding = holding + holding * company_return + holding_dividend(base, distributed);
+            }
+        }
+        let holding_value = holding + holding_dividend(base, retained);
+        values.push(match ownership {
+            Ownership::Direct => private + net_dividend(base, retained),
+            Ownership::Holding => net_dividend(base, holding_value),
+        });
+    }
+
+    PolicyOutcome { label, values }
+}
+
+// The owner's plan next to paying everything out each year and keeping
+// everything in the company until the end, and optionally the plan run
+// through a holding company
+pub fn compare_distribution_policies(base: &TaxCalculationInput, plan: &DistributionPlan) -> Vec<PolicyOutcome> {
+    let years = plan.years();
+    let policy = |retained_shares: &[Rate], ownership: Ownership, label: &'static str| {
+        simulate_policy(base, plan.annual_return, retained_shares, ownership, label)
+    };
+    let mut policies = vec![
+        policy(&plan.retained_shares, Ownership::Direct, "Din plan"),
+        policy(&vec![Rate::ZERO; years], Ownership::Direct, "Alt utbetales hvert år"),
+        policy(&vec![ALL_RETAINED; years], Ownership::Direct, "Alt beholdes i selskapet"),
+    ];
+    if plan.holding {
+        policies.push(policy(&plan.retained_shares, Ownership::Holding, "Din plan via holdingselskap"));
+    }
+    policies
+}
@@ -0,0 +1,103 @@
+use leptos::*;
+use wasm_bindgen::prelude::*;
+use web_sys::{CustomEvent, CustomEventInit, HtmlElement};
+
+use crate::components::TaxCalculator;
+use crate::money::{Money, Rate};
+use crate::routing::Route;
+use crate::tax_calculator::{TaxCalculationInput, TaxCalculationResult, TaxYear};
+
+pub const CALCULATE_EVENT: &str = "ntc-calculate";
+
+// Initial values come from the host element's attributes, e.g.
+// `<ntc-calculator gross-income="750000" entity-type="enk">`. Entity types
+// use the same slugs as the routes.
+fn input_from_attributes(host: &HtmlElement) -> TaxCalculationInput {
+    let mut input = TaxCalculationInput::default();
+    let number = |name: &str| host.get_attribute(name).and_then(|value| value.trim().parse::<f64>().ok());
+
+    if let Some(Route::Entity(entity_type)) = host
+        .get_attribute("entity-type")
+        .and_then(|slug| Route::from_path(&format!("/{}", slug.trim())))
+    {
+        input.entity_type = entity_type;
+    }
+    if let Some(tax_year) = host
+        .get_attribute("tax-year")
+        .and_then(|year| year.trim().parse().ok())
+        .and_then(TaxYear::from_year)
+    {
+        input.tax_year = tax_year;
+    }
+    if let Some(member) = host.get_attribute("church-member") {
+        input.is_church_member = member != "false";
+    }
+    if let Some(historical) = host.get_attribute("historical-church-tax") {
+        input.historical_church_tax = historical != "false";
+    }
+
+    // A JSON array in the same shape as the input's custom_taxes
+    if let Some(custom_taxes) = host
+        .get_attribute("custom-taxes")
+        .and_then(|json| serde_json::from_str(&json).ok())
+    {
+        input.custom_taxes = custom_taxes;
+    }
+
+    let amounts: [(&str, &mut Money); 7] = [
+        ("gross-income", &mut input.gross_income),
+        ("deductions", &mut input.allowable_deductions),
+        ("business-expenses", &mut input.business_expenses),
+        ("branch-payroll", &mut input.branch_payroll),
+        ("dividend-income", &mut input.dividend_income),
+        ("capital-gains", &mut input.capital_gains),
+        ("investment-wealth", &mut input.investment_wealth),
+    ];
+    for (name, field) in amounts {
+        if let Some(value) = number(name) {
+            *field = Money(value);
+        }
+    }
+
+    // In percent, as in the form
+    let rates: [(&str, &mut Rate); 3] = [
+        ("municipal-tax-rate", &mut input.municipal_tax_rate),
+        ("county-tax-rate", &mut input.county_tax_rate),
+        ("church-tax-rate", &mut input.church_tax_rate),
+    ];
+    for (name, field) in rates {
+        if let Some(value) = number(name) {
+            *field = Rate::from_percent(value);
+        }
+    }
+
+    input
+}
+
+fn dispatch_result(host: &HtmlElement, result: &TaxCalculationResult) {
+    let detail = serde_json::to_string(result)
+        .ok()
+        .and_then(|json| js_sys::JSON::parse(&json).ok())
+        .unwrap_or(JsValue::NULL);
+    let init = CustomEventInit::new();
+    init.set_detail(&detail);
+    init.set_bubbles(true);
+    init.set_composed(true);
+    if let Ok(event) = CustomEvent::new_with_event_init_dict(CALCULATE_EVENT, &init) {
+        let _ = host.dispatch_event(&event);
+    }
+}
+
+// Entry point for the `<ntc-calculator>` custom element defined in
+// embed/ntc-calculator.js
+#[wasm_bindgen]
+pub fn mount_calculator(host: HtmlElement) {
+    crate::set_panic_hook();
+    let initial_input = input_from_attributes(&host);
+    let target = host.clone();
+    let on_calculate = Callback::new(move |result: TaxCalculationResult| dispatch_result(&target, &result));
+
+    leptos::mount_to(host, move || view! {
+        <TaxCalculator embedded=true initial_input=initial_input on_calculate=on_calculate />
+    });
+}
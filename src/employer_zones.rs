@@ -0,0 +1,59 @@
+// Without the employer-cost feature the engine only uses zone 1
+#![cfg_attr(not(feature = "employer-cost"), allow(dead_code))]
+
+use crate::money::Rate;
+
+// Arbeidsgiveravgift zones. The reduced rates in zones 1a and 4a apply up to
+// a fribeløp per enterprise that a single employee never reaches, so it isn't modelled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EmployerZone {
+    #[default]
+    Zone1,
+    Zone1a,
+    Zone2,
+    Zone3,
+    Zone4,
+    Zone4a,
+    Zone5,
+}
+
+impl EmployerZone {
+    pub const ALL: [EmployerZone; 7] = [
+        EmployerZone::Zone1,
+        EmployerZone::Zone1a,
+        EmployerZone::Zone2,
+        EmployerZone::Zone3,
+        EmployerZone::Zone4,
+        EmployerZone::Zone4a,
+        EmployerZone::Zone5,
+    ];
+
+    // As written in the a-melding's avgiftssone field
+    pub fn code(&self) -> &'static str {
+        match self {
+            EmployerZone::Zone1 => "1",
+            EmployerZone::Zone1a => "1a",
+            EmployerZone::Zone2 => "2",
+            EmployerZone::Zone3 => "3",
+            EmployerZone::Zone4 => "4",
+            EmployerZone::Zone4a => "4a",
+            EmployerZone::Zone5 => "5",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<EmployerZone> {
+        Self::ALL.into_iter().find(|zone| zone.code() == code)
+    }
+
+    pub fn rate(&self) -> Rate {
+        match self {
+            EmployerZone::Zone1 => Rate::from_percent(14.1),
+            EmployerZone::Zone1a => Rate::from_percent(10.6),
+            EmployerZone::Zone2 => Rate::from_percent(10.6),
+            EmployerZone::Zone3 => Rate::from_percent(6.4),
+            EmployerZone::Zone4 => Rate::from_percent(5.1),
+            EmployerZone::Zone4a => Rate::from_percent(7.9),
+            EmployerZone::Zone5 => Rate::ZERO,
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::cell::{OnceCell, RefCell};
+use std::sync::Once;
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::fuzzing::invariant_violations;
+use crate::tax_calculator::{TaxCalculationInput, TaxCalculationResult};
+
+// Reports are posted as JSON to the URL in
+// `<meta name="ntc-error-endpoint" content="https://example.no/ntc-errors">`
+// and passed to the callback given to set_error_reporter. Without either
+// nothing is collected or sent.
+const ENDPOINT_META: &str = "meta[name=\"ntc-error-endpoint\"]";
+
+thread_local! {
+    static ENDPOINT: OnceCell<Option<String>> = const { OnceCell::new() };
+    static REPORTER: RefCell<Option<js_sys::Function>> = const { RefCell::new(None) };
+    // Input of the latest calculation, so a panic anywhere in the app is
+    // reported with the state it happened in
+    static CURRENT_INPUT: RefCell<Option<TaxCalculationInput>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorKind {
+    Panic,
+    // A result that breaks the engine's invariants, see fuzzing.rs
+    Invariant,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub version: &'static str,
+    pub input: Option<TaxCalculationInput>,
+}
+
+// For a host page that collects reports itself. The callback gets each
+// report as a plain object.
+#[wasm_bindgen]
+pub fn set_error_reporter(reporter: js_sys::Function) {
+    REPORTER.with(|current| *current.borrow_mut() = Some(reporter));
+}
+
+fn endpoint() -> Option<String> {
+    ENDPOINT.with(|endpoint| {
+        endpoint
+            .get_or_init(|| {
+                web_sys::window()?
+                    .document()?
+                    .query_selector(ENDPOINT_META)
+                    .ok()??
+                    .get_attribute("content")
+                    .filter(|url| !url.trim().is_empty())
+            })
+            .clone()
+    })
+}
+
+fn reporter() -> Option<js_sys::Function> {
+    REPORTER.with(|reporter| reporter.try_borrow().ok()?.clone())
+}
+
+pub fn enabled() -> bool {
+    reporter().is_some() || endpoint().is_some()
+}
+
+// Free text the user typed is left out. Amounts and rates stay, since the
+// calculation can't be reproduced without them.
+fn anonymized(input: &TaxCalculationInput) -> TaxCalculationInput {
+    let mut input = input.clone();
+    input.municipality = None;
+    for (index, tax) in input.custom_taxes.iter_mut().enumerate() {
+        tax.name = format!("Post {}", index + 1);
+    }
+    input
+}
+
+pub fn set_current_input(input: &TaxCalculationInput) {
+    if !enabled() {
+        return;
+    }
+    CURRENT_INPUT.with(|current| {
+        if let Ok(mut current) = current.try_borrow_mut() {
+            *current = Some(anonymized(input));
+        }
+    });
+}
+
+// sendBeacon rather than fetch, since it is synchronous to start and still
+// goes out when a panic has left the app unusable
+fn send(report: &ErrorReport) {
+    let Ok(json) = serde_json::to_string(report) else {
+        return;
+    };
+    if let (Some(endpoint), Some(window)) = (endpoint(), web_sys::window()) {
+        let _ = window.navigator().send_beacon_with_opt_str(&endpoint, Some(&json));
+    }
+    if let (Some(reporter), Ok(report)) = (reporter(), js_sys::JSON::parse(&json)) {
+        let _ = reporter.call1(&JsValue::NULL, &report);
+    }
+}
+
+fn report(kind: ErrorKind, message: String, input: Option<TaxCalculationInput>) {
+    send(&ErrorReport {
+        kind,
+        message,
+        version: env!("CARGO_PKG_VERSION"),
+        input,
+    });
+}
+
+// In place of console_error_panic_hook::set_once; panics still go to the
+// console as before
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        std::panic::set_hook(Box::new(|info| {
+            console_error_panic_hook::hook(info);
+            if enabled() {
+                let input = CURRENT_INPUT.with(|current| current.try_borrow().ok()?.clone());
+                report(ErrorKind::Panic, info.to_string(), input);
+            }
+        }));
+    });
+}
+
+pub fn check_result(input: &TaxCalculationInput, result: &TaxCalculationResult) {
+    if !enabled() {
+        return;
+    }
+    let violations = invariant_violations(input, result);
+    if !violations.is_empty() {
+        report(ErrorKind::Invariant, violations.join("; "), Some(anonymized(input)));
+    }
+}
@@ -0,0 +1,601 @@
+use crate::constraints::{applies, InputKey};
+use crate::industries::{PetroleumInput, ResourceRentActivity};
+use crate::money::{Money, Rate};
+use crate::special_deductions::Occupation;
+use crate::tax_calculator::{
+    EntityType, InputSection, NorwegianTaxCalculator, SickPayInsurance, TaxCalculationInput, TaxRule,
+};
+
+pub enum FieldKind {
+    Amount {
+        get: fn(&TaxCalculationInput) -> Money,
+        set: fn(&mut TaxCalculationInput, Money),
+        step: f64,
+    },
+    // Edited in percent
+    Rate {
+        get: fn(&TaxCalculationInput) -> Rate,
+        set: fn(&mut TaxCalculationInput, Rate),
+    },
+    // Edited in promille
+    PerMille {
+        get: fn(&TaxCalculationInput) -> Rate,
+        set: fn(&mut TaxCalculationInput, Rate),
+    },
+    // A count or distance, shown with its unit
+    Quantity {
+        get: fn(&TaxCalculationInput) -> f64,
+        set: fn(&mut TaxCalculationInput, f64),
+        step: f64,
+        unit: &'static str,
+    },
+    Checkbox {
+        get: fn(&TaxCalculationInput) -> bool,
+        set: fn(&mut TaxCalculationInput, bool),
+    },
+    // Options are labels, and the value is an index into them
+    Select {
+        options: fn() -> Vec<&'static str>,
+        get: fn(&TaxCalculationInput) -> usize,
+        set: fn(&mut TaxCalculationInput, usize),
+    },
+    // Explanatory text under the fields before it
+    Note(fn(&TaxCalculationInput) -> String),
+}
+
+impl FieldKind {
+    // The number in the field, in the unit it is edited in
+    pub fn number(&self, input: &TaxCalculationInput) -> Option<f64> {
+        match self {
+            FieldKind::Amount { get, .. } => Some(get(input).amount()),
+            FieldKind::Rate { get, .. } => Some(get(input).percent()),
+            FieldKind::PerMille { get, .. } => Some(get(input).per_mille()),
+            FieldKind::Quantity { get, .. } => Some(get(input)),
+            _ => None,
+        }
+    }
+
+    // The value as the user sees it, or None for notes
+    pub fn display(&self, input: &TaxCalculationInput) -> Option<String> {
+        match self {
+            FieldKind::Amount { get, .. } => Some(NorwegianTaxCalculator::format_nok(get(input))),
+            FieldKind::Rate { get, .. } => Some(format!("{:.2} %", get(input).percent())),
+            FieldKind::PerMille { get, .. } => Some(format!("{} ‰", get(input).per_mille())),
+            FieldKind::Quantity { get, unit, .. } => Some(format!("{} {}", get(input), unit)),
+            FieldKind::Checkbox { get, .. } => Some(if get(input) { "Ja" } else { "Nei" }.to_string()),
+            FieldKind::Select { options, get, .. } => options().get(get(input)).map(|option| option.to_string()),
+            FieldKind::Note(_) => None,
+        }
+    }
+}
+
+pub struct FieldSpec {
+    pub section: InputSection,
+    // The engine input behind the field. Its constraints decide when the
+    // field shows and what range it accepts.
+    pub key: Option<InputKey>,
+    pub label: &'static str,
+    pub rule: Option<TaxRule>,
+    pub kind: FieldKind,
+    // Extra condition for fields without an engine input, such as notes
+    pub visible: fn(&TaxCalculationInput) -> bool,
+    // Takes focus when an edit in the form reveals it
+    pub focus_when_revealed: bool,
+}
+
+impl FieldSpec {
+    const fn new(key: InputKey, section: InputSection, label: &'static str, kind: FieldKind) -> Self {
+        FieldSpec {
+            section,
+            key: Some(key),
+            label,
+            rule: None,
+            kind,
+            visible: |_| true,
+            focus_when_revealed: false,
+        }
+    }
+
+    const fn amount(
+        key: InputKey,
+        section: InputSection,
+        label: &'static str,
+        get: fn(&TaxCalculationInput) -> Money,
+        set: fn(&mut TaxCalculationInput, Money),
+        step: f64,
+    ) -> Self {
+        Self::new(key, section, label, FieldKind::Amount { get, set, step })
+    }
+
+    const fn note(
+        section: InputSection,
+        text: fn(&TaxCalculationInput) -> String,
+        visible: fn(&TaxCalculationInput) -> bool,
+    ) -> Self {
+        FieldSpec {
+            section,
+            key: None,
+            label: "",
+            rule: None,
+            kind: FieldKind::Note(text),
+            visible,
+            focus_when_revealed: false,
+        }
+    }
+
+    const fn with_rule(self, rule: TaxRule) -> Self {
+        FieldSpec { rule: Some(rule), ..self }
+    }
+
+    const fn with_focus_when_revealed(self) -> Self {
+        FieldSpec { focus_when_revealed: true, ..self }
+    }
+
+    pub fn is_visible(&self, input: &TaxCalculationInput) -> bool {
+        self.key.is_none_or(|key| applies(key, input)) && (self.visible)(input)
+    }
+}
+
+fn sick_pay_insurance_options() -> Vec<&'static str> {
+    SickPayInsurance::ALL.iter().map(SickPayInsurance::label).collect()
+}
+
+fn occupation_options() -> Vec<&'static str> {
+    Occupation::ALL.iter().map(Occupation::label).collect()
+}
+
+fn resource_rent_options() -> Vec<&'static str> {
+    ResourceRentActivity::ALL.iter().map(ResourceRentActivity::label).collect()
+}
+
+fn petroleum_note(input: &TaxCalculationInput) -> String {
+    let ordinary = NorwegianTaxCalculator::calculate_totals_only(&TaxCalculationInput {
+        petroleum: PetroleumInput::default(),
+        ..input.clone()
+    });
+    format!(
+        "Effektiv skattesats {:.1} % mot {:.1} % for et vanlig aksjeselskap. Marginalskatten er 78 % mot 22 %.",
+        NorwegianTaxCalculator::calculate_totals_only(input).effective_tax_rate.percent(),
+        ordinary.effective_tax_rate.percent(),
+    )
+}
+
+// Every generic input in the form, in render order within each section.
+// Inputs that need more than a value, like the municipality list and the
+// custom tax editor, are written by hand next to the generated ones.
+pub const FORM_FIELDS: &[FieldSpec] = &[
+    FieldSpec::amount(
+        InputKey::GrossIncome,
+        InputSection::Income,
+        "Bruttoinntekt (NOK)",
+        |i| i.gross_income,
+        |i, v| i.gross_income = v,
+        1000.0,
+    )
+    .with_rule(TaxRule::GrossIncome),
+    FieldSpec::note(
+        InputSection::Income,
+        |_| {
+            "Bruttoinntekt er omsetningen fra økonomisk virksomhet, som loddsalg, kiosk og utleie. \
+             Gaver, tilskudd og medlemskontingent holdes utenfor."
+                .to_string()
+        },
+        |i| i.entity_type == EntityType::Nonprofit,
+    ),
+    FieldSpec::amount(
+        InputKey::SickPay,
+        InputSection::Income,
+        "Sykepenger (NOK)",
+        |i| i.sick_pay,
+        |i, v| i.sick_pay = v,
+        1000.0,
+    )
+    .with_rule(TaxRule::SickPay),
+    FieldSpec::amount(
+        InputKey::DisabilityBenefit,
+        InputSection::Income,
+        "Uføretrygd (NOK)",
+        |i| i.disability_benefit,
+        |i, v| i.disability_benefit = v,
+        1000.0,
+    )
+    .with_rule(TaxRule::DisabilityBenefit),
+    FieldSpec::amount(
+        InputKey::BusinessExpenses,
+        InputSection::Income,
+        "Driftskostnader (NOK)",
+        |i| i.business_expenses,
+        |i, v| i.business_expenses = v,
+        1000.0,
+    )
+    .with_rule(TaxRule::BusinessExpenses),
+    FieldSpec::new(
+        InputKey::SickPayInsurance,
+        InputSection::Income,
+        "Tilleggstrygd for sykepenger",
+        FieldKind::Select {
+            options: sick_pay_insurance_options,
+            get: |i| SickPayInsurance::ALL.iter().position(|insurance| *insurance == i.sick_pay_insurance).unwrap_or(0),
+            set: |i, index| i.sick_pay_insurance = SickPayInsurance::ALL.get(index).copied().unwrap_or_default(),
+        },
+    )
+    .with_rule(TaxRule::SickPayInsurance),
+    FieldSpec::new(
+        InputKey::ResourceRentActivity,
+        InputSection::Income,
+        "Grunnrenteskattepliktig virksomhet",
+        FieldKind::Select {
+            options: resource_rent_options,
+            get: |i| ResourceRentActivity::ALL.iter().position(|activity| *activity == i.resource_rent.activity).unwrap_or(0),
+            set: |i, index| i.resource_rent.activity = ResourceRentActivity::ALL.get(index).copied().unwrap_or_default(),
+        },
+    )
+    .with_rule(TaxRule::ResourceRentTax),
+    FieldSpec::amount(
+        InputKey::ResourceRentIncome,
+        InputSection::Income,
+        "Grunnrenteinntekt (NOK)",
+        |i| i.resource_rent.income,
+        |i, v| i.resource_rent.income = v,
+        100000.0,
+    )
+    .with_rule(TaxRule::ResourceRentTax),
+    FieldSpec::amount(
+        InputKey::ResourceRentCosts,
+        InputSection::Income,
+        "Kostnader i grunnrentevirksomheten (NOK)",
+        |i| i.resource_rent.costs,
+        |i, v| i.resource_rent.costs = v,
+        100000.0,
+    )
+    .with_rule(TaxRule::ResourceRentTax),
+    FieldSpec::new(
+        InputKey::Petroleum,
+        InputSection::Income,
+        "Petroleumsvirksomhet (særskatt)",
+        FieldKind::Checkbox { get: |i| i.petroleum.enabled, set: |i, v| i.petroleum.enabled = v },
+    )
+    .with_rule(TaxRule::PetroleumTax),
+    FieldSpec::amount(
+        InputKey::PetroleumInvestments,
+        InputSection::Income,
+        "Investeringer i året (NOK)",
+        |i| i.petroleum.investments,
+        |i, v| i.petroleum.investments = v,
+        100000.0,
+    )
+    .with_rule(TaxRule::PetroleumTax),
+    FieldSpec::note(InputSection::Income, petroleum_note, |i| applies(InputKey::PetroleumInvestments, i)),
+    FieldSpec::amount(
+        InputKey::BranchPayroll,
+        InputSection::Income,
+        "Lønn til ansatte i Norge (NOK)",
+        |i| i.branch_payroll,
+        |i, v| i.branch_payroll = v,
+        1000.0,
+    )
+    .with_rule(TaxRule::EmployerContribution),
+    FieldSpec::new(
+        InputKey::CharitablePurpose,
+        InputSection::Income,
+        "Veldedig eller allmennyttig formål",
+        FieldKind::Checkbox { get: |i| i.charitable_purpose, set: |i, v| i.charitable_purpose = v },
+    )
+    .with_rule(TaxRule::Nonprofit),
+    FieldSpec::amount(
+        InputKey::Deductions,
+        InputSection::Deductions,
+        "Fradrag (NOK)",
+        |i| i.allowable_deductions,
+        |i, v| i.allowable_deductions = v,
+        1000.0,
+    )
+    .with_rule(TaxRule::Deductions),
+    FieldSpec::amount(
+        InputKey::Donations,
+        InputSection::Deductions,
+        "Gaver til godkjente organisasjoner (NOK)",
+        |i| i.donations,
+        |i, v| i.donations = v,
+        500.0,
+    )
+    .with_rule(TaxRule::Donations),
+    FieldSpec::amount(
+        InputKey::IpsContribution,
+        InputSection::Deductions,
+        "Innskudd på IPS (NOK)",
+        |i| i.ips_contribution,
+        |i, v| i.ips_contribution = v,
+        1000.0,
+    )
+    .with_rule(TaxRule::IndividualPensionSaving),
+    FieldSpec::new(
+        InputKey::SicknessCostsTransitional,
+        InputSection::Deductions,
+        "Hadde særfradrag for store sykdomskostnader for 2010 eller 2011",
+        FieldKind::Checkbox {
+            get: |i| i.special_deductions.sickness_costs_transitional,
+            set: |i, v| i.special_deductions.sickness_costs_transitional = v,
+        },
+    )
+    .with_rule(TaxRule::LargeSicknessCosts),
+    FieldSpec::amount(
+        InputKey::SicknessCosts,
+        InputSection::Deductions,
+        "Dokumenterte sykdomskostnader (NOK)",
+        |i| i.special_deductions.sickness_costs,
+        |i, v| i.special_deductions.sickness_costs = v,
+        1000.0,
+    )
+    .with_rule(TaxRule::LargeSicknessCosts)
+    .with_focus_when_revealed(),
+    FieldSpec::new(
+        InputKey::Occupation,
+        InputSection::Deductions,
+        "Yrke med særskilt fradrag",
+        FieldKind::Select {
+            options: occupation_options,
+            get: |i| Occupation::ALL.iter().position(|occupation| *occupation == i.special_deductions.occupation).unwrap_or(0),
+            set: |i, index| i.special_deductions.occupation = Occupation::ALL.get(index).copied().unwrap_or_default(),
+        },
+    )
+    .with_rule(TaxRule::OccupationDeduction),
+    FieldSpec::note(
+        InputSection::Deductions,
+        |i| {
+            let rules = i.rules();
+            let special = &i.special_deductions;
+            format!(
+                "{} er {:.0} % av inntekten, opp til {}. Sjekk at vilkårene er oppfylt, som minst 130 døgn om bord for sjøfolk.",
+                special.occupation.deduction_name(),
+                rules.occupation_deduction_rate.percent(),
+                NorwegianTaxCalculator::format_nok(rules.occupation_deduction_max)
+            )
+        },
+        |i| applies(InputKey::Occupation, i) && i.special_deductions.occupation != Occupation::Other,
+    ),
+    FieldSpec::new(
+        InputKey::CommuteDistance,
+        InputSection::Deductions,
+        "Avstand hjem–arbeid, én vei (km)",
+        FieldKind::Quantity {
+            get: |i| i.commute.distance_km,
+            set: |i, v| i.commute.distance_km = v,
+            step: 1.0,
+            unit: "km",
+        },
+    )
+    .with_rule(TaxRule::CommuterDeduction),
+    FieldSpec::new(
+        InputKey::CommuteWorkdays,
+        InputSection::Deductions,
+        "Arbeidsdager med reise",
+        FieldKind::Quantity {
+            get: |i| i.commute.workdays,
+            set: |i, v| i.commute.workdays = v,
+            step: 1.0,
+            unit: "dager",
+        },
+    )
+    .with_rule(TaxRule::CommuterDeduction)
+    .with_focus_when_revealed(),
+    FieldSpec::new(
+        InputKey::NightsAway,
+        InputSection::Deductions,
+        "Døgn borte fra hjemmet som pendler",
+        FieldKind::Quantity {
+            get: |i| i.commute.nights_away,
+            set: |i, v| i.commute.nights_away = v,
+            step: 1.0,
+            unit: "døgn",
+        },
+    )
+    .with_rule(TaxRule::LivingAwayFromHome),
+    FieldSpec::new(
+        InputKey::LodgingDocumented,
+        InputSection::Deductions,
+        "Losjiutgiftene er dokumentert",
+        FieldKind::Checkbox {
+            get: |i| i.commute.lodging_documented,
+            set: |i, v| i.commute.lodging_documented = v,
+        },
+    )
+    .with_rule(TaxRule::LivingAwayFromHome),
+    FieldSpec::amount(
+        InputKey::Lodging,
+        InputSection::Deductions,
+        "Losji (NOK)",
+        |i| i.commute.lodging,
+        |i, v| i.commute.lodging = v,
+        1000.0,
+    )
+    .with_rule(TaxRule::LivingAwayFromHome)
+    .with_focus_when_revealed(),
+    FieldSpec::new(
+        InputKey::Kitchen,
+        InputSection::Deductions,
+        "Kokemulighet der du bor",
+        FieldKind::Checkbox { get: |i| i.commute.kitchen, set: |i, v| i.commute.kitchen = v },
+    )
+    .with_rule(TaxRule::LivingAwayFromHome),
+    FieldSpec::new(
+        InputKey::HomeVisits,
+        InputSection::Deductions,
+        "Besøksreiser hjem",
+        FieldKind::Quantity {
+            get: |i| i.commute.home_visits,
+            set: |i, v| i.commute.home_visits = v,
+            step: 1.0,
+            unit: "reiser",
+        },
+    )
+    .with_rule(TaxRule::CommuterDeduction),
+    FieldSpec::new(
+        InputKey::HomeVisitDistance,
+        InputSection::Deductions,
+        "Avstand til hjemmet, én vei (km)",
+        FieldKind::Quantity {
+            get: |i| i.commute.home_visit_km,
+            set: |i, v| i.commute.home_visit_km = v,
+            step: 10.0,
+            unit: "km",
+        },
+    )
+    .with_rule(TaxRule::CommuterDeduction)
+    .with_focus_when_revealed(),
+    FieldSpec::note(
+        InputSection::Deductions,
+        |i| {
+            let rules = i.rules();
+            format!(
+                "{:.0} km i året gir {} i reiseutgifter. Etter bunnbeløpet på {} blir reisefradraget {}.",
+                i.commute.yearly_km(),
+                NorwegianTaxCalculator::format_nok(i.commute.travel_expense(&rules)),
+                NorwegianTaxCalculator::format_nok(rules.commuter_threshold),
+                NorwegianTaxCalculator::format_nok(i.commute.deduction(&rules))
+            )
+        },
+        |i| applies(InputKey::CommuteDistance, i) && i.commute.yearly_km() > 0.0,
+    ),
+    FieldSpec::note(
+        InputSection::Deductions,
+        |i| {
+            let rules = i.rules();
+            let lodging = if i.commute.lodging_documented {
+                "dokumenterte utgifter".to_string()
+            } else {
+                format!("{} per døgn uten dokumentasjon", NorwegianTaxCalculator::format_nok(rules.commuter_lodging_per_night))
+            };
+            let board = if i.commute.kitchen {
+                rules.commuter_board_with_kitchen
+            } else {
+                rules.commuter_board_without_kitchen
+            };
+            format!(
+                "Pendlerfradrag: losji {} ({}) og kost {} ({} per døgn).",
+                NorwegianTaxCalculator::format_nok(i.commute.lodging_deduction(&rules)),
+                lodging,
+                NorwegianTaxCalculator::format_nok(i.commute.board_deduction(&rules)),
+                NorwegianTaxCalculator::format_nok(board)
+            )
+        },
+        |i| applies(InputKey::Kitchen, i),
+    ),
+    FieldSpec::amount(
+        InputKey::DividendIncome,
+        InputSection::Investments,
+        "Aksjeutbytte (NOK)",
+        |i| i.dividend_income,
+        |i, v| i.dividend_income = v,
+        1000.0,
+    )
+    .with_rule(TaxRule::ShareholderTax),
+    FieldSpec::amount(
+        InputKey::CapitalGains,
+        InputSection::Investments,
+        "Aksjegevinst (NOK)",
+        |i| i.capital_gains,
+        |i, v| i.capital_gains = v,
+        1000.0,
+    )
+    .with_rule(TaxRule::ShareholderTax),
+    FieldSpec::amount(
+        InputKey::InvestmentWealth,
+        InputSection::Investments,
+        "Aksjeverdi for formueskatt (NOK)",
+        |i| i.investment_wealth,
+        |i, v| i.investment_wealth = v,
+        10000.0,
+    )
+    .with_rule(TaxRule::WealthTax),
+    FieldSpec::new(
+        InputKey::MunicipalTaxRate,
+        InputSection::Rates,
+        "Kommuneskatt (%)",
+        FieldKind::Rate { get: |i| i.municipal_tax_rate, set: |i, v| i.municipal_tax_rate = v },
+    )
+    .with_rule(TaxRule::MunicipalTax),
+    FieldSpec::new(
+        InputKey::CountyTaxRate,
+        InputSection::Rates,
+        "Fylkeskatt (%)",
+        FieldKind::Rate { get: |i| i.county_tax_rate, set: |i, v| i.county_tax_rate = v },
+    )
+    .with_rule(TaxRule::CountyTax),
+    FieldSpec::new(
+        InputKey::Tiltakssone,
+        InputSection::Rates,
+        "Finnmark eller Nord-Troms (tiltakssonen)",
+        FieldKind::Checkbox { get: |i| i.tiltakssone, set: |i, v| i.tiltakssone = v },
+    )
+    .with_rule(TaxRule::Fellesskatt),
+    FieldSpec::note(
+        InputSection::Rates,
+        |i| format!("Fellesskatt til staten: {:.2} %", NorwegianTaxCalculator::fellesskatt_rate(i).percent()),
+        |i| applies(InputKey::Tiltakssone, i),
+    ),
+    FieldSpec::new(
+        InputKey::ChurchMember,
+        InputSection::Rates,
+        "Medlem av Den norske kirke",
+        FieldKind::Checkbox { get: |i| i.is_church_member, set: |i, v| i.is_church_member = v },
+    )
+    .with_rule(TaxRule::ChurchTax),
+    FieldSpec::new(
+        InputKey::HistoricalChurchTax,
+        InputSection::Rates,
+        "Historisk modell med kirkeskatt",
+        FieldKind::Checkbox { get: |i| i.historical_church_tax, set: |i, v| i.historical_church_tax = v },
+    ),
+    FieldSpec::new(
+        InputKey::ChurchTaxRate,
+        InputSection::Rates,
+        "Kirkeskatt (%)",
+        FieldKind::Rate { get: |i| i.church_tax_rate, set: |i, v| i.church_tax_rate = v },
+    )
+    .with_rule(TaxRule::ChurchTax)
+    .with_focus_when_revealed(),
+    FieldSpec::new(
+        InputKey::PropertyTax,
+        InputSection::Rates,
+        "Ta med eiendomsskatt på boligen i samlet skatt",
+        FieldKind::Checkbox { get: |i| i.property_tax.include, set: |i, v| i.property_tax.include = v },
+    )
+    .with_rule(TaxRule::PropertyTax),
+    FieldSpec::amount(
+        InputKey::HomeValue,
+        InputSection::Rates,
+        "Eiendomsskattegrunnlag for boligen (NOK)",
+        |i| i.property_tax.home_value,
+        |i, v| i.property_tax.home_value = v,
+        100000.0,
+    )
+    .with_rule(TaxRule::PropertyTax)
+    .with_focus_when_revealed(),
+    FieldSpec::new(
+        InputKey::PropertyTaxPerMille,
+        InputSection::Rates,
+        "Eiendomsskattesats (‰)",
+        FieldKind::PerMille { get: |i| i.property_tax.rate, set: |i, v| i.property_tax.rate = v },
+    )
+    .with_rule(TaxRule::PropertyTax),
+    FieldSpec::amount(
+        InputKey::PropertyTaxBunnfradrag,
+        InputSection::Rates,
+        "Bunnfradrag i eiendomsskatten (NOK)",
+        |i| i.property_tax.bunnfradrag,
+        |i, v| i.property_tax.bunnfradrag = v,
+        10000.0,
+    )
+    .with_rule(TaxRule::PropertyTax),
+    FieldSpec::note(
+        InputSection::Rates,
+        |i| {
+            format!(
+                "Anslått eiendomsskatt: {} i året. Den betales til kommunen og er ikke en del av inntektsskatten.",
+                NorwegianTaxCalculator::format_nok(i.property_tax.estimate())
+            )
+        },
+        |i| applies(InputKey::HomeValue, i),
+    ),
+];
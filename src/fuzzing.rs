@@ -0,0 +1,246 @@
+use std::collections::HashSet;
+
+use crate::commuter::CommuteInput;
+use crate::custom_taxes::{CustomTax, CustomTaxBase, CustomTaxKind};
+use crate::industries::{PetroleumInput, ResourceRentActivity, ResourceRentInput};
+use crate::money::{Money, Rate};
+use crate::property_tax::PropertyTaxInput;
+use crate::special_deductions::{Occupation, SpecialDeductionsInput};
+use crate::tax_calculator::{
+    EntityType, NorwegianTaxCalculator, PolicyChanges, SickPayInsurance, TaxCalculationInput, TaxCalculationResult,
+    TaxYear,
+};
+
+// Amounts go up to 10 billion NOK, in both directions where the UI lets the
+// user type a negative number
+const MAX_AMOUNT: f64 = 1e10;
+const MAX_CUSTOM_TAXES: u8 = 4;
+
+// Reads fields from the fuzzer's bytes. Once the bytes run out every read
+// returns zero, so any input decodes.
+struct Bytes<'a>(&'a [u8]);
+
+impl Bytes<'_> {
+    fn byte(&mut self) -> u8 {
+        match self.0.split_first() {
+            Some((&byte, rest)) => {
+                self.0 = rest;
+                byte
+            }
+            None => 0,
+        }
+    }
+
+    fn flag(&mut self) -> bool {
+        self.byte() & 1 == 1
+    }
+
+    fn pick<T: Copy>(&mut self, options: &[T]) -> T {
+        options[self.byte() as usize % options.len()]
+    }
+
+    fn fraction(&mut self) -> f64 {
+        let bytes = [self.byte(), self.byte(), self.byte(), self.byte()];
+        u32::from_le_bytes(bytes) as f64 / u32::MAX as f64
+    }
+
+    // Mostly ordinary amounts, sometimes zero or an extreme
+    fn amount(&mut self) -> Money {
+        Money(match self.byte() % 8 {
+            0 => 0.0,
+            1 => -self.fraction() * MAX_AMOUNT,
+            2 => self.fraction() * MAX_AMOUNT,
+            _ => self.fraction() * 5_000_000.0,
+        })
+    }
+
+    fn rate(&mut self, max_percent: f64) -> Rate {
+        Rate::from_percent(self.fraction() * max_percent)
+    }
+}
+
+fn entity_type(byte: u8) -> EntityType {
+    match byte % 6 {
+        0 => EntityType::Individual,
+        1 => EntityType::Corporation,
+        2 => EntityType::Partnership,
+        3 => EntityType::SoleProprietorship,
+        4 => EntityType::ForeignBranch,
+        _ => EntityType::Nonprofit,
+    }
+}
+
+pub fn decode_input(data: &[u8]) -> TaxCalculationInput {
+    let mut bytes = Bytes(data);
+    let tax_year = bytes.pick(&TaxYear::ALL);
+
+    TaxCalculationInput {
+        gross_income: bytes.amount(),
+        entity_type: entity_type(bytes.byte()),
+        municipal_tax_rate: bytes.rate(20.0),
+        county_tax_rate: bytes.rate(10.0),
+        church_tax_rate: bytes.rate(2.0),
+        is_church_member: bytes.flag(),
+        historical_church_tax: bytes.flag(),
+        allowable_deductions: bytes.amount(),
+        dividend_income: bytes.amount(),
+        capital_gains: bytes.amount(),
+        investment_wealth: bytes.amount(),
+        business_expenses: bytes.amount(),
+        tax_year,
+        municipality: None,
+        sick_pay_insurance: bytes.pick(&SickPayInsurance::ALL),
+        tiltakssone: bytes.flag(),
+        branch_payroll: bytes.amount(),
+        charitable_purpose: bytes.flag(),
+        resource_rent: ResourceRentInput {
+            activity: bytes.pick(&ResourceRentActivity::ALL),
+            income: bytes.amount(),
+            costs: bytes.amount(),
+        },
+        petroleum: PetroleumInput {
+            enabled: bytes.flag(),
+            investments: bytes.amount(),
+        },
+        custom_taxes: (0..bytes.byte() % (MAX_CUSTOM_TAXES + 1))
+            .map(|_| {
+                let rate = bytes.flag();
+                let amount = bytes.amount().amount() / 1_000.0;
+                CustomTax {
+                    name: String::new(),
+                    kind: if rate {
+                        CustomTaxKind::Rate(Rate::from_percent(amount))
+                    } else {
+                        CustomTaxKind::Fixed(Money(amount))
+                    },
+                    base: bytes.pick(&CustomTaxBase::ALL),
+                }
+            })
+            .collect(),
+        policy: bytes.flag().then(|| {
+            let baseline = PolicyChanges::baseline(tax_year);
+            PolicyChanges {
+                state_tax_rates: baseline.state_tax_rates.iter().map(|_| bytes.rate(50.0)).collect(),
+                wealth_tax_rate: bytes.rate(5.0),
+                wealth_tax_threshold: bytes.amount().abs(),
+                dividend_factor: 1.0 + bytes.fraction(),
+            }
+        }),
+        // Last, so existing corpus entries decode as before
+        municipal_wealth_tax_rate: bytes.flag().then(|| bytes.rate(2.0)),
+        property_tax: PropertyTaxInput {
+            include: bytes.flag(),
+            home_value: bytes.amount(),
+            rate: Rate::from_per_mille(bytes.fraction() * 10.0),
+            bunnfradrag: bytes.amount(),
+        },
+        sick_pay: bytes.amount(),
+        disability_benefit: bytes.amount(),
+        commute: CommuteInput {
+            distance_km: bytes.fraction() * 200.0,
+            workdays: bytes.fraction() * 366.0,
+            nights_away: bytes.fraction() * 366.0,
+            lodging: bytes.amount(),
+            lodging_documented: bytes.flag(),
+            kitchen: bytes.flag(),
+            home_visits: bytes.fraction() * 100.0,
+            home_visit_km: bytes.fraction() * 2_000.0,
+        },
+        donations: bytes.amount(),
+        ips_contribution: bytes.amount(),
+        special_deductions: SpecialDeductionsInput {
+            sickness_costs_transitional: bytes.flag(),
+            sickness_costs: bytes.amount(),
+            occupation: bytes.pick(&Occupation::ALL),
+        },
+    }
+}
+
+// What a result must satisfy for any input, as messages for the ones it
+// breaks. The app reports them too when error reporting is set up.
+pub fn invariant_violations(input: &TaxCalculationInput, result: &TaxCalculationResult) -> Vec<String> {
+    let mut violations = Vec::new();
+    let fields = [
+        ("gross_income", result.gross_income),
+        ("personinntekt", result.personinntekt),
+        ("alminnelig_inntekt", result.alminnelig_inntekt),
+        ("taxable_income", result.taxable_income),
+        ("total_tax", result.total_tax),
+        ("net_income", result.net_income),
+        ("pension_accrual", result.pension_accrual),
+    ];
+    for (name, value) in fields {
+        if !value.amount().is_finite() {
+            violations.push(format!("{} is {}", name, value));
+        }
+    }
+    if !result.effective_tax_rate.fraction().is_finite() {
+        violations.push(format!("effective_tax_rate is {}", result.effective_tax_rate));
+    }
+    if !result.wage_effective_tax_rate.fraction().is_finite() {
+        violations.push(format!("wage_effective_tax_rate is {}", result.wage_effective_tax_rate));
+    }
+
+    let taxes = [
+        ("municipal_tax", result.municipal_tax),
+        ("county_tax", result.county_tax),
+        ("fellesskatt", result.fellesskatt),
+        ("church_tax", result.church_tax),
+        ("state_tax", result.state_tax),
+        ("corporate_tax", result.corporate_tax),
+        ("resource_rent_tax", result.resource_rent_tax),
+        ("national_insurance", result.national_insurance),
+        ("wealth_tax", result.wealth_tax),
+        ("property_tax", result.property_tax),
+    ];
+    for (name, value) in taxes {
+        if !(value.amount().is_finite() && value >= Money::ZERO) {
+            violations.push(format!("{} is {}", name, value));
+        }
+    }
+
+    let mut ids = HashSet::new();
+    for item in &result.breakdown {
+        if !item.amount.amount().is_finite() {
+            violations.push(format!("breakdown item {} is {}", item.id, item.amount));
+        }
+        if !ids.insert(&item.id) {
+            violations.push(format!("duplicate breakdown id {}", item.id));
+        }
+    }
+    if result.tax_year != input.tax_year {
+        violations.push(format!("tax year {} for an input in {}", result.tax_year.year(), input.tax_year.year()));
+    }
+    if result.assumptions.is_empty() {
+        violations.push("no assumptions".to_string());
+    }
+    violations
+}
+
+fn check_invariants(input: &TaxCalculationInput, result: &TaxCalculationResult) {
+    let violations = invariant_violations(input, result);
+    assert!(violations.is_empty(), "{} for {:?}", violations.join("; "), input);
+}
+
+// Entry point for cargo-fuzz: any bytes must give a result without panicking,
+// with the invariants above and the same amounts from every entry point.
+// Panics on a violation so the fuzzer keeps the input.
+pub fn fuzz_calculate(data: &[u8]) {
+    let input = decode_input(data);
+    let result = NorwegianTaxCalculator::calculate_tax(&input);
+    check_invariants(&input, &result);
+
+    let many = NorwegianTaxCalculator::calculate_many(std::slice::from_ref(&input));
+    assert_eq!(many.first(), Some(&result), "calculate_many differs for {:?}", input);
+
+    let totals = NorwegianTaxCalculator::calculate_totals_only(&input);
+    let expected = TaxCalculationResult {
+        breakdown: Vec::new(),
+        assumptions: Vec::new(),
+        ..result
+    };
+    assert_eq!(totals, expected, "calculate_totals_only differs for {:?}", input);
+
+    let many_totals = NorwegianTaxCalculator::calculate_many_totals(std::slice::from_ref(&input));
+    assert_eq!(many_totals.first(), Some(&totals), "calculate_many_totals differs for {:?}", input);
+}
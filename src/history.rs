@@ -0,0 +1,206 @@
+use serde::Serialize;
+
+use crate::comparisons::entity_title;
+use crate::custom_taxes::CustomTaxKind;
+use crate::form_schema::FORM_FIELDS;
+use crate::tax_calculator::{NorwegianTaxCalculator, TaxCalculationInput, TaxCalculationResult};
+
+#[derive(Clone, Debug)]
+pub struct InputHistory {
+    entries: Vec<TaxCalculationInput>,
+    cursor: usize,
+    limit: usize,
+}
+
+impl InputHistory {
+    pub fn new(initial: TaxCalculationInput, limit: usize) -> Self {
+        Self {
+            entries: vec![initial],
+            cursor: 0,
+            limit: limit.max(1),
+        }
+    }
+
+    // Records a new snapshot, dropping any redo entries. Snapshots equal to the
+    // current entry are ignored so that undo/redo themselves are not recorded.
+    pub fn record(&mut self, input: TaxCalculationInput) {
+        if self.entries[self.cursor] == input {
+            return;
+        }
+
+        self.entries.truncate(self.cursor + 1);
+        self.entries.push(input);
+
+        if self.entries.len() > self.limit {
+            self.entries.remove(0);
+        }
+
+        self.cursor = self.entries.len() - 1;
+    }
+
+    pub fn undo(&mut self) -> Option<TaxCalculationInput> {
+        if !self.can_undo() {
+            return None;
+        }
+
+        self.cursor -= 1;
+        Some(self.entries[self.cursor].clone())
+    }
+
+    pub fn redo(&mut self) -> Option<TaxCalculationInput> {
+        if !self.can_redo() {
+            return None;
+        }
+
+        self.cursor += 1;
+        Some(self.entries[self.cursor].clone())
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.cursor > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.cursor + 1 < self.entries.len()
+    }
+}
+
+// Results recorded this soon after the previous one replace it, so typing a
+// number doesn't fill the history with every keystroke
+pub const SNAPSHOT_COALESCE_MS: f64 = 2_000.0;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ResultSnapshot {
+    // Milliseconds since the Unix epoch
+    pub timestamp: f64,
+    pub input: TaxCalculationInput,
+    pub result: TaxCalculationResult,
+}
+
+// The latest calculation results this session, newest first
+#[derive(Clone, Debug)]
+pub struct SnapshotHistory {
+    entries: Vec<ResultSnapshot>,
+    limit: usize,
+}
+
+impl SnapshotHistory {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            limit: limit.max(1),
+        }
+    }
+
+    pub fn record(&mut self, snapshot: ResultSnapshot) {
+        if let Some(latest) = self.entries.first() {
+            if latest.input == snapshot.input {
+                return;
+            }
+            if snapshot.timestamp - latest.timestamp < SNAPSHOT_COALESCE_MS {
+                self.entries.remove(0);
+            }
+        }
+
+        self.entries.insert(0, snapshot);
+        self.entries.truncate(self.limit);
+    }
+
+    pub fn entries(&self) -> &[ResultSnapshot] {
+        &self.entries
+    }
+}
+
+// One field's change in the audit log, with the values as the user sees them
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct InputChange {
+    // Milliseconds since the Unix epoch
+    pub timestamp: f64,
+    pub field: String,
+    pub old: String,
+    pub new: String,
+}
+
+fn custom_taxes_summary(input: &TaxCalculationInput) -> String {
+    if input.custom_taxes.is_empty() {
+        return "Ingen".to_string();
+    }
+    input
+        .custom_taxes
+        .iter()
+        .map(|tax| match tax.kind {
+            CustomTaxKind::Fixed(amount) => format!("{} ({})", tax.name, NorwegianTaxCalculator::format_nok(amount)),
+            CustomTaxKind::Rate(rate) => format!("{} ({:.2})", tax.name, rate),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Inputs edited outside the generated form, as a label and a displayed value
+type ExtraField = (&'static str, fn(&TaxCalculationInput) -> String);
+
+const EXTRA_FIELDS: [ExtraField; 5] = [
+    ("Selskapsform", |i| entity_title(i.entity_type).to_string()),
+    ("Skatteår", |i| i.tax_year.year().to_string()),
+    ("Kommune", |i| i.municipality.clone().unwrap_or_else(|| "Ingen".to_string())),
+    ("Egendefinerte poster", custom_taxes_summary),
+    ("Hva hvis-satser", |i| if i.policy.is_some() { "Endret" } else { "Gjeldende regler" }.to_string()),
+];
+
+// The fields that differ between two inputs, as (field, old, new)
+pub fn input_changes(previous: &TaxCalculationInput, current: &TaxCalculationInput) -> Vec<(String, String, String)> {
+    let mut changes = Vec::new();
+    for (field, value) in EXTRA_FIELDS {
+        let (old, new) = (value(previous), value(current));
+        if old != new {
+            changes.push((field.to_string(), old, new));
+        }
+    }
+    for spec in FORM_FIELDS {
+        if let (Some(old), Some(new)) = (spec.kind.display(previous), spec.kind.display(current)) {
+            if old != new {
+                changes.push((spec.label.to_string(), old, new));
+            }
+        }
+    }
+    changes
+}
+
+// Every input change this session, newest first, so an advisor can retrace
+// how a figure came about. Edits to the same field in quick succession, such
+// as typing a number, are merged into one entry.
+#[derive(Clone, Debug)]
+pub struct AuditLog {
+    entries: Vec<InputChange>,
+    limit: usize,
+}
+
+impl AuditLog {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            limit: limit.max(1),
+        }
+    }
+
+    pub fn record(&mut self, timestamp: f64, previous: &TaxCalculationInput, current: &TaxCalculationInput) {
+        for (field, old, new) in input_changes(previous, current) {
+            if let Some(latest) = self.entries.first_mut() {
+                if latest.field == field && timestamp - latest.timestamp < SNAPSHOT_COALESCE_MS {
+                    latest.timestamp = timestamp;
+                    latest.new = new;
+                    if latest.old == latest.new {
+                        self.entries.remove(0);
+                    }
+                    continue;
+                }
+            }
+            self.entries.insert(0, InputChange { timestamp, field, old, new });
+        }
+        self.entries.truncate(self.limit);
+    }
+
+    pub fn entries(&self) -> &[InputChange] {
+        &self.entries
+    }
+}
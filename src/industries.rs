@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+use crate::money::{Money, Rate};
+
+// Industries that pay grunnrenteskatt on top of the corporate tax
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResourceRentActivity {
+    #[default]
+    None,
+    Aquaculture,
+    Hydropower,
+}
+
+impl ResourceRentActivity {
+    pub const ALL: [ResourceRentActivity; 3] = [
+        ResourceRentActivity::None,
+        ResourceRentActivity::Aquaculture,
+        ResourceRentActivity::Hydropower,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ResourceRentActivity::None => "Ingen",
+            ResourceRentActivity::Aquaculture => "Havbruk",
+            ResourceRentActivity::Hydropower => "Vannkraft",
+        }
+    }
+
+    // Nominal rate. The corporate tax on the grunnrenteinntekt is deducted
+    // from the base, so the effective rate is lower.
+    pub fn rate(&self) -> Rate {
+        match self {
+            ResourceRentActivity::None => Rate::ZERO,
+            ResourceRentActivity::Aquaculture => Rate::from_percent(25.0),
+            ResourceRentActivity::Hydropower => Rate::from_percent(45.0),
+        }
+    }
+
+    // Bunnfradrag per company. Small hydropower plants are exempt by
+    // capacity instead, which the user is expected to have checked.
+    pub fn base_allowance(&self) -> Money {
+        match self {
+            ResourceRentActivity::Aquaculture => Money(70_000_000.0),
+            ResourceRentActivity::None | ResourceRentActivity::Hydropower => Money::ZERO,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceRentInput {
+    pub activity: ResourceRentActivity,
+    // Income and costs of the qualifying activity alone, which differ from
+    // the company's ordinary accounts: sales are valued at market price and
+    // only costs tied to the production count
+    pub income: Money,
+    pub costs: Money,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResourceRentTax {
+    pub corporate_tax_deduction: Money,
+    pub allowance: Money,
+    pub base: Money,
+    pub tax: Money,
+}
+
+pub fn resource_rent_tax(input: &ResourceRentInput, corporate_tax_rate: Rate) -> ResourceRentTax {
+    let activity = input.activity;
+    let profit = (input.income - input.costs).max(Money::ZERO);
+    let corporate_tax_deduction = profit * corporate_tax_rate;
+    let allowance = activity.base_allowance().min(profit - corporate_tax_deduction);
+    let base = profit - corporate_tax_deduction - allowance;
+
+    ResourceRentTax {
+        corporate_tax_deduction,
+        allowance,
+        base,
+        tax: base * activity.rate(),
+    }
+}
+
+// Særskatt on petroleum extraction. The corporate tax is deductible in its
+// base, which brings the marginal rate to 22% + 56% = 78%.
+pub const PETROLEUM_SPECIAL_TAX_RATE: Rate = Rate::from_percent(71.8);
+// Friinntekt on the year's investments, deducted in the special tax base only
+pub const PETROLEUM_UPLIFT_RATE: Rate = Rate::from_percent(12.4);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PetroleumInput {
+    pub enabled: bool,
+    pub investments: Money,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PetroleumTax {
+    pub corporate_tax_deduction: Money,
+    pub uplift: Money,
+    pub base: Money,
+    pub tax: Money,
+}
+
+// The special tax on the company's profit from extraction, here the same
+// profit the corporate tax is levied on
+pub fn petroleum_special_tax(input: &PetroleumInput, profit: Money, corporate_tax_rate: Rate) -> PetroleumTax {
+    let corporate_tax_deduction = profit * corporate_tax_rate;
+    let uplift = input.investments.max(Money::ZERO) * PETROLEUM_UPLIFT_RATE;
+    let base = (profit - corporate_tax_deduction - uplift).max(Money::ZERO);
+
+    PetroleumTax {
+        corporate_tax_deduction,
+        uplift,
+        base,
+        tax: base * PETROLEUM_SPECIAL_TAX_RATE,
+    }
+}
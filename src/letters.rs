@@ -0,0 +1,93 @@
+use crate::analysis::marginal_tax_rate;
+use crate::comparisons::entity_title;
+use crate::tax_calculator::{NorwegianTaxCalculator, TaxCalculationInput, TaxCalculationResult};
+
+// Placeholders a letter template can use, with what they are replaced by
+pub const PLACEHOLDERS: &[(&str, &str)] = &[
+    ("klient", "Klientens navn"),
+    ("dato", "Dagens dato"),
+    ("skatteår", "Skatteåret"),
+    ("selskapsform", "Selskapsformen"),
+    ("bruttoinntekt", "Bruttoinntekt"),
+    ("personinntekt", "Personinntekt"),
+    ("alminnelig_inntekt", "Alminnelig inntekt"),
+    ("total_skatt", "Total skatt"),
+    ("formueskatt", "Formueskatt"),
+    ("nettoinntekt", "Nettoinntekt"),
+    ("nettoinntekt_per_måned", "Nettoinntekt per måned"),
+    ("effektiv_skattesats", "Effektiv skattesats"),
+    ("marginalskatt", "Marginalskatt"),
+];
+
+// The recommendation itself is the advisor's, so the template leaves room
+// for it in prose
+pub const DEFAULT_TEMPLATE: &str = "{dato}
+
+Til {klient}
+
+Skatteberegning for {skatteår}
+Selskapsform: {selskapsform}
+
+Vi har beregnet skatten med en bruttoinntekt på {bruttoinntekt}. Etter reglene for {skatteår} \
+blir den samlede skatten {total_skatt}. Det gir en effektiv skattesats på {effektiv_skattesats} og en nettoinntekt \
+på {nettoinntekt}, eller {nettoinntekt_per_måned} i måneden.
+
+Av hver krone du tjener i tillegg, går {marginalskatt} til skatt.
+
+Vår anbefaling: [skriv anbefalingen her, for eksempel fordelingen mellom lønn og utbytte]
+
+Beregningen er et anslag ut fra opplysningene vi har fått, og erstatter ikke skatteoppgjøret.
+
+Med vennlig hilsen
+";
+
+pub fn letter_values(
+    client: &str,
+    date: &str,
+    input: &TaxCalculationInput,
+    result: &TaxCalculationResult,
+) -> Vec<(&'static str, String)> {
+    vec![
+        ("klient", client.to_string()),
+        ("dato", date.to_string()),
+        ("skatteår", result.tax_year.year().to_string()),
+        ("selskapsform", entity_title(input.entity_type).to_string()),
+        ("bruttoinntekt", NorwegianTaxCalculator::format_nok(result.gross_income)),
+        ("personinntekt", NorwegianTaxCalculator::format_nok(result.personinntekt)),
+        ("alminnelig_inntekt", NorwegianTaxCalculator::format_nok(result.alminnelig_inntekt)),
+        ("total_skatt", NorwegianTaxCalculator::format_nok(result.total_tax)),
+        ("formueskatt", NorwegianTaxCalculator::format_nok(result.wealth_tax)),
+        ("nettoinntekt", NorwegianTaxCalculator::format_nok(result.net_income)),
+        ("nettoinntekt_per_måned", NorwegianTaxCalculator::format_nok(result.net_income / 12.0)),
+        ("effektiv_skattesats", format!("{:.1}", result.effective_tax_rate)),
+        ("marginalskatt", format!("{:.1}", marginal_tax_rate(input))),
+    ]
+}
+
+// Replaces each {name} with its value. Unknown names and unmatched braces
+// are kept as written, so a typo shows up in the letter rather than
+// disappearing.
+pub fn render_template(template: &str, values: &[(&str, String)]) -> String {
+    let mut letter = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        letter.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            values.iter().find(|(key, _)| *key == name).map(|(_, value)| (value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                letter.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                letter.push('{');
+                rest = after;
+            }
+        }
+    }
+    letter.push_str(rest);
+    letter
+}
@@ -1,21 +1,85 @@
+//! Norwegian tax calculation for individuals and businesses. The engine in
+//! [`tax_calculator`] works on the amounts and rates in [`money`] and has no
+//! UI dependencies in its API; the rest of the crate is the Leptos app.
+
 use leptos::*;
-use wasm_bindgen::prelude::*;
 
+mod analysis;
+mod backup;
+mod batch;
+mod benchmarks;
+mod clients;
+pub mod commuter;
+mod comparisons;
 mod components;
-mod tax_calculator;
+mod constraints;
+mod currency;
+pub mod custom_taxes;
+#[cfg(feature = "charts")]
+mod distribution;
+mod download;
+mod embed;
+mod employer_zones;
+#[cfg(feature = "integrations")]
+mod error_reports;
+#[cfg(feature = "integrations")]
+mod fetch;
+mod form_schema;
+pub mod fuzzing;
+mod history;
+pub mod industries;
+mod letters;
+pub mod money;
+mod municipalities;
+#[cfg(feature = "employer-cost")]
+mod payroll;
+mod presets;
+pub mod property_tax;
+mod population;
+mod pwa;
+mod report;
+mod routing;
+mod scenarios;
+#[cfg(feature = "charts")]
+mod simulation;
+#[cfg(feature = "integrations")]
+mod skattemelding;
+pub mod special_deductions;
+mod storage;
+pub mod tax_calculator;
+pub mod testing;
+mod upload;
 
 use components::*;
-use tax_calculator::*;
+use leptos_router::{Route, Router, Routes};
 
 #[component]
 pub fn App() -> impl IntoView {
+    let base = routing::BASE_PATH.unwrap_or_default();
     view! {
-        <TaxCalculator />
+        <Router base=base>
+            <Routes base=base.to_string()>
+                <Route path="/:entity?" view=|| view! { <TaxCalculator /> } />
+            </Routes>
+        </Router>
     }
 }
 
-#[wasm_bindgen(start)]
-pub fn main() {
+// Panics go to the console, and to the error reporter when one is set up
+fn set_panic_hook() {
+    #[cfg(feature = "integrations")]
+    error_reports::install_panic_hook();
+    #[cfg(not(feature = "integrations"))]
     console_error_panic_hook::set_once();
+}
+
+#[cfg_attr(not(feature = "embed"), wasm_bindgen::prelude::wasm_bindgen(start))]
+pub fn main() {
+    set_panic_hook();
+    spawn_local(async {
+        if let Err(err) = pwa::register().await {
+            logging::warn!("Kunne ikke registrere service worker: {:?}", err);
+        }
+    });
     leptos::mount_to_body(App);
 }
\ No newline at end of file
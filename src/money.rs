@@ -0,0 +1,277 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// An amount in NOK
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Money(pub f64);
+
+impl Money {
+    pub const ZERO: Money = Money(0.0);
+
+    pub fn amount(self) -> f64 {
+        self.0
+    }
+
+    pub fn max(self, other: Money) -> Money {
+        Money(self.0.max(other.0))
+    }
+
+    pub fn min(self, other: Money) -> Money {
+        Money(self.0.min(other.0))
+    }
+
+    pub fn clamp(self, min: Money, max: Money) -> Money {
+        Money(self.0.clamp(min.0, max.0))
+    }
+
+    pub fn abs(self) -> Money {
+        Money(self.0.abs())
+    }
+
+    // To whole kroner
+    pub fn round(self) -> Money {
+        Money(self.0.round())
+    }
+
+    pub fn total_cmp(&self, other: &Money) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+
+    // Groups of three digits from the right, separated by spaces, and no unit
+    pub fn grouped(self) -> String {
+        let digits = format!("{:.0}", self.0);
+        let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index) % 3 == 0 {
+                grouped.push(' ');
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+}
+
+impl From<f64> for Money {
+    fn from(amount: f64) -> Money {
+        Money(amount)
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 < 0.0 {
+            write!(f, "-{} NOK", (-*self).grouped())
+        } else {
+            write!(f, "{} NOK", self.grouped())
+        }
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        Money(self.0 + other.0)
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        Money(self.0 - other.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Money) {
+        self.0 += other.0;
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, other: Money) {
+        self.0 -= other.0;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+// Scaling by a plain factor, such as a multiple or a valuation discount
+impl Mul<f64> for Money {
+    type Output = Money;
+
+    fn mul(self, factor: f64) -> Money {
+        Money(self.0 * factor)
+    }
+}
+
+impl Div<f64> for Money {
+    type Output = Money;
+
+    fn div(self, divisor: f64) -> Money {
+        Money(self.0 / divisor)
+    }
+}
+
+// The ratio between two amounts
+impl Div for Money {
+    type Output = f64;
+
+    fn div(self, other: Money) -> f64 {
+        self.0 / other.0
+    }
+}
+
+impl Mul<Rate> for Money {
+    type Output = Money;
+
+    fn mul(self, rate: Rate) -> Money {
+        Money(self.0 * rate.fraction())
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Money {
+        iter.copied().sum()
+    }
+}
+
+// A tax rate. Kept in percent, which is what users type and what saved
+// inputs contain, so a typed rate reads back exactly; the constructors and
+// accessors say which unit a bare number is in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Rate(f64);
+
+impl Rate {
+    pub const ZERO: Rate = Rate(0.0);
+
+    pub const fn from_fraction(fraction: f64) -> Rate {
+        Rate(fraction * 100.0)
+    }
+
+    pub const fn from_percent(percent: f64) -> Rate {
+        Rate(percent)
+    }
+
+    pub fn fraction(self) -> f64 {
+        self.0 / 100.0
+    }
+
+    pub fn percent(self) -> f64 {
+        self.0
+    }
+
+    // Eiendomsskatt is set in promille
+    pub const fn from_per_mille(per_mille: f64) -> Rate {
+        Rate(per_mille / 10.0)
+    }
+
+    pub fn per_mille(self) -> f64 {
+        self.0 * 10.0
+    }
+
+    pub fn max(self, other: Rate) -> Rate {
+        Rate(self.0.max(other.0))
+    }
+
+    pub fn min(self, other: Rate) -> Rate {
+        Rate(self.0.min(other.0))
+    }
+
+    pub fn clamp(self, min: Rate, max: Rate) -> Rate {
+        Rate(self.0.clamp(min.0, max.0))
+    }
+
+    pub fn total_cmp(&self, other: &Rate) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+// Percent with the formatter's precision, such as "22.0%" for {:.1}
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*}%", precision, self.percent()),
+            None => write!(f, "{}%", self.percent()),
+        }
+    }
+}
+
+impl Serialize for Rate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.percent())
+    }
+}
+
+impl<'de> Deserialize<'de> for Rate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Rate, D::Error> {
+        f64::deserialize(deserializer).map(Rate::from_percent)
+    }
+}
+
+impl Add for Rate {
+    type Output = Rate;
+
+    fn add(self, other: Rate) -> Rate {
+        Rate(self.0 + other.0)
+    }
+}
+
+impl Sub for Rate {
+    type Output = Rate;
+
+    fn sub(self, other: Rate) -> Rate {
+        Rate(self.0 - other.0)
+    }
+}
+
+// A rate applied to part of a base, or adjusted by a factor
+impl Mul<f64> for Rate {
+    type Output = Rate;
+
+    fn mul(self, factor: f64) -> Rate {
+        Rate(self.0 * factor)
+    }
+}
+
+impl Mul for Rate {
+    type Output = Rate;
+
+    fn mul(self, other: Rate) -> Rate {
+        Rate(self.0 * other.fraction())
+    }
+}
+
+impl Div for Rate {
+    type Output = f64;
+
+    fn div(self, other: Rate) -> f64 {
+        self.0 / other.0
+    }
+}
+
+impl Mul<Money> for Rate {
+    type Output = Money;
+
+    fn mul(self, amount: Money) -> Money {
+        amount * self
+    }
+}
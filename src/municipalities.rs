@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "integrations")]
+use wasm_bindgen::JsValue;
+
+#[cfg(feature = "integrations")]
+use crate::fetch::fetch_text;
+use crate::money::{Money, Rate};
+use crate::storage;
+
+pub const MUNICIPALITIES_KEY: &str = "ntc.municipalities";
+#[cfg(feature = "integrations")]
+pub const ONLINE_MODE_KEY: &str = "ntc.online-mode";
+
+// SSB's classification of municipalities (KLASS 131)
+#[cfg(feature = "integrations")]
+const KLASS_MUNICIPALITIES_URL: &str = "https://data.ssb.no/api/klass/v1/classifications/131/codesAt.json";
+#[cfg(feature = "integrations")]
+const CACHE_MAX_AGE_MS: f64 = 7.0 * 24.0 * 60.0 * 60.0 * 1000.0;
+
+// Every municipality uses the maximum skattøre set by Stortinget
+const MUNICIPAL_TAX_RATE_2024: Rate = Rate::from_percent(10.95);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Municipality {
+    pub number: String,
+    pub name: String,
+    pub municipal_tax_rate: Rate,
+    #[serde(default)]
+    pub property_tax_rate: Option<Rate>,
+    // Bunnfradrag on dwellings, where the municipality has eiendomsskatt
+    #[serde(default)]
+    pub property_tax_bunnfradrag: Option<Money>,
+    // Kommunal formueskatt, where the municipality has set it below the
+    // maximum; None is the maximum
+    #[serde(default)]
+    pub wealth_tax_rate: Option<Rate>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MunicipalityCache {
+    #[cfg_attr(not(feature = "integrations"), allow(dead_code))]
+    fetched_at: f64,
+    municipalities: Vec<Municipality>,
+}
+
+#[cfg(feature = "integrations")]
+#[derive(Deserialize)]
+struct KlassCodes {
+    codes: Vec<KlassCode>,
+}
+
+#[cfg(feature = "integrations")]
+#[derive(Deserialize)]
+struct KlassCode {
+    code: String,
+    name: String,
+}
+
+const BUNDLED: &[(&str, &str)] = &[
+    ("0301", "Oslo"),
+    ("4601", "Bergen"),
+    ("5001", "Trondheim"),
+    ("1103", "Stavanger"),
+    ("3201", "Bærum"),
+    ("4204", "Kristiansand"),
+    ("3301", "Drammen"),
+    ("3205", "Lillestrøm"),
+    ("3107", "Fredrikstad"),
+    ("1108", "Sandnes"),
+    ("5501", "Tromsø"),
+    ("3203", "Asker"),
+    ("3105", "Sarpsborg"),
+    ("4003", "Skien"),
+    ("1804", "Bodø"),
+];
+
+fn municipality(number: &str, name: &str) -> Municipality {
+    Municipality {
+        number: number.to_string(),
+        name: name.to_string(),
+        municipal_tax_rate: MUNICIPAL_TAX_RATE_2024,
+        property_tax_rate: None,
+        property_tax_bunnfradrag: None,
+        wealth_tax_rate: None,
+    }
+}
+
+// Offline fallback used until (or unless) the online list has been fetched
+pub fn bundled_municipalities() -> Vec<Municipality> {
+    let mut municipalities = BUNDLED
+        .iter()
+        .map(|(number, name)| municipality(number, name))
+        .collect::<Vec<_>>();
+    municipalities.sort_by(|a, b| a.name.cmp(&b.name));
+    municipalities
+}
+
+pub fn cached_or_bundled() -> Vec<Municipality> {
+    storage::load::<MunicipalityCache>(MUNICIPALITIES_KEY)
+        .map(|cache| cache.municipalities)
+        .filter(|municipalities| !municipalities.is_empty())
+        .unwrap_or_else(bundled_municipalities)
+}
+
+#[cfg(feature = "integrations")]
+fn cache_is_fresh() -> bool {
+    storage::load::<MunicipalityCache>(MUNICIPALITIES_KEY)
+        .is_some_and(|cache| js_sys::Date::now() - cache.fetched_at < CACHE_MAX_AGE_MS)
+}
+
+// Fetches the current municipality list, keeping rates and property tax
+// from the existing data where a municipality is already known
+#[cfg(feature = "integrations")]
+pub async fn refresh_municipalities() -> Result<Vec<Municipality>, JsValue> {
+    let known = cached_or_bundled();
+    if cache_is_fresh() {
+        return Ok(known);
+    }
+
+    let today = String::from(js_sys::Date::new_0().to_iso_string());
+    let url = format!("{}?date={}", KLASS_MUNICIPALITIES_URL, &today[..10]);
+    let text = fetch_text(&url).await?;
+    let klass: KlassCodes = serde_json::from_str(&text).map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+    let mut municipalities = klass
+        .codes
+        .into_iter()
+        .filter(|code| code.code != "9999")
+        .map(|code| {
+            known
+                .iter()
+                .find(|municipality| municipality.number == code.code)
+                .map(|existing| Municipality {
+                    name: code.name.clone(),
+                    ..existing.clone()
+                })
+                .unwrap_or_else(|| municipality(&code.code, &code.name))
+        })
+        .collect::<Vec<_>>();
+    municipalities.sort_by(|a, b| a.name.cmp(&b.name));
+
+    storage::save(
+        MUNICIPALITIES_KEY,
+        &MunicipalityCache {
+            fetched_at: js_sys::Date::now(),
+            municipalities: municipalities.clone(),
+        },
+    );
+    Ok(municipalities)
+}
@@ -0,0 +1,362 @@
+use serde::Serialize;
+
+use crate::commuter::CommuteInput;
+use crate::employer_zones::EmployerZone;
+use crate::money::{Money, Rate};
+use crate::special_deductions::SpecialDeductionsInput;
+use crate::tax_calculator::{EntityType, NorwegianTaxCalculator, TaxCalculationInput, TaxYear, OTP_LOWER_G, OTP_UPPER_G};
+
+// Feriepenger under ferieloven (4 weeks + 1 day) or the common tariff
+// agreements with a fifth week
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HolidayPayRate {
+    #[default]
+    Statutory,
+    FiveWeeks,
+}
+
+impl HolidayPayRate {
+    pub const ALL: [HolidayPayRate; 2] = [HolidayPayRate::Statutory, HolidayPayRate::FiveWeeks];
+
+    pub fn rate(&self) -> Rate {
+        match self {
+            HolidayPayRate::Statutory => Rate::from_percent(10.2),
+            HolidayPayRate::FiveWeeks => Rate::from_percent(12.0),
+        }
+    }
+
+    // Working days of holiday, deducted from the ordinary salary as ferietrekk
+    pub fn holiday_days(&self) -> f64 {
+        match self {
+            HolidayPayRate::Statutory => 21.0,
+            HolidayPayRate::FiveWeeks => 25.0,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HolidayPayRate::Statutory => "10,2 % (4 uker og 1 dag)",
+            HolidayPayRate::FiveWeeks => "12 % (5 uker)",
+        }
+    }
+}
+
+// Average working days per month, used to turn holiday days into ferietrekk
+const WORKING_DAYS_PER_MONTH: f64 = 21.67;
+
+// Obligatorisk tjenestepensjon: at least 2% (at most 7%) of salary between
+// 1G and 12G
+pub const OTP_MIN_RATE: Rate = Rate::from_percent(2.0);
+pub const OTP_MAX_RATE: Rate = Rate::from_percent(7.0);
+
+// Shortest payout period for an innskuddspensjon
+pub const PENSION_PAYOUT_YEARS: u32 = 10;
+
+// Months of full tabelltrekk per year: none in the holiday pay month and
+// half in December
+const WITHHOLDING_MONTHS: f64 = 10.5;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PayrollInput {
+    pub annual_salary: Money,
+    pub zone: EmployerZone,
+    pub holiday_pay_rate: HolidayPayRate,
+    // Month feriepenger are paid out, 1-12
+    pub holiday_pay_month: u32,
+    // Feriepenger paid this year are earned on last year's salary. Assumed
+    // equal to this year's salary when not given.
+    pub previous_year_salary: Option<Money>,
+    pub pension_rate: Rate,
+}
+
+impl Default for PayrollInput {
+    fn default() -> Self {
+        Self {
+            annual_salary: Money(600_000.0),
+            zone: EmployerZone::Zone1,
+            holiday_pay_rate: HolidayPayRate::Statutory,
+            holiday_pay_month: 6,
+            previous_year_salary: None,
+            pension_rate: OTP_MIN_RATE,
+        }
+    }
+}
+
+impl PayrollInput {
+    pub fn holiday_pay_base(&self) -> Money {
+        self.previous_year_salary.unwrap_or(self.annual_salary).max(Money::ZERO)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PayrollMonth {
+    pub month: u32,
+    // Ordinary salary after any ferietrekk
+    pub salary: Money,
+    pub holiday_deduction: Money,
+    pub holiday_pay: Money,
+    // Feriepenger earned this month, paid out next year
+    pub holiday_pay_accrued: Money,
+    // OTP premium paid by the employer; not taxed for the employee
+    pub pension_contribution: Money,
+    pub withholding: Money,
+    pub employer_contribution: Money,
+    pub surcharge_base: Money,
+    pub surcharge: Money,
+}
+
+impl PayrollMonth {
+    pub fn gross_pay(&self) -> Money {
+        self.salary + self.holiday_pay
+    }
+
+    pub fn net_pay(&self) -> Money {
+        self.gross_pay() - self.withholding
+    }
+
+    pub fn employer_cost(&self) -> Money {
+        self.gross_pay() + self.pension_contribution + self.employer_contribution + self.surcharge
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PayrollSummary {
+    pub tax_year: TaxYear,
+    pub zone: EmployerZone,
+    pub months: Vec<PayrollMonth>,
+}
+
+impl PayrollSummary {
+    fn total(&self, value: impl Fn(&PayrollMonth) -> Money) -> Money {
+        self.months.iter().map(value).sum()
+    }
+
+    pub fn salary(&self) -> Money {
+        self.total(|month| month.salary)
+    }
+
+    pub fn holiday_pay(&self) -> Money {
+        self.total(|month| month.holiday_pay)
+    }
+
+    pub fn holiday_pay_accrued(&self) -> Money {
+        self.total(|month| month.holiday_pay_accrued)
+    }
+
+    pub fn pension_contribution(&self) -> Money {
+        self.total(|month| month.pension_contribution)
+    }
+
+    pub fn withholding(&self) -> Money {
+        self.total(|month| month.withholding)
+    }
+
+    pub fn employer_contribution(&self) -> Money {
+        self.total(|month| month.employer_contribution + month.surcharge)
+    }
+
+    pub fn employer_cost(&self) -> Money {
+        self.total(PayrollMonth::employer_cost)
+    }
+
+    pub fn net_pay(&self) -> Money {
+        self.total(PayrollMonth::net_pay)
+    }
+}
+
+// The employee's annual tax on this year's pay alone, using the rates from
+// the main calculator
+fn annual_withholding(gross_pay: Money, base: &TaxCalculationInput) -> Money {
+    let employee = TaxCalculationInput {
+        gross_income: gross_pay,
+        entity_type: EntityType::Individual,
+        allowable_deductions: Money::ZERO,
+        commute: CommuteInput::default(),
+        donations: Money::ZERO,
+        ips_contribution: Money::ZERO,
+        special_deductions: SpecialDeductionsInput::default(),
+        dividend_income: Money::ZERO,
+        capital_gains: Money::ZERO,
+        investment_wealth: Money::ZERO,
+        business_expenses: Money::ZERO,
+        sick_pay: Money::ZERO,
+        disability_benefit: Money::ZERO,
+        ..base.clone()
+    };
+    NorwegianTaxCalculator::calculate_totals_only(&employee).total_tax
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BonusEffect {
+    pub regular_withholding: Money,
+    pub bonus_month_withholding: Money,
+    // The bonus's share of the annual tax
+    pub bonus_tax: Money,
+}
+
+impl BonusEffect {
+    pub fn extra_withholding(&self) -> Money {
+        self.bonus_month_withholding - self.regular_withholding
+    }
+
+    // Positive when too much is withheld and comes back at skatteoppgjøret
+    pub fn settlement(&self) -> Money {
+        self.extra_withholding() - self.bonus_tax
+    }
+}
+
+// The trekktabell reads a month's pay as if it were paid every month, so a
+// bonus is withheld at the marginal rate of a much higher annual income
+pub fn bonus_effect(annual_salary: Money, bonus: Money, base: &TaxCalculationInput) -> BonusEffect {
+    let monthly_salary = annual_salary.max(Money::ZERO) / 12.0;
+    let bonus = bonus.max(Money::ZERO);
+    let table_withholding = |month_pay: Money| annual_withholding(month_pay * 12.0, base) / WITHHOLDING_MONTHS;
+
+    BonusEffect {
+        regular_withholding: table_withholding(monthly_salary),
+        bonus_month_withholding: table_withholding(monthly_salary + bonus),
+        bonus_tax: annual_withholding(annual_salary + bonus, base) - annual_withholding(annual_salary, base),
+    }
+}
+
+pub fn otp_contribution(annual_salary: Money, pension_rate: Rate, tax_year: TaxYear) -> Money {
+    let g = tax_year.rules().grunnbeloep;
+    let pensionable = annual_salary.min(g.times(OTP_UPPER_G)) - g.times(OTP_LOWER_G);
+    pensionable.max(Money::ZERO) * pension_rate.clamp(OTP_MIN_RATE, OTP_MAX_RATE)
+}
+
+// Balance at retirement from a yearly contribution paid at the end of each
+// year, and what it pays out per year over the shortest payout period
+pub fn project_pension(annual_contribution: Money, years: u32, annual_return: Rate) -> (Money, Money) {
+    let balance = (0..years).fold(Money::ZERO, |balance, _| balance + balance * annual_return + annual_contribution);
+    (balance, balance / PENSION_PAYOUT_YEARS as f64)
+}
+
+pub fn calculate_payroll(payroll: &PayrollInput, base: &TaxCalculationInput) -> PayrollSummary {
+    let rules = base.tax_year.rules();
+    let holiday_pay_rate = payroll.holiday_pay_rate;
+    let payout_month = payroll.holiday_pay_month.clamp(1, 12);
+    let monthly_salary = payroll.annual_salary.max(Money::ZERO) / 12.0;
+    let holiday_pay = payroll.holiday_pay_base() * holiday_pay_rate.rate();
+    // Ordinary salary isn't paid for the holiday, which feriepenger cover instead
+    let holiday_deduction =
+        (monthly_salary / WORKING_DAYS_PER_MONTH * holiday_pay_rate.holiday_days()).min(monthly_salary);
+
+    // Tabelltrekk: no withholding in the payout month and half in December,
+    // so the annual tax is spread over 10.5 months
+    let withholding_share = |month: u32| match month {
+        _ if month == payout_month => 0.0,
+        12 => 0.5,
+        _ => 1.0,
+    };
+    let withholding_months: f64 = (1..=12).map(withholding_share).sum();
+    let annual_tax = annual_withholding(monthly_salary * 12.0 - holiday_deduction + holiday_pay, base);
+    let pension_contribution = otp_contribution(payroll.annual_salary, payroll.pension_rate, base.tax_year) / 12.0;
+
+    // The surcharge applies to the part of the year-to-date pay above the threshold
+    let above_threshold = |paid: Money| (paid - rules.employer_surcharge_threshold).max(Money::ZERO);
+    let mut paid_before = Money::ZERO;
+    let months = (1..=12)
+        .map(|month| {
+            let is_payout = month == payout_month;
+            let deduction = if is_payout { holiday_deduction } else { Money::ZERO };
+            let salary = monthly_salary - deduction;
+            let holiday_pay = if is_payout { holiday_pay } else { Money::ZERO };
+            let gross_pay = salary + holiday_pay;
+            // Pension premiums are subject to arbeidsgiveravgift like salary
+            let contribution_base = gross_pay + pension_contribution;
+
+            let surcharge_base = if rules.employer_surcharge_rate > Rate::ZERO {
+                above_threshold(paid_before + contribution_base) - above_threshold(paid_before)
+            } else {
+                Money::ZERO
+            };
+            paid_before += contribution_base;
+
+            PayrollMonth {
+                month,
+                salary,
+                holiday_deduction: deduction,
+                holiday_pay,
+                holiday_pay_accrued: salary * holiday_pay_rate.rate(),
+                pension_contribution,
+                withholding: annual_tax * withholding_share(month) / withholding_months,
+                employer_contribution: contribution_base * payroll.zone.rate(),
+                surcharge_base,
+                surcharge: surcharge_base * rules.employer_surcharge_rate,
+            }
+        })
+        .collect();
+
+    PayrollSummary {
+        tax_year: base.tax_year,
+        zone: payroll.zone,
+        months,
+    }
+}
+
+// One row per month, named after the a-melding fields they correspond to
+#[derive(Serialize)]
+struct AMeldingRow {
+    kalendermaaned: String,
+    #[serde(rename = "inntekt.beskrivelse")]
+    description: &'static str,
+    #[serde(rename = "inntekt.beloep")]
+    salary: Money,
+    forskuddstrekk: Money,
+    avgiftssone: &'static str,
+    #[serde(rename = "beregningskodeForArbeidsgiveravgift")]
+    calculation_code: &'static str,
+    avgiftsgrunnlag: Money,
+    #[serde(rename = "prosentsatsForAvgiftsberegning")]
+    rate: f64,
+    arbeidsgiveravgift: Money,
+    #[serde(rename = "avgiftsgrunnlagEkstraArbeidsgiveravgift")]
+    surcharge_base: Money,
+    #[serde(rename = "ekstraArbeidsgiveravgift")]
+    surcharge: Money,
+}
+
+// Feriepenger are reported as their own inntekt line in the payout month.
+// Forskuddstrekk, the extra avgift and the OTP premium, which isn't an
+// inntekt but is part of the avgiftsgrunnlag, go on the fastloenn line.
+pub fn payroll_to_csv(summary: &PayrollSummary) -> Result<Vec<u8>, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let rate = summary.zone.rate();
+    // The percent with one decimal, as the a-melding expects
+    let percent = (rate.percent() * 10.0).round() / 10.0;
+    for month in &summary.months {
+        let kalendermaaned = format!("{}-{:02}", summary.tax_year.year(), month.month);
+        writer.serialize(AMeldingRow {
+            kalendermaaned: kalendermaaned.clone(),
+            description: "fastloenn",
+            salary: month.salary.round(),
+            forskuddstrekk: month.withholding.round(),
+            avgiftssone: summary.zone.code(),
+            calculation_code: "generelleNaeringer",
+            avgiftsgrunnlag: (month.salary + month.pension_contribution).round(),
+            rate: percent,
+            arbeidsgiveravgift: ((month.salary + month.pension_contribution) * rate).round(),
+            surcharge_base: month.surcharge_base.round(),
+            surcharge: month.surcharge.round(),
+        })?;
+        if month.holiday_pay > Money::ZERO {
+            writer.serialize(AMeldingRow {
+                kalendermaaned,
+                description: "feriepenger",
+                salary: month.holiday_pay.round(),
+                forskuddstrekk: Money::ZERO,
+                avgiftssone: summary.zone.code(),
+                calculation_code: "generelleNaeringer",
+                avgiftsgrunnlag: month.holiday_pay.round(),
+                rate: percent,
+                arbeidsgiveravgift: (month.holiday_pay * rate).round(),
+                surcharge_base: Money::ZERO,
+                surcharge: Money::ZERO,
+            })?;
+        }
+    }
+    writer
+        .into_inner()
+        .map_err(|err| csv::Error::from(err.into_error()))
+}
@@ -0,0 +1,126 @@
+use crate::batch::BatchRow;
+use crate::money::{Money, Rate};
+
+pub const DECILES: usize = 10;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecileStats {
+    // 1 for the lowest incomes
+    pub decile: usize,
+    pub clients: usize,
+    pub lowest_income: Money,
+    pub highest_income: Money,
+    pub total_tax: Money,
+    // Total tax over total gross income in the decile
+    pub average_rate: Rate,
+    pub median_rate: Rate,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PopulationStats {
+    pub clients: usize,
+    pub total_gross_income: Money,
+    pub total_tax: Money,
+    pub average_rate: Rate,
+    pub median_rate: Rate,
+    pub deciles: Vec<DecileStats>,
+    // Cumulative share of income held by the poorest share of the clients,
+    // as (population share, income share) from (0, 0) to (1, 1)
+    pub lorenz_gross: Vec<(f64, f64)>,
+    pub lorenz_net: Vec<(f64, f64)>,
+    pub gini_gross: f64,
+    pub gini_net: f64,
+}
+
+fn median(rates: &mut [Rate]) -> Rate {
+    if rates.is_empty() {
+        return Rate::ZERO;
+    }
+    rates.sort_by(|a, b| a.total_cmp(b));
+    let middle = rates.len() / 2;
+    if rates.len().is_multiple_of(2) {
+        (rates[middle - 1] + rates[middle]) * 0.5
+    } else {
+        rates[middle]
+    }
+}
+
+fn rate(tax: Money, income: Money) -> Rate {
+    if income > Money::ZERO { Rate::from_fraction(tax / income) } else { Rate::ZERO }
+}
+
+// Negative incomes (losses) are counted as zero so the curve stays a share
+// of what was actually earned
+fn lorenz_curve(incomes: &[Money]) -> Vec<(f64, f64)> {
+    let mut sorted = incomes.iter().map(|income| income.amount().max(0.0)).collect::<Vec<_>>();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let total = sorted.iter().sum::<f64>();
+    let count = sorted.len() as f64;
+
+    let mut cumulative = 0.0;
+    let mut points = vec![(0.0, 0.0)];
+    for (index, income) in sorted.iter().enumerate() {
+        cumulative += income;
+        let share = if total > 0.0 { cumulative / total } else { 0.0 };
+        points.push(((index + 1) as f64 / count, share));
+    }
+    points
+}
+
+// Twice the area between the line of equality and the curve
+fn gini(curve: &[(f64, f64)]) -> f64 {
+    let below = curve
+        .windows(2)
+        .map(|pair| (pair[1].0 - pair[0].0) * (pair[0].1 + pair[1].1) / 2.0)
+        .sum::<f64>();
+    (1.0 - 2.0 * below).max(0.0)
+}
+
+// Deciles are by gross income. With fewer than ten clients some deciles are
+// empty and left out.
+pub fn population_stats(rows: &[BatchRow]) -> Option<PopulationStats> {
+    if rows.is_empty() {
+        return None;
+    }
+
+    let mut sorted = rows.iter().map(|row| &row.result).collect::<Vec<_>>();
+    sorted.sort_by(|a, b| a.gross_income.total_cmp(&b.gross_income));
+    let total_gross_income = sorted.iter().map(|result| result.gross_income).sum::<Money>();
+    let total_tax = sorted.iter().map(|result| result.total_tax).sum::<Money>();
+
+    let deciles = (0..DECILES)
+        .filter_map(|decile| {
+            let group = &sorted[decile * sorted.len() / DECILES..(decile + 1) * sorted.len() / DECILES];
+            let (first, last) = (group.first()?, group.last()?);
+            let income = group.iter().map(|result| result.gross_income).sum::<Money>();
+            let tax = group.iter().map(|result| result.total_tax).sum::<Money>();
+            let mut rates = group.iter().map(|result| result.effective_tax_rate).collect::<Vec<_>>();
+            Some(DecileStats {
+                decile: decile + 1,
+                clients: group.len(),
+                lowest_income: first.gross_income,
+                highest_income: last.gross_income,
+                total_tax: tax,
+                average_rate: rate(tax, income),
+                median_rate: median(&mut rates),
+            })
+        })
+        .collect();
+
+    let lorenz_gross = lorenz_curve(&sorted.iter().map(|result| result.gross_income).collect::<Vec<_>>());
+    let lorenz_net = lorenz_curve(&sorted.iter().map(|result| result.net_income).collect::<Vec<_>>());
+    let mut rates = sorted.iter().map(|result| result.effective_tax_rate).collect::<Vec<_>>();
+
+    Some(PopulationStats {
+        clients: rows.len(),
+        total_gross_income,
+        total_tax,
+        average_rate: rate(total_tax, total_gross_income),
+        median_rate: median(&mut rates),
+        deciles,
+        gini_gross: gini(&lorenz_gross),
+        gini_net: gini(&lorenz_net),
+        lorenz_gross,
+        lorenz_net,
+    })
+}
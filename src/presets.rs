@@ -0,0 +1,75 @@
+use crate::money::Money;
+use crate::tax_calculator::{EntityType, TaxCalculationInput, TaxYear};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputPreset {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub year: u16,
+    pub entity_type: EntityType,
+    pub gross_income: Money,
+}
+
+impl InputPreset {
+    pub fn to_input(&self) -> TaxCalculationInput {
+        TaxCalculationInput {
+            gross_income: self.gross_income,
+            entity_type: self.entity_type,
+            tax_year: TaxYear::from_year(self.year).unwrap_or_default(),
+            ..TaxCalculationInput::default()
+        }
+    }
+}
+
+pub fn presets() -> Vec<InputPreset> {
+    // The G presets follow the grunnbeløp in the 2024 rules
+    let grunnbeloep = TaxYear::Y2024.rules().grunnbeloep;
+    vec![
+        InputPreset {
+            id: "median-salary",
+            label: "Medianlønn i Norge",
+            year: 2024,
+            entity_type: EntityType::Individual,
+            gross_income: Money(612_000.0),
+        },
+        InputPreset {
+            id: "minimum-pension",
+            label: "Minste pensjonsnivå (enslig)",
+            year: 2024,
+            entity_type: EntityType::Individual,
+            gross_income: Money(224_248.0),
+        },
+        InputPreset {
+            id: "developer-salary",
+            label: "Typisk utviklerlønn",
+            year: 2024,
+            entity_type: EntityType::Individual,
+            gross_income: Money(750_000.0),
+        },
+        InputPreset {
+            id: "3g",
+            label: "3G",
+            year: 2024,
+            entity_type: EntityType::Individual,
+            gross_income: grunnbeloep.times(3.0),
+        },
+        InputPreset {
+            id: "6g",
+            label: "6G",
+            year: 2024,
+            entity_type: EntityType::Individual,
+            gross_income: grunnbeloep.times(6.0),
+        },
+        InputPreset {
+            id: "7.1g",
+            label: "7,1G",
+            year: 2024,
+            entity_type: EntityType::Individual,
+            gross_income: grunnbeloep.times(7.1),
+        },
+    ]
+}
+
+pub fn find_preset(id: &str) -> Option<InputPreset> {
+    presets().into_iter().find(|preset| preset.id == id)
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+use crate::money::{Money, Rate};
+use crate::tax_calculator::{BreakdownCategory, TaxBreakdownItem, TaxCalculationResult, TaxRule};
+
+// Dwellings are taxed on 70 % of the base (eigedomsskattelova § 8 C-1)
+const DWELLING_REDUCTION_FACTOR: f64 = 0.7;
+
+// Eiendomsskatt on the user's home. It is a municipal tax on property, not
+// on income, so it is listed in its own category and only counted in the
+// total when asked for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "StoredPropertyTax")]
+pub struct PropertyTaxInput {
+    pub include: bool,
+    // The municipality's eiendomsskattegrunnlag for the home, before the
+    // reduction factor
+    pub home_value: Money,
+    // Rate on the reduced base, at most 4 ‰ for dwellings
+    pub rate: Rate,
+    pub bunnfradrag: Money,
+}
+
+// Saved inputs from before the rate was a Rate have it in promille
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct StoredPropertyTax {
+    include: bool,
+    home_value: Money,
+    rate: Option<Rate>,
+    per_mille: Option<f64>,
+    bunnfradrag: Money,
+}
+
+impl From<StoredPropertyTax> for PropertyTaxInput {
+    fn from(stored: StoredPropertyTax) -> PropertyTaxInput {
+        PropertyTaxInput {
+            include: stored.include,
+            home_value: stored.home_value,
+            rate: stored.rate.or(stored.per_mille.map(Rate::from_per_mille)).unwrap_or_default(),
+            bunnfradrag: stored.bunnfradrag,
+        }
+    }
+}
+
+impl PropertyTaxInput {
+    // The yearly tax, whether or not it is included
+    pub fn estimate(&self) -> Money {
+        let base = (self.home_value * DWELLING_REDUCTION_FACTOR - self.bunnfradrag).max(Money::ZERO);
+        (base * self.rate).max(Money::ZERO)
+    }
+}
+
+// Adds the tax to a finished result when it is included. Custom items on the
+// total tax are applied first, so they don't count the property tax.
+pub fn apply_property_tax(result: &mut TaxCalculationResult, input: &PropertyTaxInput, detailed: bool) {
+    let tax = input.estimate();
+    if !input.include || !tax.amount().is_finite() || tax == Money::ZERO {
+        return;
+    }
+
+    if detailed {
+        result.breakdown.push(TaxBreakdownItem {
+            id: "property-tax".into(),
+            description: format!("Eiendomsskatt på bolig ({:.1} ‰)", input.rate.per_mille()).into(),
+            amount: tax,
+            rate: Some(input.rate),
+            category: BreakdownCategory::PropertyTax,
+            rule: TaxRule::PropertyTax,
+            is_estimate: true,
+        });
+    }
+
+    result.property_tax = tax;
+    result.total_tax += tax;
+    result.net_income -= tax;
+    result.effective_tax_rate = if result.gross_income > Money::ZERO {
+        Rate::from_fraction(result.total_tax / result.gross_income)
+    } else {
+        Rate::ZERO
+    };
+}
@@ -0,0 +1,44 @@
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::tax_calculator::{NorwegianTaxCalculator, TaxYear};
+
+const MANIFEST_PATH: &str = "manifest.webmanifest";
+const SERVICE_WORKER_PATH: &str = "sw.js";
+
+// The service worker names its cache after this, so a new build or a
+// ruleset update replaces everything cached offline
+pub fn cache_version() -> String {
+    let rulesets = TaxYear::ALL
+        .iter()
+        .map(|year| NorwegianTaxCalculator::ruleset_version(*year))
+        .collect::<Vec<_>>()
+        .join("+");
+    format!("{}-{}", env!("CARGO_PKG_VERSION"), rulesets)
+}
+
+fn add_manifest_link(document: &web_sys::Document) -> Result<(), JsValue> {
+    if document.query_selector("link[rel=\"manifest\"]")?.is_some() {
+        return Ok(());
+    }
+    let link = document.create_element("link")?;
+    link.set_attribute("rel", "manifest")?;
+    link.set_attribute("href", MANIFEST_PATH)?;
+    document.head().ok_or_else(|| JsValue::from_str("no head"))?.append_child(&link)?;
+    Ok(())
+}
+
+pub async fn register() -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("no window"))?;
+    let document = window.document().ok_or_else(|| JsValue::from_str("no document"))?;
+    add_manifest_link(&document)?;
+
+    // Browsers without service worker support still run the app, just not offline
+    let navigator = window.navigator();
+    if !js_sys::Reflect::has(&navigator, &JsValue::from_str("serviceWorker"))? {
+        return Ok(());
+    }
+    let url = format!("{}?v={}", SERVICE_WORKER_PATH, js_sys::encode_uri_component(&cache_version()));
+    JsFuture::from(navigator.service_worker().register(&url)).await?;
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use printpdf::{
+    BuiltinFont, Color, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Rect, Rgb,
+};
+
+use crate::money::Money;
+use crate::scenarios::ScenarioNotes;
+use crate::tax_calculator::{NorwegianTaxCalculator, TaxCalculationResult};
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const LINE_HEIGHT: f32 = 6.5;
+// About what fits between the margins in 10 pt Helvetica
+const PARAGRAPH_WIDTH: usize = 95;
+
+struct ReportWriter {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    y: f32,
+}
+
+impl ReportWriter {
+    fn new(title: &str) -> Result<Self, printpdf::Error> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Skatterapport");
+        let regular = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        Ok(Self {
+            doc,
+            layer,
+            regular,
+            bold,
+            y: PAGE_HEIGHT - MARGIN,
+        })
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Skatterapport");
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn heading(&mut self, text: &str, size: f32) {
+        self.ensure_space(size * 0.6 + LINE_HEIGHT);
+        self.y -= size * 0.4;
+        self.set_text_color(15, 23, 42);
+        self.layer.use_text(text, size, Mm(MARGIN), Mm(self.y), &self.bold);
+        self.y -= LINE_HEIGHT;
+    }
+
+    fn row(&mut self, label: &str, rate: &str, amount: &str, bold: bool) {
+        self.ensure_space(LINE_HEIGHT);
+        let font = if bold { &self.bold } else { &self.regular };
+        self.set_text_color(51, 65, 85);
+        self.layer.use_text(label, 10.0, Mm(MARGIN), Mm(self.y), font);
+        self.layer.use_text(rate, 10.0, Mm(125.0), Mm(self.y), font);
+        self.layer.use_text(amount, 10.0, Mm(150.0), Mm(self.y), font);
+        self.y -= LINE_HEIGHT;
+    }
+
+    // Wrapped at word boundaries, keeping the line breaks in the text
+    fn paragraph(&mut self, text: &str) {
+        for line in text.lines() {
+            let mut current = String::new();
+            for word in line.split_whitespace() {
+                if !current.is_empty() && current.chars().count() + word.chars().count() + 1 > PARAGRAPH_WIDTH {
+                    self.row(&current, "", "", false);
+                    current.clear();
+                }
+                if !current.is_empty() {
+                    current.push(' ');
+                }
+                current.push_str(word);
+            }
+            self.row(&current, "", "", false);
+        }
+    }
+
+    fn bar(&mut self, label: &str, value: Money, max_value: Money) {
+        let bar_height = 7.0;
+        self.ensure_space(bar_height + 3.0);
+
+        let max_width = PAGE_WIDTH - 2.0 * MARGIN - 60.0;
+        let width = if max_value > Money::ZERO { (value / max_value) as f32 * max_width } else { 0.0 };
+        let bottom = self.y - bar_height + 2.0;
+
+        self.set_text_color(51, 65, 85);
+        self.layer.use_text(label, 9.0, Mm(MARGIN), Mm(bottom + 2.0), &self.regular);
+
+        self.layer.set_fill_color(Color::Rgb(Rgb::new(0.231, 0.510, 0.965, None)));
+        self.layer.add_rect(Rect::new(
+            Mm(MARGIN + 45.0),
+            Mm(bottom),
+            Mm(MARGIN + 45.0 + width.max(0.5)),
+            Mm(bottom + bar_height - 2.0),
+        ));
+
+        self.set_text_color(51, 65, 85);
+        self.layer.use_text(
+            NorwegianTaxCalculator::format_nok(value),
+            9.0,
+            Mm(MARGIN + 47.0 + width),
+            Mm(bottom + 2.0),
+            &self.regular,
+        );
+
+        self.y -= bar_height + 3.0;
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn set_text_color(&self, r: u8, g: u8, b: u8) {
+        self.layer.set_fill_color(Color::Rgb(Rgb::new(
+            r as f32 / 255.0,
+            g as f32 / 255.0,
+            b as f32 / 255.0,
+            None,
+        )));
+    }
+}
+
+pub fn generate_pdf_report(
+    result: &TaxCalculationResult,
+    comparisons: &[(String, TaxCalculationResult)],
+    notes: &ScenarioNotes,
+) -> Result<Vec<u8>, printpdf::Error> {
+    let mut writer = ReportWriter::new("Skatteberegning")?;
+
+    writer.heading("Norsk Skattekalkulator - Skatteberegning", 18.0);
+    writer.gap(4.0);
+
+    writer.heading("Sammendrag", 13.0);
+    writer.row("Bruttoinntekt", "", &NorwegianTaxCalculator::format_nok(result.gross_income), false);
+    if result.personinntekt > Money::ZERO {
+        writer.row("Personinntekt", "", &NorwegianTaxCalculator::format_nok(result.personinntekt), false);
+    }
+    writer.row("Alminnelig inntekt", "", &NorwegianTaxCalculator::format_nok(result.alminnelig_inntekt), false);
+    writer.row("Total skatt", "", &NorwegianTaxCalculator::format_nok(result.total_tax), false);
+    writer.row("Nettoinntekt", "", &NorwegianTaxCalculator::format_nok(result.net_income), true);
+    writer.row("Effektiv skattesats", "", &format!("{:.1}", result.effective_tax_rate), false);
+    if result.pension_accrual > Money::ZERO {
+        writer.row("Pensjonsopptjening (estimat)", "", &NorwegianTaxCalculator::format_nok(result.pension_accrual), false);
+    }
+    writer.row(
+        "Regelverk",
+        "",
+        &format!("{} ({})", NorwegianTaxCalculator::ruleset_version(result.tax_year), result.tax_year.year()),
+        false,
+    );
+    writer.gap(6.0);
+
+    if !result.assumptions.is_empty() {
+        writer.heading("Forutsetninger", 13.0);
+        for assumption in &result.assumptions {
+            writer.row(assumption, "", "", false);
+        }
+        writer.gap(6.0);
+    }
+
+    if !notes.text.trim().is_empty() {
+        writer.heading("Notater", 13.0);
+        writer.paragraph(notes.text.trim());
+        writer.gap(6.0);
+    }
+
+    writer.heading("Detaljert beregning", 13.0);
+    writer.row("Post", "Sats", "Beløp", true);
+    for item in &result.breakdown {
+        let rate = item.rate.map(|rate| format!("{:.1}", rate)).unwrap_or_default();
+        let description = if item.is_estimate {
+            format!("{} (anslag)", item.description).into()
+        } else {
+            item.description.clone()
+        };
+        writer.row(&description, &rate, &NorwegianTaxCalculator::format_nok(item.amount), false);
+        if let Some(note) = notes.line(&item.id) {
+            writer.row(&format!("    Notat: {}", note), "", "", false);
+        }
+    }
+    writer.gap(6.0);
+
+    if !comparisons.is_empty() {
+        writer.heading("Sammenligning av total skatt", 13.0);
+        let max_tax = comparisons
+            .iter()
+            .map(|(_, result)| result.total_tax)
+            .fold(Money::ZERO, Money::max);
+        for (title, result) in comparisons {
+            writer.bar(title, result.total_tax, max_tax);
+        }
+    }
+
+    writer.doc.save_to_bytes()
+}
@@ -0,0 +1,74 @@
+use leptos::*;
+use leptos_router::{use_navigate, use_params_map, NavigateOptions};
+
+use crate::tax_calculator::EntityType;
+
+// Set at build time when the app is served under a sub-path, such as the
+// repository name on GitHub Pages. The deploy copies index.html to 404.html
+// so a deep link there still loads the app.
+pub const BASE_PATH: Option<&str> = option_env!("NTC_BASE_PATH");
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Route {
+    Entity(EntityType),
+}
+
+impl Route {
+    pub fn path(&self) -> &'static str {
+        match self {
+            Route::Entity(EntityType::Individual) => "/person",
+            Route::Entity(EntityType::Corporation) => "/as",
+            Route::Entity(EntityType::Partnership) => "/deltakerlignet",
+            Route::Entity(EntityType::SoleProprietorship) => "/enk",
+            Route::Entity(EntityType::ForeignBranch) => "/nuf",
+            Route::Entity(EntityType::Nonprofit) => "/ideell",
+        }
+    }
+
+    pub fn from_path(path: &str) -> Option<Route> {
+        match path.trim_end_matches('/') {
+            "/person" => Some(Route::Entity(EntityType::Individual)),
+            "/as" => Some(Route::Entity(EntityType::Corporation)),
+            "/deltakerlignet" => Some(Route::Entity(EntityType::Partnership)),
+            "/enk" => Some(Route::Entity(EntityType::SoleProprietorship)),
+            "/nuf" => Some(Route::Entity(EntityType::ForeignBranch)),
+            "/ideell" => Some(Route::Entity(EntityType::Nonprofit)),
+            _ => None,
+        }
+    }
+}
+
+// Links from before the router kept the route in the hash (`#/enk`)
+fn legacy_hash_route() -> Option<Route> {
+    let hash = window().location().hash().ok()?;
+    Route::from_path(hash.trim_start_matches('#'))
+}
+
+// The route in the `/:entity?` segment. Must be called within the <Router>.
+pub fn use_url_route() -> Memo<Option<Route>> {
+    let params = use_params_map();
+    create_memo(move |_| {
+        params
+            .with(|params| params.get("entity").and_then(|slug| Route::from_path(&format!("/{}", slug))))
+            .or_else(legacy_hash_route)
+    })
+}
+
+// Keeps the URL on the given route. The first sync replaces the entry, so a
+// bare or legacy URL becomes the canonical one without an extra back step;
+// later changes push one, so back/forward steps through the routes. The
+// route is read again when the frame runs, so two changes in one frame end
+// on the last instead of landing on the first and bouncing back.
+pub fn sync_url_route(route: impl Fn() -> Route + Copy + 'static) {
+    let navigate = use_navigate();
+    create_effect(move |previous: Option<()>| {
+        route();
+        let navigate = navigate.clone();
+        let options = NavigateOptions {
+            replace: previous.is_none(),
+            scroll: false,
+            ..NavigateOptions::default()
+        };
+        request_animation_frame(move || navigate(untrack(route).path(), options));
+    });
+}
@@ -0,0 +1,62 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+use crate::tax_calculator::TaxCalculationInput;
+
+pub const SCENARIOS_KEY: &str = "ntc.scenarios";
+// Notes on the calculation being edited, which go with it when it is saved
+pub const NOTES_KEY: &str = "ntc.notes";
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScenarioNotes {
+    pub text: String,
+    // By breakdown line id, which stays the same when the line is reworded
+    pub lines: BTreeMap<String, String>,
+}
+
+impl ScenarioNotes {
+    pub fn line(&self, id: &str) -> Option<&str> {
+        self.lines.get(id).map(String::as_str)
+    }
+
+    // An empty note removes the line's note
+    pub fn set_line(&mut self, id: &str, note: &str) {
+        let note = note.trim();
+        if note.is_empty() {
+            self.lines.remove(id);
+        } else {
+            self.lines.insert(id.to_string(), note.to_string());
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedScenario {
+    pub name: String,
+    pub input: TaxCalculationInput,
+    #[serde(default)]
+    pub notes: ScenarioNotes,
+}
+
+pub fn load_scenarios() -> Vec<SavedScenario> {
+    storage::load(SCENARIOS_KEY).unwrap_or_default()
+}
+
+pub fn save_scenarios(scenarios: &[SavedScenario]) {
+    storage::save(SCENARIOS_KEY, &scenarios);
+}
+
+pub fn load_notes() -> ScenarioNotes {
+    storage::load(NOTES_KEY).unwrap_or_default()
+}
+
+pub fn save_notes(notes: &ScenarioNotes) {
+    storage::save(NOTES_KEY, notes);
+}
+
+pub fn next_scenario_name(scenarios: &[SavedScenario]) -> String {
+    format!("Scenario {}", scenarios.len() + 1)
+}
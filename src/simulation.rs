@@ -0,0 +1,133 @@
+use crate::money::{Money, Rate};
+use crate::tax_calculator::{NorwegianTaxCalculator, TaxCalculationInput};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationParameters {
+    pub years: usize,
+    pub paths: usize,
+    pub expected_return: Rate,
+    pub volatility: Rate,
+    pub dividend_yield: Rate,
+    pub seed: u64,
+}
+
+impl Default for SimulationParameters {
+    fn default() -> Self {
+        Self {
+            years: 10,
+            paths: 500,
+            expected_return: Rate::from_percent(7.0),
+            volatility: Rate::from_percent(15.0),
+            dividend_yield: Rate::from_percent(3.0),
+            seed: 2024,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PercentileBand {
+    pub year: usize,
+    pub p10: Money,
+    pub p25: Money,
+    pub p50: Money,
+    pub p75: Money,
+    pub p90: Money,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationResult {
+    pub bands: Vec<PercentileBand>,
+    pub median_total_tax: Money,
+}
+
+// SplitMix64: small, fast and good enough for return paths. Seeded so a
+// simulation is reproducible for the same parameters.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Standard normal sample via Box-Muller
+    fn next_normal(&mut self) -> f64 {
+        let u1 = self.next_f64().max(f64::MIN_POSITIVE);
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+fn percentile(sorted: &[Money], p: f64) -> Money {
+    if sorted.is_empty() {
+        return Money::ZERO;
+    }
+    let index = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[index]
+}
+
+fn band(year: usize, values: &mut [Money]) -> PercentileBand {
+    values.sort_by(|a, b| a.total_cmp(b));
+    PercentileBand {
+        year,
+        p10: percentile(values, 0.10),
+        p25: percentile(values, 0.25),
+        p50: percentile(values, 0.50),
+        p75: percentile(values, 0.75),
+        p90: percentile(values, 0.90),
+    }
+}
+
+// Simulates after-tax investment wealth. Each year the portfolio draws a
+// normally distributed return; dividends and the realized gain are taxed
+// through the engine's shareholder rules and wealth tax is paid on the
+// opening value, with all taxes withdrawn from the portfolio.
+pub fn simulate_investment_outcomes(
+    base: &TaxCalculationInput,
+    parameters: &SimulationParameters,
+) -> SimulationResult {
+    let mut rng = Rng(parameters.seed);
+    let paths = parameters.paths.max(1);
+    let mut wealth = vec![base.investment_wealth; paths];
+    let mut total_tax = vec![Money::ZERO; paths];
+    let mut bands = vec![band(0, &mut wealth.clone())];
+
+    for year in 1..=parameters.years {
+        for (path_wealth, path_tax) in wealth.iter_mut().zip(total_tax.iter_mut()) {
+            let opening = *path_wealth;
+            let annual_return = parameters.expected_return + parameters.volatility * rng.next_normal();
+            let dividends = opening * parameters.dividend_yield;
+            let gain = opening * (annual_return - parameters.dividend_yield);
+
+            let result = NorwegianTaxCalculator::calculate_totals_only(&TaxCalculationInput {
+                gross_income: Money::ZERO,
+                allowable_deductions: Money::ZERO,
+                business_expenses: Money::ZERO,
+                dividend_income: dividends,
+                capital_gains: gain,
+                investment_wealth: opening,
+                ..base.clone()
+            });
+            let tax = result.investment_tax + result.wealth_tax;
+
+            *path_tax += tax;
+            *path_wealth = (opening + dividends + gain - tax).max(Money::ZERO);
+        }
+
+        bands.push(band(year, &mut wealth.clone()));
+    }
+
+    total_tax.sort_by(|a, b| a.total_cmp(b));
+
+    SimulationResult {
+        bands,
+        median_total_tax: percentile(&total_tax, 0.5),
+    }
+}
@@ -0,0 +1,272 @@
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::money::Money;
+use crate::tax_calculator::{EntityType, NorwegianTaxCalculator, TaxCalculationInput};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PostField {
+    GrossIncome,
+    Deductions,
+    DividendIncome,
+    CapitalGains,
+    InvestmentWealth,
+}
+
+impl PostField {
+    fn value(self, input: &TaxCalculationInput) -> Money {
+        match self {
+            PostField::GrossIncome => input.gross_income,
+            PostField::Deductions => input.allowable_deductions,
+            PostField::DividendIncome => input.dividend_income,
+            PostField::CapitalGains => input.capital_gains,
+            PostField::InvestmentWealth => input.investment_wealth,
+        }
+    }
+
+    fn target(self, input: &mut TaxCalculationInput) -> &mut Money {
+        match self {
+            PostField::GrossIncome => &mut input.gross_income,
+            PostField::Deductions => &mut input.allowable_deductions,
+            PostField::DividendIncome => &mut input.dividend_income,
+            PostField::CapitalGains => &mut input.capital_gains,
+            PostField::InvestmentWealth => &mut input.investment_wealth,
+        }
+    }
+}
+
+// Skattemelding posts the calculator understands. A post number ending in
+// a dot maps the whole section, e.g. every 3.2.x deduction.
+pub struct PostMapping {
+    pub post: &'static str,
+    pub label: &'static str,
+    pub field: PostField,
+}
+
+pub const POST_MAPPINGS: &[PostMapping] = &[
+    PostMapping { post: "2.1.1", label: "Lønn", field: PostField::GrossIncome },
+    PostMapping { post: "3.1.8", label: "Aksjeutbytte", field: PostField::DividendIncome },
+    PostMapping { post: "3.1.10", label: "Gevinst ved salg av aksjer", field: PostField::CapitalGains },
+    PostMapping { post: "3.2.", label: "Fradrag i arbeidsinntekt", field: PostField::Deductions },
+    PostMapping { post: "3.3.1", label: "Renter på gjeld", field: PostField::Deductions },
+    PostMapping { post: "4.1.7", label: "Aksjefond", field: PostField::InvestmentWealth },
+    PostMapping { post: "4.1.8", label: "Aksjer", field: PostField::InvestmentWealth },
+];
+
+// The post each field is written to on export
+pub const EXPORT_POSTS: &[PostMapping] = &[
+    PostMapping { post: "2.1.1", label: "Lønn", field: PostField::GrossIncome },
+    PostMapping { post: "3.1.8", label: "Aksjeutbytte", field: PostField::DividendIncome },
+    PostMapping { post: "3.1.10", label: "Gevinst ved salg av aksjer", field: PostField::CapitalGains },
+    PostMapping { post: "3.2.1", label: "Fradrag i arbeidsinntekt", field: PostField::Deductions },
+    PostMapping { post: "4.1.8", label: "Aksjer", field: PostField::InvestmentWealth },
+];
+
+pub fn find_mapping(post: &str) -> Option<&'static PostMapping> {
+    POST_MAPPINGS.iter().find(|mapping| {
+        if mapping.post.ends_with('.') {
+            post.starts_with(mapping.post)
+        } else {
+            post == mapping.post
+        }
+    })
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    Json(serde_json::Error),
+    Xml(roxmltree::Error),
+    UnknownFormat,
+    NoPosts,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Json(err) => write!(f, "Ugyldig JSON: {}", err),
+            ImportError::Xml(err) => write!(f, "Ugyldig XML: {}", err),
+            ImportError::UnknownFormat => write!(f, "Filen er verken JSON eller XML"),
+            ImportError::NoPosts => write!(f, "Fant ingen poster i skattemeldingen"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedPost {
+    pub post: String,
+    pub label: &'static str,
+    pub amount: Money,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SkattemeldingImport {
+    pub input: TaxCalculationInput,
+    pub applied: Vec<ImportedPost>,
+    pub ignored: Vec<String>,
+}
+
+fn parse_amount(raw: &str) -> Option<f64> {
+    raw.chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .replace(',', ".")
+        .parse()
+        .ok()
+}
+
+fn json_amount(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(raw) => parse_amount(raw),
+        _ => None,
+    }
+}
+
+// Accepts a flat `{"2.1.1": 600000}` object, a list of
+// `{"post": "2.1.1", "beloep": 600000}` entries, or such a list under "poster"
+fn json_posts(value: &Value) -> Vec<(String, f64)> {
+    let entry = |item: &Value| {
+        let post = item.get("post").or_else(|| item.get("postnummer"))?;
+        let post = post.as_str().map(str::to_string).unwrap_or_else(|| post.to_string());
+        let amount = json_amount(item.get("beloep").or_else(|| item.get("belop"))?)?;
+        Some((post, amount))
+    };
+
+    match value {
+        Value::Array(items) => items.iter().filter_map(entry).collect(),
+        Value::Object(map) => match map.get("poster") {
+            Some(poster) => json_posts(poster),
+            None => map
+                .iter()
+                .filter_map(|(post, amount)| Some((post.clone(), json_amount(amount)?)))
+                .collect(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+// Any element carrying a post number, as a `post`/`postnummer` attribute or
+// child element, with the amount in a `beloep` attribute, child or its text
+fn xml_posts(document: &roxmltree::Document) -> Vec<(String, f64)> {
+    let child_text = |node: roxmltree::Node, name: &str| {
+        node.children()
+            .find(|child| child.tag_name().name() == name)
+            .and_then(|child| child.text())
+            .map(str::to_string)
+    };
+
+    document
+        .descendants()
+        .filter(|node| node.is_element())
+        .filter_map(|node| {
+            let post = node
+                .attribute("post")
+                .or_else(|| node.attribute("postnummer"))
+                .map(str::to_string)
+                .or_else(|| child_text(node, "post"))
+                .or_else(|| child_text(node, "postnummer"))?;
+            let amount = node
+                .attribute("beloep")
+                .map(str::to_string)
+                .or_else(|| child_text(node, "beloep"))
+                .or_else(|| node.text().map(str::to_string))?;
+            Some((post.trim().to_string(), parse_amount(&amount)?))
+        })
+        .collect()
+}
+
+pub fn import_skattemelding(data: &str, base: &TaxCalculationInput) -> Result<SkattemeldingImport, ImportError> {
+    let trimmed = data.trim_start_matches('\u{feff}').trim_start();
+    let posts = match trimmed.chars().next() {
+        Some('{') | Some('[') => json_posts(&serde_json::from_str(trimmed).map_err(ImportError::Json)?),
+        Some('<') => xml_posts(&roxmltree::Document::parse(trimmed).map_err(ImportError::Xml)?),
+        _ => return Err(ImportError::UnknownFormat),
+    };
+    if posts.is_empty() {
+        return Err(ImportError::NoPosts);
+    }
+
+    let mut totals: Vec<(PostField, Money)> = Vec::new();
+    let mut applied = Vec::new();
+    let mut ignored = Vec::new();
+
+    for (post, amount) in posts {
+        let amount = Money(amount);
+        let Some(mapping) = find_mapping(&post) else {
+            ignored.push(post);
+            continue;
+        };
+        match totals.iter_mut().find(|(field, _)| *field == mapping.field) {
+            Some((_, total)) => *total += amount.abs(),
+            None => totals.push((mapping.field, amount.abs())),
+        }
+        applied.push(ImportedPost {
+            post,
+            label: mapping.label,
+            amount,
+        });
+    }
+
+    // Imported posts replace the matching fields; everything else is kept
+    let mut input = base.clone();
+    for (field, total) in totals {
+        *field.target(&mut input) = total;
+    }
+
+    Ok(SkattemeldingImport { input, applied, ignored })
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ExportedPost {
+    pub post: &'static str,
+    #[serde(rename = "beskrivelse")]
+    pub label: &'static str,
+    #[serde(rename = "beloep")]
+    pub amount: Money,
+}
+
+#[derive(Serialize)]
+struct ExportDocument<'a> {
+    inntektsaar: u16,
+    regelverk: &'static str,
+    poster: &'a [ExportedPost],
+}
+
+// Only personal tax returns are covered; companies and sole proprietors
+// report business income on forms the calculator doesn't model
+pub fn supports_export(entity_type: EntityType) -> bool {
+    entity_type == EntityType::Individual
+}
+
+pub fn export_posts(input: &TaxCalculationInput) -> Vec<ExportedPost> {
+    EXPORT_POSTS
+        .iter()
+        .map(|mapping| ExportedPost {
+            post: mapping.post,
+            label: mapping.label,
+            amount: mapping.field.value(input).round(),
+        })
+        .filter(|post| post.amount > Money::ZERO)
+        .collect()
+}
+
+// Written in the same shape the importer reads, so an export can be loaded back
+pub fn posts_to_json(input: &TaxCalculationInput) -> Result<Vec<u8>, serde_json::Error> {
+    serde_json::to_vec_pretty(&ExportDocument {
+        inntektsaar: input.tax_year.year(),
+        regelverk: NorwegianTaxCalculator::ruleset_version(input.tax_year),
+        poster: &export_posts(input),
+    })
+}
+
+pub fn posts_to_csv(input: &TaxCalculationInput) -> Result<Vec<u8>, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for post in export_posts(input) {
+        writer.serialize(post)?;
+    }
+    writer
+        .into_inner()
+        .map_err(|err| csv::Error::from(err.into_error()))
+}
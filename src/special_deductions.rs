@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use crate::money::Money;
+use crate::tax_calculator::TaxRules;
+
+// Work that gives a deduction of its own: sjømannsfradrag for seafarers on
+// Norwegian ships and fiskerfradrag for fishers (skatteloven § 6-60 and § 6-61)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Occupation {
+    #[default]
+    Other,
+    Seafarer,
+    Fisher,
+}
+
+impl Occupation {
+    pub const ALL: [Occupation; 3] = [Occupation::Other, Occupation::Seafarer, Occupation::Fisher];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Occupation::Other => "Ingen",
+            Occupation::Seafarer => "Sjømann",
+            Occupation::Fisher => "Fisker",
+        }
+    }
+
+    pub fn deduction_name(&self) -> &'static str {
+        match self {
+            Occupation::Other => "Særskilt fradrag",
+            Occupation::Seafarer => "Sjømannsfradrag",
+            Occupation::Fisher => "Fiskerfradrag",
+        }
+    }
+}
+
+// Særfradrag few taxpayers qualify for, each behind the condition for it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpecialDeductionsInput {
+    // Særfradrag for store sykdomskostnader was abolished in 2012, but those
+    // who had it for 2010 or 2011 keep it (skatteloven § 6-83)
+    pub sickness_costs_transitional: bool,
+    pub sickness_costs: Money,
+    pub occupation: Occupation,
+}
+
+impl SpecialDeductionsInput {
+    // Documented costs above the bunnbeløp
+    pub fn sickness_costs_deduction(&self, rules: &TaxRules) -> Money {
+        if !self.sickness_costs_transitional {
+            return Money::ZERO;
+        }
+        (self.sickness_costs - rules.sickness_costs_threshold).max(Money::ZERO)
+    }
+
+    // A share of the income from the occupation, up to the cap
+    pub fn occupation_deduction(&self, rules: &TaxRules, income: Money) -> Money {
+        match self.occupation {
+            Occupation::Other => Money::ZERO,
+            Occupation::Seafarer | Occupation::Fisher => {
+                (income * rules.occupation_deduction_rate).clamp(Money::ZERO, rules.occupation_deduction_max)
+            }
+        }
+    }
+}
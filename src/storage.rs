@@ -0,0 +1,54 @@
+use serde::{de::DeserializeOwned, Serialize};
+use web_sys::Storage;
+
+// Every key the app saves starts with this
+pub const KEY_PREFIX: &str = "ntc.";
+pub const SESSION_KEY: &str = "ntc.session";
+pub const ADVANCED_MODE_KEY: &str = "ntc.advanced-mode";
+pub const ACCOUNTANT_MODE_KEY: &str = "ntc.accountant-mode";
+pub const TARGET_NET_MODE_KEY: &str = "ntc.target-net-mode";
+pub const POLICY_MODE_KEY: &str = "ntc.policy-mode";
+pub const STUDENT_MODE_KEY: &str = "ntc.student-mode";
+pub const LETTER_TEMPLATE_KEY: &str = "ntc.letter-template";
+
+fn local_storage() -> Option<Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let raw = local_storage()?.get_item(key).ok()??;
+    serde_json::from_str(&raw).ok()
+}
+
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    if let (Some(storage), Ok(raw)) = (local_storage(), serde_json::to_string(value)) {
+        let _ = storage.set_item(key, &raw);
+    }
+}
+
+pub fn remove(key: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(key);
+    }
+}
+
+// The app's keys and their values as saved
+pub fn entries() -> Vec<(String, String)> {
+    let Some(storage) = local_storage() else {
+        return Vec::new();
+    };
+    (0..storage.length().unwrap_or(0))
+        .filter_map(|index| {
+            let key = storage.key(index).ok()??;
+            let value = storage.get_item(&key).ok()??;
+            Some((key, value))
+        })
+        .filter(|(key, _)| key.starts_with(KEY_PREFIX))
+        .collect()
+}
+
+pub fn clear() {
+    if let Some(storage) = local_storage() {
+        let _ = storage.clear();
+    }
+}
@@ -1,345 +1,2258 @@
-#[derive(Clone, Copy, Debug, PartialEq)]
+//! The tax engine: inputs, results and [`NorwegianTaxCalculator`].
+//!
+//! ```
+//! use norwegian_tax_calculator::money::Money;
+//! use norwegian_tax_calculator::tax_calculator::{EntityType, NorwegianTaxCalculator, TaxCalculationInput};
+//!
+//! let input = TaxCalculationInput::new(EntityType::Individual, Money(750_000.0));
+//! let result = NorwegianTaxCalculator::calculate_tax(&input);
+//! println!("{} i skatt, {} netto", result.total_tax, result.net_income);
+//! ```
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::commuter::CommuteInput;
+use crate::custom_taxes::{apply_custom_taxes, CustomTax};
+use crate::industries::{
+    petroleum_special_tax, resource_rent_tax, PetroleumInput, ResourceRentActivity, ResourceRentInput,
+    PETROLEUM_SPECIAL_TAX_RATE,
+};
+use crate::employer_zones::EmployerZone;
+use crate::money::{Money, Rate};
+use crate::property_tax::{apply_property_tax, PropertyTaxInput};
+use crate::special_deductions::SpecialDeductionsInput;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum EntityType {
     Individual,
     Corporation,
     Partnership,
     SoleProprietorship,
+    // NUF: the Norwegian branch of a foreign company
+    ForeignBranch,
+    // Ideell organisasjon or stiftelse; only economic activity can be taxed
+    Nonprofit,
 }
 
-#[derive(Clone, Debug)]
+impl EntityType {
+    // Taxed as a company rather than a person, with no personinntekt or
+    // municipal and county tax
+    pub fn is_company(&self) -> bool {
+        matches!(self, EntityType::Corporation | EntityType::ForeignBranch | EntityType::Nonprofit)
+    }
+}
+
+// Frivillig tilleggstrygd for sykepenger. Without it an ENK owner gets 80%
+// sick pay from day 17.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SickPayInsurance {
+    #[default]
+    None,
+    FullFromDay17,
+    EightyFromDay1,
+    FullFromDay1,
+}
+
+impl SickPayInsurance {
+    pub const ALL: [SickPayInsurance; 4] = [
+        SickPayInsurance::None,
+        SickPayInsurance::FullFromDay17,
+        SickPayInsurance::EightyFromDay1,
+        SickPayInsurance::FullFromDay1,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SickPayInsurance::None => "Ingen (80 % fra dag 17)",
+            SickPayInsurance::FullFromDay17 => "100 % fra dag 17",
+            SickPayInsurance::EightyFromDay1 => "80 % fra dag 1",
+            SickPayInsurance::FullFromDay1 => "100 % fra dag 1",
+        }
+    }
+
+    // Premium as a share of pensjonsgivende inntekt up to 6G
+    pub fn premium_rate(&self) -> Rate {
+        match self {
+            SickPayInsurance::None => Rate::ZERO,
+            SickPayInsurance::FullFromDay17 => Rate::from_percent(2.0),
+            SickPayInsurance::EightyFromDay1 => Rate::from_percent(5.9),
+            SickPayInsurance::FullFromDay1 => Rate::from_percent(8.8),
+        }
+    }
+
+    // Days without sick pay, and the share of income covered after that
+    pub fn coverage(&self) -> (f64, f64) {
+        match self {
+            SickPayInsurance::None => (16.0, 0.8),
+            SickPayInsurance::FullFromDay17 => (16.0, 1.0),
+            SickPayInsurance::EightyFromDay1 => (0.0, 0.8),
+            SickPayInsurance::FullFromDay1 => (0.0, 1.0),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TaxCalculationInput {
-    pub gross_income: f64,
+    pub gross_income: Money,
     pub entity_type: EntityType,
-    pub municipal_tax_rate: f64,
-    pub county_tax_rate: f64,
-    pub church_tax_rate: f64,
+    pub municipal_tax_rate: Rate,
+    pub county_tax_rate: Rate,
+    pub church_tax_rate: Rate,
     pub is_church_member: bool,
-    pub allowable_deductions: f64,
-    pub dividend_income: f64,
-    pub capital_gains: f64,
-    pub investment_wealth: f64,
-    pub business_expenses: f64,
+    // Kirkeskatt was abolished; membership only affects tax in the historical model
+    pub historical_church_tax: bool,
+    pub allowable_deductions: Money,
+    pub dividend_income: Money,
+    pub capital_gains: Money,
+    pub investment_wealth: Money,
+    pub business_expenses: Money,
+    // Reisefradrag between home and work; only used for personal taxpayers
+    pub commute: CommuteInput,
+    // Gifts to approved voluntary organisations; only used for personal taxpayers
+    pub donations: Money,
+    // Paid into individuell pensjonssparing (IPS); only used for personal taxpayers
+    pub ips_contribution: Money,
+    // Særfradrag with conditions; only used for personal taxpayers
+    pub special_deductions: SpecialDeductionsInput,
+    // NAV benefits on top of the gross income. Sykepenger are taxed as pay;
+    // uføretrygd too, but with the lower trygdeavgift rate for pensions.
+    // Only used for individuals and partners.
+    pub sick_pay: Money,
+    pub disability_benefit: Money,
+    pub tax_year: TaxYear,
+    // Kommune number the municipal rate was picked from, if any
+    pub municipality: Option<String>,
+    // Only used for ENK
+    pub sick_pay_insurance: SickPayInsurance,
+    // Finnmark and Nord-Troms have a lower rate on alminnelig inntekt
+    pub tiltakssone: bool,
+    // Kommunal formueskatt where the municipality has set it below the
+    // year's maximum; None is the maximum
+    pub municipal_wealth_tax_rate: Option<Rate>,
+    // Salaries paid to employees in Norway; only used for NUF
+    pub branch_payroll: Money,
+    // A charitable or public-benefit purpose doubles the revenue limit for
+    // tax-free economic activity; only used for nonprofits
+    pub charitable_purpose: bool,
+    // Grunnrente activity of an AS
+    pub resource_rent: ResourceRentInput,
+    // Petroleum tax regime for an AS, mainly to compare against ordinary companies
+    pub petroleum: PetroleumInput,
+    // Extra line items on top of the statutory taxes
+    pub custom_taxes: Vec<CustomTax>,
+    // Eiendomsskatt on the home; only used for personal taxpayers
+    pub property_tax: PropertyTaxInput,
+    // Hypothetical rates replacing the year's law, for the policy playground
+    pub policy: Option<PolicyChanges>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+// Policy parameters that can be changed to test a proposal against the law
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PolicyChanges {
+    // One rate per trinnskatt step; the thresholds stay as in the law
+    pub state_tax_rates: Vec<Rate>,
+    pub wealth_tax_rate: Rate,
+    pub wealth_tax_threshold: Money,
+    // Oppjusteringsfaktor for dividends and gains on shares
+    pub dividend_factor: f64,
+}
+
+impl PolicyChanges {
+    pub fn baseline(tax_year: TaxYear) -> Self {
+        let rules = tax_year.rules();
+        Self {
+            state_tax_rates: rules.state_tax_brackets.iter().map(|&(_, rate)| rate).collect(),
+            wealth_tax_rate: rules.wealth_tax_rate,
+            wealth_tax_threshold: rules.wealth_tax_threshold,
+            dividend_factor: rules.dividend_factor(),
+        }
+    }
+
+    fn apply(&self, rules: &TaxRules) -> TaxRules {
+        let mut changed = rules.clone();
+        changed.state_tax_brackets = rules
+            .state_tax_brackets
+            .iter()
+            .zip(&self.state_tax_rates)
+            .map(|(&(threshold, _), &rate)| (threshold, rate))
+            .collect();
+        changed.wealth_tax_rate = self.wealth_tax_rate;
+        changed.wealth_tax_threshold = self.wealth_tax_threshold;
+        changed.investment_tax_rate = rules.ordinary_income_rate * self.dividend_factor;
+        changed
+    }
+}
+
+impl Default for TaxCalculationInput {
+    fn default() -> Self {
+        let (municipal_tax_rate, county_tax_rate, church_tax_rate) = NorwegianTaxCalculator::get_default_rates();
+        Self {
+            gross_income: Money(600_000.0),
+            entity_type: EntityType::Individual,
+            municipal_tax_rate,
+            county_tax_rate,
+            church_tax_rate,
+            is_church_member: true,
+            historical_church_tax: false,
+            allowable_deductions: Money::ZERO,
+            dividend_income: Money::ZERO,
+            capital_gains: Money::ZERO,
+            investment_wealth: Money::ZERO,
+            business_expenses: Money::ZERO,
+            commute: CommuteInput::default(),
+            donations: Money::ZERO,
+            ips_contribution: Money::ZERO,
+            special_deductions: SpecialDeductionsInput::default(),
+            sick_pay: Money::ZERO,
+            disability_benefit: Money::ZERO,
+            tax_year: TaxYear::default(),
+            municipality: None,
+            sick_pay_insurance: SickPayInsurance::None,
+            tiltakssone: false,
+            municipal_wealth_tax_rate: None,
+            branch_payroll: Money::ZERO,
+            charitable_purpose: true,
+            resource_rent: ResourceRentInput::default(),
+            petroleum: PetroleumInput::default(),
+            custom_taxes: Vec::new(),
+            property_tax: PropertyTaxInput::default(),
+            policy: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputSection {
+    Income,
+    Deductions,
+    Investments,
+    Rates,
+    CustomTaxes,
+}
+
+impl InputSection {
+    pub fn label(&self) -> &'static str {
+        match self {
+            InputSection::Income => "Inntekt",
+            InputSection::Deductions => "Fradrag",
+            InputSection::Investments => "Investeringer og formue",
+            InputSection::Rates => "Skattesatser",
+            InputSection::CustomTaxes => "Egendefinerte poster",
+        }
+    }
+}
+
+impl TaxCalculationInput {
+    /// An input for the given entity type and gross income, with the defaults
+    /// for everything else. The `with_` methods set the rest:
+    ///
+    /// ```
+    /// use norwegian_tax_calculator::money::{Money, Rate};
+    /// use norwegian_tax_calculator::tax_calculator::{EntityType, TaxCalculationInput, TaxYear};
+    ///
+    /// let input = TaxCalculationInput::new(EntityType::SoleProprietorship, Money(900_000.0))
+    ///     .with_tax_year(TaxYear::Y2024)
+    ///     .with_business_expenses(Money(120_000.0))
+    ///     .with_local_tax_rates(Rate::from_percent(10.95), Rate::from_percent(11.4))
+    ///     .with_church_member(false);
+    /// assert_eq!(input.business_expenses, Money(120_000.0));
+    /// ```
+    pub fn new(entity_type: EntityType, gross_income: Money) -> Self {
+        Self { entity_type, gross_income, ..Self::default() }
+    }
+
+    pub fn with_tax_year(self, tax_year: TaxYear) -> Self {
+        Self { tax_year, ..self }
+    }
+
+    pub fn with_deductions(self, allowable_deductions: Money) -> Self {
+        Self { allowable_deductions, ..self }
+    }
+
+    pub fn with_business_expenses(self, business_expenses: Money) -> Self {
+        Self { business_expenses, ..self }
+    }
+
+    pub fn with_branch_payroll(self, branch_payroll: Money) -> Self {
+        Self { branch_payroll, ..self }
+    }
+
+    pub fn with_dividend_income(self, dividend_income: Money) -> Self {
+        Self { dividend_income, ..self }
+    }
+
+    pub fn with_capital_gains(self, capital_gains: Money) -> Self {
+        Self { capital_gains, ..self }
+    }
+
+    pub fn with_investment_wealth(self, investment_wealth: Money) -> Self {
+        Self { investment_wealth, ..self }
+    }
+
+    /// Kommuneskatt and fylkeskatt; together they make up the municipal
+    /// share of the tax on alminnelig inntekt
+    pub fn with_local_tax_rates(self, municipal_tax_rate: Rate, county_tax_rate: Rate) -> Self {
+        Self { municipal_tax_rate, county_tax_rate, municipality: None, ..self }
+    }
+
+    pub fn with_church_member(self, is_church_member: bool) -> Self {
+        Self { is_church_member, ..self }
+    }
+
+    pub fn with_custom_tax(mut self, tax: CustomTax) -> Self {
+        self.custom_taxes.push(tax);
+        self
+    }
+
+    // The year's rules, with any policy changes applied
+    pub fn rules(&self) -> Cow<'static, TaxRules> {
+        let rules = self.tax_year.rules();
+        match &self.policy {
+            Some(policy) => Cow::Owned(policy.apply(rules)),
+            None => Cow::Borrowed(rules),
+        }
+    }
+
+    // The same scenario as seen by another entity type, with the fields that
+    // don't apply to that entity cleared
+    pub fn comparison_variant(&self, entity_type: EntityType) -> Self {
+        let mut variant = Self {
+            entity_type,
+            ..self.clone()
+        };
+        match entity_type {
+            EntityType::Individual | EntityType::Partnership => {
+                variant.business_expenses = Money::ZERO;
+                variant.sick_pay_insurance = SickPayInsurance::None;
+            }
+            EntityType::Corporation | EntityType::ForeignBranch => {
+                variant.is_church_member = false;
+                variant.investment_wealth = Money::ZERO;
+                variant.business_expenses = Money::ZERO;
+                variant.sick_pay_insurance = SickPayInsurance::None;
+            }
+            EntityType::Nonprofit => {
+                variant.is_church_member = false;
+                variant.investment_wealth = Money::ZERO;
+                variant.sick_pay_insurance = SickPayInsurance::None;
+            }
+            EntityType::SoleProprietorship => {}
+        }
+        variant
+    }
+
+    pub fn reset_section(&mut self, section: InputSection) {
+        let defaults = Self::default();
+        match section {
+            InputSection::Income => {
+                self.gross_income = defaults.gross_income;
+                self.business_expenses = defaults.business_expenses;
+                self.sick_pay = defaults.sick_pay;
+                self.disability_benefit = defaults.disability_benefit;
+                self.sick_pay_insurance = defaults.sick_pay_insurance;
+                self.branch_payroll = defaults.branch_payroll;
+                self.charitable_purpose = defaults.charitable_purpose;
+                self.resource_rent = defaults.resource_rent;
+                self.petroleum = defaults.petroleum;
+            }
+            InputSection::Deductions => {
+                self.allowable_deductions = defaults.allowable_deductions;
+                self.commute = defaults.commute;
+                self.donations = defaults.donations;
+                self.ips_contribution = defaults.ips_contribution;
+                self.special_deductions = defaults.special_deductions;
+            }
+            InputSection::Investments => {
+                self.dividend_income = defaults.dividend_income;
+                self.capital_gains = defaults.capital_gains;
+                self.investment_wealth = defaults.investment_wealth;
+            }
+            InputSection::Rates => {
+                self.municipal_tax_rate = defaults.municipal_tax_rate;
+                self.municipality = defaults.municipality;
+                self.municipal_wealth_tax_rate = defaults.municipal_wealth_tax_rate;
+                self.property_tax = defaults.property_tax;
+                self.county_tax_rate = defaults.county_tax_rate;
+                self.tiltakssone = defaults.tiltakssone;
+                self.church_tax_rate = defaults.church_tax_rate;
+                self.is_church_member = defaults.is_church_member;
+                self.historical_church_tax = defaults.historical_church_tax;
+            }
+            InputSection::CustomTaxes => {
+                self.custom_taxes = defaults.custom_taxes;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct TaxCalculationResult {
-    pub gross_income: f64,
-    pub personal_allowance: f64,
-    pub taxable_income: f64,
-    pub municipal_tax: f64,
-    pub county_tax: f64,
-    pub church_tax: f64,
-    pub state_tax: f64,
-    pub corporate_tax: f64,
-    pub national_insurance: f64,
-    pub investment_tax: f64,
-    pub wealth_tax: f64,
-    pub total_tax: f64,
-    pub net_income: f64,
-    pub effective_tax_rate: f64,
+    pub gross_income: Money,
+    pub personal_allowance: Money,
+    // Base for trinnskatt and trygdeavgift
+    pub personinntekt: Money,
+    // Base for the 22% tax, before personfradrag
+    pub alminnelig_inntekt: Money,
+    pub taxable_income: Money,
+    pub municipal_tax: Money,
+    pub county_tax: Money,
+    pub fellesskatt: Money,
+    pub church_tax: Money,
+    pub state_tax: Money,
+    pub corporate_tax: Money,
+    pub resource_rent_tax: Money,
+    pub national_insurance: Money,
+    pub investment_tax: Money,
+    pub wealth_tax: Money,
+    // Eiendomsskatt on the home, when included in the total
+    pub property_tax: Money,
+    pub total_tax: Money,
+    pub net_income: Money,
+    pub effective_tax_rate: Rate,
+    // Dividends and gains counted in gross_income
+    pub capital_income: Money,
+    // The effective rate without wealth tax and capital items, which can
+    // move the headline rate a lot in a year with a one-off gain
+    pub wage_effective_tax_rate: Rate,
+    // Estimated addition to the folketrygd pension balance; not a tax
+    pub pension_accrual: Money,
     pub breakdown: Vec<TaxBreakdownItem>,
+    pub warnings: Vec<TaxWarning>,
+    // What the figures rest on, so their precision isn't mistaken for a
+    // complete tax assessment
+    pub assumptions: Vec<String>,
+    pub tax_year: TaxYear,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+impl TaxCalculationResult {
+    /// The effective tax rate with the wealth tax, the capital items or both
+    /// left out. Leaving out capital items takes the dividends and gains out
+    /// of the income and the tax on them out of the total.
+    ///
+    /// ```
+    /// use norwegian_tax_calculator::money::Money;
+    /// use norwegian_tax_calculator::tax_calculator::{EntityType, NorwegianTaxCalculator, TaxCalculationInput};
+    ///
+    /// let input = TaxCalculationInput {
+    ///     capital_gains: Money(2_000_000.0),
+    ///     ..TaxCalculationInput::new(EntityType::Individual, Money(600_000.0))
+    /// };
+    /// let result = NorwegianTaxCalculator::calculate_tax(&input);
+    ///
+    /// assert_eq!(result.effective_tax_rate_excluding(false, false), result.effective_tax_rate);
+    /// assert_eq!(result.effective_tax_rate_excluding(true, true), result.wage_effective_tax_rate);
+    /// assert!(result.wage_effective_tax_rate < result.effective_tax_rate);
+    /// ```
+    pub fn effective_tax_rate_excluding(&self, wealth_tax: bool, capital: bool) -> Rate {
+        let mut tax = self.total_tax;
+        let mut income = self.gross_income;
+        if wealth_tax {
+            tax -= self.wealth_tax;
+        }
+        if capital {
+            tax -= self.investment_tax;
+            income -= self.capital_income;
+        }
+        if income > Money::ZERO {
+            Rate::from_fraction(tax / income)
+        } else {
+            Rate::ZERO
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct TaxBreakdownItem {
-    pub description: String,
-    pub amount: f64,
-    pub rate: Option<f64>,
+    // Stays the same when the description is reworded or translated, so
+    // exports, diffs and snapshots can match lines across runs. Unique
+    // within one result.
+    pub id: Cow<'static, str>,
+    pub description: Cow<'static, str>,
+    pub amount: Money,
+    pub rate: Option<Rate>,
+    pub category: BreakdownCategory,
+    pub rule: TaxRule,
+    // The rule is simplified or the figure rests on an estimate, such as a
+    // valuation discount or an assumed rate
+    pub is_estimate: bool,
 }
 
-pub struct NorwegianTaxCalculator;
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub enum BreakdownCategory {
+    Info,
+    Deductions,
+    IncomeTax,
+    NationalInsurance,
+    Capital,
+    Wealth,
+    ResourceRent,
+    PropertyTax,
+    Custom,
+}
 
-impl NorwegianTaxCalculator {
-    // 2024 Norwegian Tax Rates and Constants
-    const PERSONAL_ALLOWANCE_2024: f64 = 69_100.0;
-    const CORPORATE_TAX_RATE_2024: f64 = 0.22; // 22%
-    const NATIONAL_INSURANCE_RATE_2024: f64 = 0.077; // 7.7% for employees
-    const NATIONAL_INSURANCE_RATE_ENK_2024: f64 = 0.109; // 10.9% for sole proprietors
-    const INVESTMENT_TAX_RATE_2024: f64 = 0.3784; // 37.84% effective rate on investments
-    const WEALTH_TAX_RATE_2024: f64 = 0.01; // 1% wealth tax
-    const WEALTH_TAX_THRESHOLD_2024: f64 = 2_000_000.0; // 2M NOK threshold
-    const RISK_FREE_RATE_2024: f64 = 0.0172; // 1.72% risk-free return allowance
-    
-    // State tax brackets for 2024 (statsskatt)
-    const STATE_TAX_BRACKETS: &'static [(f64, f64)] = &[
-        (208_050.0, 0.017),   // 1.7% on income above 208,050 NOK
-        (292_850.0, 0.04),    // 4.0% on income above 292,850 NOK
-        (670_000.0, 0.136),   // 13.6% on income above 670,000 NOK
-        (937_900.0, 0.166),   // 16.6% on income above 937,900 NOK
-        (1_350_000.0, 0.176), // 17.6% on income above 1,350,000 NOK
+impl BreakdownCategory {
+    pub const ALL: [BreakdownCategory; 9] = [
+        BreakdownCategory::Info,
+        BreakdownCategory::Deductions,
+        BreakdownCategory::IncomeTax,
+        BreakdownCategory::NationalInsurance,
+        BreakdownCategory::Capital,
+        BreakdownCategory::Wealth,
+        BreakdownCategory::ResourceRent,
+        BreakdownCategory::PropertyTax,
+        BreakdownCategory::Custom,
     ];
 
+    pub fn label(&self) -> &'static str {
+        match self {
+            BreakdownCategory::Info => "Informasjon",
+            BreakdownCategory::Deductions => "Fradrag",
+            BreakdownCategory::IncomeTax => "Inntektsskatt",
+            BreakdownCategory::NationalInsurance => "Trygdeavgift",
+            BreakdownCategory::Capital => "Kapitalinntekt",
+            BreakdownCategory::Wealth => "Formueskatt",
+            BreakdownCategory::ResourceRent => "Grunnrenteskatt",
+            BreakdownCategory::PropertyTax => "Eiendomsskatt (kommunal, ikke inntektsskatt)",
+            BreakdownCategory::Custom => "Egendefinerte poster",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum TaxRule {
+    GrossIncome,
+    Personinntekt,
+    AlminneligInntekt,
+    PersonalAllowance,
+    Deductions,
+    CommuterDeduction,
+    LivingAwayFromHome,
+    Donations,
+    IndividualPensionSaving,
+    LargeSicknessCosts,
+    OccupationDeduction,
+    BusinessExpenses,
+    MunicipalTax,
+    CountyTax,
+    Fellesskatt,
+    ChurchTax,
+    StateTax,
+    NationalInsurance,
+    CorporateTax,
+    RiskFreeAllowance,
+    ShareholderTax,
+    ParticipationExemption,
+    WealthTax,
+    PartnershipTaxation,
+    SoleProprietorship,
+    PensionAccrual,
+    SickPayInsurance,
+    SickPay,
+    DisabilityBenefit,
+    LossCarryForward,
+    ForeignBranch,
+    EmployerContribution,
+    Nonprofit,
+    ResourceRentTax,
+    PetroleumTax,
+    PropertyTax,
+    CustomTax,
+}
+
+// The income bases a deduction can be subtracted from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum IncomeBase {
+    Personinntekt,
+    AlminneligInntekt,
+}
+
+impl IncomeBase {
+    pub fn label(&self) -> &'static str {
+        match self {
+            IncomeBase::Personinntekt => "personinntekt",
+            IncomeBase::AlminneligInntekt => "alminnelig inntekt",
+        }
+    }
+}
+
+// Raised where the engine clamps a base to zero, so the user can see that
+// part of a deduction or allowance had no effect
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum TaxWarning {
+    Deductions { base: IncomeBase, unused: Money },
+    PersonalAllowance { unused: Money },
+    RiskFreeAllowance { unused: Money },
+    Donations { cap: Money, unused: Money },
+    // Kommuneskatt and fylkeskatt above the total rate on alminnelig inntekt
+    LocalRatesAboveTotal { cuts: LocalRateCuts, excess: Money },
+}
+
+impl TaxWarning {
+    pub fn message(&self) -> String {
+        match self {
+            TaxWarning::Deductions { base, unused } => format!(
+                "Fradragene overstiger {}; {} av fradragene ble ikke brukt",
+                base.label(),
+                NorwegianTaxCalculator::format_nok(*unused)
+            ),
+            TaxWarning::PersonalAllowance { unused } => format!(
+                "Personfradraget overstiger alminnelig inntekt; {} av personfradraget ble ikke brukt",
+                NorwegianTaxCalculator::format_nok(*unused)
+            ),
+            TaxWarning::RiskFreeAllowance { unused } => format!(
+                "Risikofritt fradrag overstiger utbytte og gevinst; {} av fradraget ble ikke brukt",
+                NorwegianTaxCalculator::format_nok(*unused)
+            ),
+            TaxWarning::Donations { cap, unused } => format!(
+                "Gavene overstiger fradragsgrensen på {}; {} av gavene gir ikke fradrag",
+                NorwegianTaxCalculator::format_nok(*cap),
+                NorwegianTaxCalculator::format_nok(*unused)
+            ),
+            TaxWarning::LocalRatesAboveTotal { cuts, excess } => format!(
+                "{}; {} med satsene som oppgitt ble ikke beregnet",
+                cuts.describe(),
+                NorwegianTaxCalculator::format_nok(*excess)
+            ),
+        }
+    }
+
+    // What happens to the unused amount: losses and unused skjerming carry
+    // forward to later years, unused personfradrag is lost
+    pub fn info_item(&self) -> TaxBreakdownItem {
+        let (id, description, amount, rule) = match *self {
+            TaxWarning::Deductions { base: IncomeBase::AlminneligInntekt, unused } => {
+                ("loss-carry-forward", "Underskudd til fremføring", unused, TaxRule::LossCarryForward)
+            }
+            TaxWarning::Deductions { base: IncomeBase::Personinntekt, unused } => {
+                ("negative-personinntekt", "Negativ personinntekt til fremføring", unused, TaxRule::LossCarryForward)
+            }
+            TaxWarning::PersonalAllowance { unused } => {
+                ("unused-personal-allowance", "Ubenyttet personfradrag (faller bort)", unused, TaxRule::PersonalAllowance)
+            }
+            TaxWarning::RiskFreeAllowance { unused } => {
+                ("unused-risk-free-allowance", "Ubenyttet skjerming til fremføring", unused, TaxRule::RiskFreeAllowance)
+            }
+            TaxWarning::Donations { unused, .. } => {
+                ("donations-over-cap", "Gaver over fradragsgrensen (gir ikke fradrag)", unused, TaxRule::Donations)
+            }
+            TaxWarning::LocalRatesAboveTotal { excess, .. } => {
+                ("local-rates-above-total", "Kommune- og fylkesskatt over totalsatsen (ikke beregnet)", excess, TaxRule::MunicipalTax)
+            }
+        };
+        TaxBreakdownItem {
+            id: id.into(),
+            description: description.into(),
+            amount,
+            rate: None,
+            category: BreakdownCategory::Info,
+            rule,
+            is_estimate: false,
+        }
+    }
+}
+
+struct Deduction {
+    id: &'static str,
+    description: &'static str,
+    amount: Money,
+    rule: TaxRule,
+}
+
+struct IncomeBases {
+    personinntekt: Money,
+    alminnelig_inntekt: Money,
+}
+
+// The rates on alminnelig inntekt, in percent
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IncomeTaxRates {
+    pub total: Rate,
+    pub municipal: Rate,
+    pub county: Rate,
+    pub fellesskatt: Rate,
+}
+
+// A local rate as entered and as applied
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct RateCut {
+    pub entered: Rate,
+    pub applied: Rate,
+}
+
+// Local rates that added up to more than the total rate on alminnelig
+// inntekt, cut to fit it: fylkeskatt first, then kommuneskatt
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct LocalRateCuts {
+    pub total: Rate,
+    pub municipal: RateCut,
+    pub county: RateCut,
+}
+
+impl LocalRateCuts {
+    pub fn describe(&self) -> String {
+        let cuts = [("fylkeskatten", self.county), ("kommuneskatten", self.municipal)]
+            .into_iter()
+            .filter(|(_, cut)| cut.applied < cut.entered)
+            .enumerate()
+            .map(|(index, (name, cut))| {
+                let verb = if index == 0 { "satt ned " } else { "" };
+                format!("{} {}fra {:.2} til {:.2}", name, verb, cut.entered, cut.applied)
+            })
+            .collect::<Vec<_>>();
+        format!(
+            "Kommuneskatt og fylkeskatt er til sammen over {:.1}, som er hele skatten på alminnelig inntekt. Derfor er {}",
+            self.total,
+            cuts.join(", og ")
+        )
+    }
+}
+
+// Collects the breakdown, or skips building the items (and formatting their
+// descriptions) when only the totals are wanted
+struct Breakdown {
+    items: Vec<TaxBreakdownItem>,
+    detailed: bool,
+}
+
+impl Breakdown {
+    fn new(detailed: bool) -> Self {
+        Self { items: Vec::new(), detailed }
+    }
+
+    fn push(&mut self, item: impl FnOnce() -> TaxBreakdownItem) {
+        if self.detailed {
+            self.items.push(item());
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RuleInfo {
+    pub explanation: &'static str,
+    pub reference: &'static str,
+}
+
+impl TaxRule {
+    // Bases a deduction under this rule reduces; empty for rules that aren't deductions
+    pub fn deduction_bases(&self) -> &'static [IncomeBase] {
+        match self {
+            TaxRule::BusinessExpenses | TaxRule::EmployerContribution => &[IncomeBase::Personinntekt, IncomeBase::AlminneligInntekt],
+            TaxRule::Deductions
+            | TaxRule::CommuterDeduction
+            | TaxRule::LivingAwayFromHome
+            | TaxRule::Donations
+            | TaxRule::IndividualPensionSaving
+            | TaxRule::LargeSicknessCosts
+            | TaxRule::OccupationDeduction
+            | TaxRule::PersonalAllowance => &[IncomeBase::AlminneligInntekt],
+            _ => &[],
+        }
+    }
+
+    pub fn info(&self) -> RuleInfo {
+        match self {
+            TaxRule::GrossIncome => RuleInfo {
+                explanation: "Samlet lønn, næringsinntekt eller overskudd før fradrag. Beløpet danner grunnlaget for både personinntekt og alminnelig inntekt.",
+                reference: "Skatteloven § 5-1",
+            },
+            TaxRule::Personinntekt => RuleInfo {
+                explanation: "Lønn og personinntekt fra næring uten fradrag. Trinnskatt og trygdeavgift beregnes av dette grunnlaget.",
+                reference: "Skatteloven § 12-2",
+            },
+            TaxRule::AlminneligInntekt => RuleInfo {
+                explanation: "Alle skattepliktige inntekter etter fradrag. Kommune-, fylkes- og fellesskatt på 22 % beregnes av dette grunnlaget etter personfradrag.",
+                reference: "Skatteloven § 5-1 og § 15-2",
+            },
+            TaxRule::PersonalAllowance => RuleInfo {
+                explanation: "Et fast fradrag alle personlige skattytere får i alminnelig inntekt. Det reduserer grunnlaget for kommune- og fylkesskatt, men ikke trinnskatt eller trygdeavgift.",
+                reference: "Skatteloven § 15-4",
+            },
+            TaxRule::Deductions => RuleInfo {
+                explanation: "Fradrag som minstefradrag og rentefradrag reduserer alminnelig inntekt. Beregningen bruker det samlede beløpet du oppgir. Reisefradraget beregnes for seg ut fra avstand og arbeidsdager.",
+                reference: "Skatteloven § 6-1 og § 6-32",
+            },
+            TaxRule::CommuterDeduction => RuleInfo {
+                explanation: "Reiser mellom hjem og fast arbeidssted gir fradrag i alminnelig inntekt. Reisen tur-retur hver arbeidsdag regnes med en fast sats per km, og bare det som overstiger bunnbeløpet trekkes fra, opp til en øvre grense.",
+                reference: "Skatteloven § 6-44",
+            },
+            TaxRule::LivingAwayFromHome => RuleInfo {
+                explanation: "Pendlere som bor borte fra hjemmet på grunn av arbeidet, kan trekke fra losji og kost. Losji trekkes fra med dokumenterte utgifter, eller med en fast sats per døgn uten kvitteringer. Kost trekkes fra med en sats per døgn, som er lavere når boligen har kokemulighet. Besøksreiser hjem inngår i reisefradraget.",
+                reference: "Skatteloven § 6-13",
+            },
+            TaxRule::Donations => RuleInfo {
+                explanation: "Pengegaver til frivillige organisasjoner som er godkjent av Skatteetaten, gir fradrag i alminnelig inntekt. Hver gave må være på minst 500 kroner, og fradraget er begrenset til et fast beløp i året. Gaver over grensen gir ikke fradrag.",
+                reference: "Skatteloven § 6-50",
+            },
+            TaxRule::IndividualPensionSaving => RuleInfo {
+                explanation: "Innskudd på individuell pensjonssparing (IPS) gir fradrag i alminnelig inntekt opp til et fast beløp i året. Sparingen er fri for formueskatt og skatt på avkastning underveis, men utbetalingene skattlegges som alminnelig inntekt når de tas ut, tidligst fra 62 år.",
+                reference: "Skatteloven § 6-47 og forskrift om individuell pensjonsordning",
+            },
+            TaxRule::LargeSicknessCosts => RuleInfo {
+                explanation: "Særfradraget for store sykdomskostnader ble avviklet fra 2012. Etter en overgangsregel beholder de som fikk fradraget for 2010 eller 2011, fradrag for dokumenterte kostnader over et bunnbeløp på 9 180 kroner.",
+                reference: "Skatteloven § 6-83 og overgangsregler",
+            },
+            TaxRule::OccupationDeduction => RuleInfo {
+                explanation: "Sjøfolk på norske skip og fiskere får et særskilt fradrag i alminnelig inntekt på 30 % av inntekten fra arbeidet, opp til et fast beløp i året. Sjømenn må ha vært om bord i minst 130 døgn i året.",
+                reference: "Skatteloven § 6-60 og § 6-61",
+            },
+            TaxRule::BusinessExpenses => RuleInfo {
+                explanation: "Kostnader til å erverve, vedlikeholde eller sikre inntekten i virksomheten kan trekkes fra før overskuddet beskattes.",
+                reference: "Skatteloven § 6-1",
+            },
+            TaxRule::MunicipalTax => RuleInfo {
+                explanation: "Skatt på alminnelig inntekt som tilfaller kommunen. Satsen fastsettes av Stortinget innenfor et maksimalt skattøre.",
+                reference: "Skatteloven § 15-2 og Stortingets skattevedtak",
+            },
+            TaxRule::CountyTax => RuleInfo {
+                explanation: "Skatt på alminnelig inntekt som tilfaller fylkeskommunen. Sammen med kommuneskatt og fellesskatt utgjør den skatten på alminnelig inntekt.",
+                reference: "Skatteloven § 15-2 og Stortingets skattevedtak",
+            },
+            TaxRule::Fellesskatt => RuleInfo {
+                explanation: "Skatten på alminnelig inntekt er 22 % og deles mellom kommune, fylke og staten. Fellesskatten til staten er resten etter kommune- og fylkesskatt. I Finnmark og Nord-Troms er samlet sats 18,5 %.",
+                reference: "Stortingets skattevedtak § 3-2 og § 3-3",
+            },
+            TaxRule::ChurchTax => RuleInfo {
+                explanation: "Norge har ikke egen kirkeskatt; kirken finansieres over offentlige budsjetter, og medlemskap påvirker ikke skatten. Den historiske modellen med kirkeskatt kan slås på for sammenligning og hva-om-beregninger.",
+                reference: "Tidligere skattevedtak for kirkelig fellesråd",
+            },
+            TaxRule::StateTax => RuleInfo {
+                explanation: "Trinnskatt er en progressiv skatt på personinntekt. Hvert trinn har sin sats som bare gjelder inntekten over trinnets innslagspunkt.",
+                reference: "Skatteloven § 15-3",
+            },
+            TaxRule::NationalInsurance => RuleInfo {
+                explanation: "Trygdeavgift finansierer folketrygden og beregnes av personinntekten. Satsen er høyere for næringsinntekt enn for lønn. Under frigrensen betales ingen trygdeavgift, og over den er avgiften aldri mer enn 25 % av inntekten som overstiger grensen.",
+                reference: "Folketrygdloven § 23-3",
+            },
+            TaxRule::CorporateTax => RuleInfo {
+                explanation: "Aksjeselskaper betaler en flat skatt på overskuddet etter fradrag. Utbytte til eierne beskattes separat hos mottakeren.",
+                reference: "Skatteloven § 2-2 og Stortingets skattevedtak",
+            },
+            TaxRule::RiskFreeAllowance => RuleInfo {
+                explanation: "Skjermingsfradraget gir skattefritak for en risikofri avkastning på aksjenes inngangsverdi, slik at bare avkastning utover dette beskattes.",
+                reference: "Skatteloven § 10-12",
+            },
+            TaxRule::ShareholderTax => RuleInfo {
+                explanation: "Utbytte og gevinst på aksjer oppjusteres med en faktor før de beskattes som alminnelig inntekt, som gir en høyere effektiv sats enn 22 %.",
+                reference: "Skatteloven § 10-11 og § 10-31",
+            },
+            TaxRule::ParticipationExemption => RuleInfo {
+                explanation: "Etter fritaksmetoden er utbytte og gevinst på aksjer i selskap skattefritt, bortsett fra at 3 % av utbyttet inntektsføres.",
+                reference: "Skatteloven § 2-38",
+            },
+            TaxRule::WealthTax => RuleInfo {
+                explanation: "Formueskatt beregnes av nettoformue over bunnfradraget. Aksjer verdsettes med rabatt før skatten beregnes.",
+                reference: "Skatteloven § 15-1 og § 4-12",
+            },
+            TaxRule::PartnershipTaxation => RuleInfo {
+                explanation: "Deltakerlignede selskaper er ikke egne skattesubjekter. Overskuddet skattlegges hos den enkelte deltaker.",
+                reference: "Skatteloven § 10-40 flg.",
+            },
+            TaxRule::SoleProprietorship => RuleInfo {
+                explanation: "Overskudd i enkeltpersonforetak skattlegges hos innehaveren som alminnelig inntekt og personinntekt fra næring.",
+                reference: "Skatteloven § 12-10",
+            },
+            TaxRule::PensionAccrual => RuleInfo {
+                explanation: "Hvert år legges 18,1 % av pensjonsgivende inntekt opp til 7,1G til pensjonsbeholdningen i folketrygden. Lavere personinntekt gir derfor også lavere alderspensjon.",
+                reference: "Folketrygdloven § 20-5",
+            },
+            TaxRule::SickPayInsurance => RuleInfo {
+                explanation: "Selvstendig næringsdrivende får 80 % sykepenger fra dag 17. Mot en premie av inntekten opp til 6G kan du forsikre deg for 100 % dekning og/eller sykepenger fra første dag.",
+                reference: "Folketrygdloven § 8-36",
+            },
+            TaxRule::SickPay => RuleInfo {
+                explanation: "Sykepenger fra NAV eller arbeidsgiveren skattlegges som lønn, med trygdeavgift etter satsen for lønn og rett til minstefradrag.",
+                reference: "Skatteloven § 5-10 og folketrygdloven § 23-3",
+            },
+            TaxRule::DisabilityBenefit => RuleInfo {
+                explanation: "Uføretrygd skattlegges som lønn og gir minstefradrag og trinnskatt på vanlig måte, men trygdeavgiften er den lave satsen for pensjoner.",
+                reference: "Skatteloven § 12-2 og folketrygdloven § 23-3",
+            },
+            TaxRule::LossCarryForward => RuleInfo {
+                explanation: "Underskudd i alminnelig inntekt kan føres til fradrag i senere års inntekt. Negativ personinntekt fra næring kan fremføres mot senere personinntekt fra samme virksomhet. Ubenyttet personfradrag faller bort.",
+                reference: "Skatteloven § 14-6 og § 12-13",
+            },
+            TaxRule::ForeignBranch => RuleInfo {
+                explanation: "Et norskregistrert utenlandsk foretak (NUF) er en norsk filial av et utenlandsk selskap. Overskudd fra virksomheten i Norge skattlegges med 22 % her, og overføring av overskudd til hovedforetaket utløser ikke kildeskatt.",
+                reference: "Skatteloven § 2-3 første ledd bokstav b",
+            },
+            TaxRule::EmployerContribution => RuleInfo {
+                explanation: "Arbeidsgiveren betaler arbeidsgiveravgift av lønn til ansatte i Norge, også når arbeidsgiveren er utenlandsk. Beregningen bruker satsen i sone 1, og avgiften trekkes fra i overskuddet sammen med lønnen.",
+                reference: "Folketrygdloven § 23-2",
+            },
+            TaxRule::Nonprofit => RuleInfo {
+                explanation: "Ideelle organisasjoner og stiftelser er skattefrie, også for formue og avkastning på den. Økonomisk virksomhet blir skattepliktig når omsetningen overstiger 140 000 kr for veldedige og allmennyttige formål og 70 000 kr for andre, og da skattlegges hele overskuddet med 22 %. Gaver, tilskudd og medlemskontingent regnes ikke som omsetning.",
+                reference: "Skatteloven § 2-32",
+            },
+            TaxRule::ResourceRentTax => RuleInfo {
+                explanation: "Selskaper med havbruk eller vannkraft betaler grunnrenteskatt i tillegg til selskapsskatten, med 25 % for havbruk og 45 % for vannkraft. Grunnlaget er inntekten fra virksomheten med markedsverdi, minus kostnadene knyttet til den og beregnet selskapsskatt på grunnrenteinntekten. Havbruk har et bunnfradrag på 70 mill. kroner. Negativ grunnrenteinntekt fremføres for havbruk og utbetales med skatteverdien for vannkraft, noe som ikke er med i beregningen.",
+                reference: "Skatteloven kapittel 18 og 18A",
+            },
+            TaxRule::PetroleumTax => RuleInfo {
+                explanation: "Utvinning av petroleum på sokkelen betales det særskatt for i tillegg til selskapsskatten. Særskatten er 71,8 % av overskuddet etter fradrag for selskapsskatten, slik at marginalskatten blir 78 %. Friinntekt på 12,4 % av årets investeringer trekkes bare fra i særskattegrunnlaget. Beregningen er forenklet og ment for sammenligning.",
+                reference: "Petroleumsskatteloven § 5",
+            },
+            TaxRule::PropertyTax => RuleInfo {
+                explanation: "Kommunen kan skrive ut eiendomsskatt på boliger med 1 til 4 promille av eiendomsskattegrunnlaget. For boliger reduseres grunnlaget med 30 %, og mange kommuner gir i tillegg et bunnfradrag. Skatten er ikke en inntektsskatt og betales til kommunen, så den tas bare med i samlet skatt når du velger det.",
+                reference: "Eigedomsskattelova § 8 C-1 og § 11",
+            },
+            TaxRule::CustomTax => RuleInfo {
+                explanation: "En post du har lagt til selv, som et fast beløp eller en sats av et valgt grunnlag. Den inngår i samlet skatt, men er ikke en del av gjeldende regler. Negative beløp virker som fradrag i skatten.",
+                reference: "Egendefinert",
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateTaxBracket {
+    pub step: usize,
+    pub threshold: Money,
+    pub upper: Option<Money>,
+    pub rate: Rate,
+    pub income_in_bracket: Money,
+    pub tax: Money,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TaxYear {
+    #[serde(rename = "2023")]
+    Y2023,
+    #[default]
+    #[serde(rename = "2024")]
+    Y2024,
+    #[serde(rename = "2025")]
+    Y2025,
+}
+
+impl TaxYear {
+    pub const ALL: [TaxYear; 3] = [TaxYear::Y2023, TaxYear::Y2024, TaxYear::Y2025];
+
+    pub fn year(&self) -> u16 {
+        match self {
+            TaxYear::Y2023 => 2023,
+            TaxYear::Y2024 => 2024,
+            TaxYear::Y2025 => 2025,
+        }
+    }
+
+    pub fn from_year(year: u16) -> Option<TaxYear> {
+        Self::ALL.into_iter().find(|tax_year| tax_year.year() == year)
+    }
+
+    // None for the first year the calculator has rules for
+    pub fn previous(&self) -> Option<TaxYear> {
+        Self::from_year(self.year() - 1)
+    }
+
+    pub fn rules(&self) -> &'static TaxRules {
+        match self {
+            TaxYear::Y2023 => &RULES_2023,
+            TaxYear::Y2024 => &RULES_2024,
+            TaxYear::Y2025 => &RULES_2025,
+        }
+    }
+}
+
+// Folketrygdens grunnbeløp. Limits defined in multiples of G are kept as
+// multiples, so a new year only needs the new amount.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Grunnbeloep(pub Money);
+
+impl Grunnbeloep {
+    pub fn amount(&self) -> Money {
+        self.0
+    }
+
+    pub fn times(&self, multiple: f64) -> Money {
+        self.0 * multiple
+    }
+}
+
+// Sick pay and the premiums for extra sick pay cover apply up to 6G
+pub const SICK_PAY_CAP_G: f64 = 6.0;
+
+// Personinntekt above 7.1G gives no pension accrual in folketrygden
+pub const PENSION_ACCRUAL_CAP_G: f64 = 7.1;
+pub const PENSION_ACCRUAL_RATE: Rate = Rate::from_percent(18.1);
+// Salary band for obligatorisk tjenestepensjon
+pub const OTP_LOWER_G: f64 = 1.0;
+pub const OTP_UPPER_G: f64 = 12.0;
+// Above the lower limit, trygdeavgift is at most this share of the
+// personinntekt over the limit
+pub const NATIONAL_INSURANCE_PHASE_IN_RATE: Rate = Rate::from_percent(25.0);
+// Fritaksmetoden: a company is taxed on 3% of its share income
+pub const PARTICIPATION_EXEMPTION_TAXABLE_SHARE: f64 = 0.03;
+
+// Rates and thresholds for one income year. State tax brackets are
+// (threshold, rate) pairs for trinnskatt. Bump the version's minor number
+// whenever a year's figures are corrected, and describe the change.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaxRules {
+    pub version: &'static str,
+    pub changes: &'static [&'static str],
+    pub personal_allowance: Money,
+    pub corporate_tax_rate: Rate,
+    pub national_insurance_rate: Rate,
+    pub national_insurance_rate_enk: Rate,
+    // The lower rate on pensions and uføretrygd
+    pub national_insurance_rate_pension: Rate,
+    // No trygdeavgift on personinntekt up to this amount
+    pub national_insurance_lower_limit: Money,
+    // Pay up to the limit has no tax withheld on a frikort
+    pub frikort_limit: Money,
+    // Minstefradrag on wages: a share of the pay, up to the maximum
+    pub minstefradrag_rate: Rate,
+    pub minstefradrag_max: Money,
+    // Reisefradrag: the year's travel at a rate per km, less the bunnbeløp,
+    // up to the maximum
+    pub commuter_rate_per_km: Money,
+    pub commuter_threshold: Money,
+    pub commuter_deduction_max: Money,
+    // Pendlerfradrag per night away: undocumented lodging, and board with
+    // and without a kitchen
+    pub commuter_lodging_per_night: Money,
+    pub commuter_board_with_kitchen: Money,
+    pub commuter_board_without_kitchen: Money,
+    // Gifts to approved voluntary organisations are deductible up to this
+    pub donation_deduction_max: Money,
+    // Yearly deduction for individuell pensjonssparing
+    pub ips_deduction_max: Money,
+    // Særfradrag for store sykdomskostnader covers costs above this
+    pub sickness_costs_threshold: Money,
+    // Sjømannsfradrag and fiskerfradrag: a share of the income, up to the maximum
+    pub occupation_deduction_rate: Rate,
+    pub occupation_deduction_max: Money,
+    pub investment_tax_rate: Rate,
+    // Municipal, county and fellesskatt together, with the lower rate in tiltakssonen
+    pub ordinary_income_rate: Rate,
+    pub ordinary_income_rate_tiltakssone: Rate,
+    pub wealth_tax_rate: Rate,
+    // The municipal share of the wealth tax rate, the most a municipality
+    // may levy; the state takes the rest
+    pub municipal_wealth_tax_rate: Rate,
+    pub wealth_tax_threshold: Money,
+    pub risk_free_rate: Rate,
+    pub state_tax_brackets: Cow<'static, [(Money, Rate)]>,
+    // Extra arbeidsgiveravgift on each employee's salary above the threshold
+    pub employer_surcharge_threshold: Money,
+    pub employer_surcharge_rate: Rate,
+    // Revenue from a nonprofit's economic activity that stays tax-free, for
+    // charitable purposes and for other nonprofits
+    pub nonprofit_revenue_limit_charitable: Money,
+    pub nonprofit_revenue_limit: Money,
+    // Grunnbeløp from May of the income year
+    pub grunnbeloep: Grunnbeloep,
+}
+
+impl TaxRules {
+    // Shares are taxed at the ordinary rate on an adjusted-up amount
+    pub fn dividend_factor(&self) -> f64 {
+        self.investment_tax_rate / self.ordinary_income_rate
+    }
+
+    pub fn minstefradrag(&self, wages: Money) -> Money {
+        (wages * self.minstefradrag_rate).clamp(Money::ZERO, self.minstefradrag_max)
+    }
+}
+
+const RULES_2023: TaxRules = TaxRules {
+    version: "rules-2023.2",
+    changes: &[
+        "Første versjon av regelverket for 2023",
+        "Trygdeavgift beregnes ikke under frigrensen på 69 650 NOK og trappes opp med 25 % av inntekten over",
+    ],
+    personal_allowance: Money(79_600.0),
+    corporate_tax_rate: Rate::from_percent(22.0),
+    national_insurance_rate: Rate::from_percent(7.9),
+    national_insurance_rate_enk: Rate::from_percent(11.0),
+    national_insurance_rate_pension: Rate::from_percent(5.1),
+    national_insurance_lower_limit: Money(69_650.0),
+    frikort_limit: Money(70_000.0),
+    minstefradrag_rate: Rate::from_percent(45.0),
+    minstefradrag_max: Money(104_450.0),
+    commuter_rate_per_km: Money(1.70),
+    commuter_threshold: Money(14_400.0),
+    commuter_deduction_max: Money(97_000.0),
+    commuter_lodging_per_night: Money(104.0),
+    commuter_board_with_kitchen: Money(99.0),
+    commuter_board_without_kitchen: Money(198.0),
+    donation_deduction_max: Money(25_000.0),
+    ips_deduction_max: Money(15_000.0),
+    sickness_costs_threshold: Money(9_180.0),
+    occupation_deduction_rate: Rate::from_percent(30.0),
+    occupation_deduction_max: Money(150_000.0),
+    investment_tax_rate: Rate::from_percent(37.84),
+    ordinary_income_rate: Rate::from_percent(22.0),
+    ordinary_income_rate_tiltakssone: Rate::from_percent(18.5),
+    wealth_tax_rate: Rate::from_percent(1.0),
+    municipal_wealth_tax_rate: Rate::from_percent(0.7),
+    wealth_tax_threshold: Money(1_700_000.0),
+    risk_free_rate: Rate::from_percent(1.72),
+    state_tax_brackets: Cow::Borrowed(&[
+        (Money(198_350.0), Rate::from_percent(1.7)),
+        (Money(279_150.0), Rate::from_percent(4.0)),
+        (Money(642_950.0), Rate::from_percent(13.5)),
+        (Money(926_800.0), Rate::from_percent(16.5)),
+        (Money(1_500_000.0), Rate::from_percent(17.5)),
+    ]),
+    employer_surcharge_threshold: Money(750_000.0),
+    employer_surcharge_rate: Rate::from_percent(5.0),
+    nonprofit_revenue_limit_charitable: Money(140_000.0),
+    nonprofit_revenue_limit: Money(70_000.0),
+    grunnbeloep: Grunnbeloep(Money(118_620.0)),
+};
+
+// 2024 Norwegian Tax Rates and Constants
+const RULES_2024: TaxRules = TaxRules {
+    version: "rules-2024.2",
+    changes: &[
+        "Trinnskattgrenser oppjustert og satsene i trinn 3-5 økt med 0,1 prosentpoeng",
+        "Trygdeavgift redusert til 7,7 % (lønn) og 10,9 % (næring)",
+        "Innslagspunkt for formueskatt hevet til 2 000 000 NOK",
+        "Trygdeavgift beregnes ikke under frigrensen på 69 650 NOK og trappes opp med 25 % av inntekten over",
+    ],
+    personal_allowance: Money(69_100.0),
+    corporate_tax_rate: Rate::from_percent(22.0),
+    national_insurance_rate: Rate::from_percent(7.7), // Employees
+    national_insurance_rate_enk: Rate::from_percent(10.9), // Sole proprietors
+    national_insurance_rate_pension: Rate::from_percent(5.1),
+    national_insurance_lower_limit: Money(69_650.0),
+    frikort_limit: Money(100_000.0),
+    minstefradrag_rate: Rate::from_percent(46.0),
+    minstefradrag_max: Money(92_000.0),
+    commuter_rate_per_km: Money(1.83),
+    commuter_threshold: Money(14_950.0),
+    commuter_deduction_max: Money(100_000.0),
+    commuter_lodging_per_night: Money(104.0),
+    commuter_board_with_kitchen: Money(107.0),
+    commuter_board_without_kitchen: Money(214.0),
+    donation_deduction_max: Money(25_000.0),
+    ips_deduction_max: Money(15_000.0),
+    sickness_costs_threshold: Money(9_180.0),
+    occupation_deduction_rate: Rate::from_percent(30.0),
+    occupation_deduction_max: Money(150_000.0),
+    investment_tax_rate: Rate::from_percent(37.84), // Effective rate on investments
+    ordinary_income_rate: Rate::from_percent(22.0),
+    ordinary_income_rate_tiltakssone: Rate::from_percent(18.5),
+    wealth_tax_rate: Rate::from_percent(1.0),
+    municipal_wealth_tax_rate: Rate::from_percent(0.7),
+    wealth_tax_threshold: Money(2_000_000.0),
+    risk_free_rate: Rate::from_percent(1.72),
+    state_tax_brackets: Cow::Borrowed(&[
+        (Money(208_050.0), Rate::from_percent(1.7)),
+        (Money(292_850.0), Rate::from_percent(4.0)),
+        (Money(670_000.0), Rate::from_percent(13.6)),
+        (Money(937_900.0), Rate::from_percent(16.6)),
+        (Money(1_350_000.0), Rate::from_percent(17.6)),
+    ]),
+    employer_surcharge_threshold: Money(750_000.0),
+    employer_surcharge_rate: Rate::from_percent(5.0),
+    nonprofit_revenue_limit_charitable: Money(140_000.0),
+    nonprofit_revenue_limit: Money(70_000.0),
+    grunnbeloep: Grunnbeloep(Money(124_028.0)),
+};
+
+const RULES_2025: TaxRules = TaxRules {
+    version: "rules-2025.2",
+    changes: &[
+        "Personfradrag økt til 108 550 NOK",
+        "Trinnskattgrenser oppjustert og satsene i trinn 3-5 økt med 0,1 prosentpoeng",
+        "Trygdeavgift for næring redusert til 10,8 %",
+        "Innslagspunkt for formueskatt satt til 1 760 000 NOK",
+        "Ekstra arbeidsgiveravgift på lønn over 750 000 NOK avviklet",
+        "Trygdeavgift beregnes ikke under frigrensen på 99 650 NOK og trappes opp med 25 % av inntekten over",
+    ],
+    personal_allowance: Money(108_550.0),
+    corporate_tax_rate: Rate::from_percent(22.0),
+    national_insurance_rate: Rate::from_percent(7.7),
+    national_insurance_rate_enk: Rate::from_percent(10.8),
+    national_insurance_rate_pension: Rate::from_percent(5.1),
+    national_insurance_lower_limit: Money(99_650.0),
+    frikort_limit: Money(100_000.0),
+    minstefradrag_rate: Rate::from_percent(46.0),
+    minstefradrag_max: Money(92_000.0),
+    commuter_rate_per_km: Money(1.83),
+    commuter_threshold: Money(15_250.0),
+    commuter_deduction_max: Money(100_000.0),
+    commuter_lodging_per_night: Money(104.0),
+    commuter_board_with_kitchen: Money(111.0),
+    commuter_board_without_kitchen: Money(222.0),
+    donation_deduction_max: Money(25_000.0),
+    ips_deduction_max: Money(15_000.0),
+    sickness_costs_threshold: Money(9_180.0),
+    occupation_deduction_rate: Rate::from_percent(30.0),
+    occupation_deduction_max: Money(150_000.0),
+    investment_tax_rate: Rate::from_percent(37.84),
+    ordinary_income_rate: Rate::from_percent(22.0),
+    ordinary_income_rate_tiltakssone: Rate::from_percent(18.5),
+    wealth_tax_rate: Rate::from_percent(1.0),
+    municipal_wealth_tax_rate: Rate::from_percent(0.525),
+    wealth_tax_threshold: Money(1_760_000.0),
+    risk_free_rate: Rate::from_percent(1.72),
+    state_tax_brackets: Cow::Borrowed(&[
+        (Money(217_400.0), Rate::from_percent(1.7)),
+        (Money(306_050.0), Rate::from_percent(4.0)),
+        (Money(697_150.0), Rate::from_percent(13.7)),
+        (Money(942_400.0), Rate::from_percent(16.7)),
+        (Money(1_410_750.0), Rate::from_percent(17.7)),
+    ]),
+    employer_surcharge_threshold: Money(750_000.0),
+    employer_surcharge_rate: Rate::from_percent(0.0),
+    nonprofit_revenue_limit_charitable: Money(140_000.0),
+    nonprofit_revenue_limit: Money(70_000.0),
+    grunnbeloep: Grunnbeloep(Money(130_160.0)),
+};
+
+// Upper bound for the solver; targets that need more are reported as unreachable
+const SOLVER_MAX_GROSS: Money = Money(100_000_000.0);
+const SOLVER_TOLERANCE: Money = Money(1.0);
+
+/// Inverts a net income function by bisection: a gross income in whole
+/// kroner whose net income reaches `target_net`, at most two kroner above the
+/// least that does, or `None` when that takes more than 100 million NOK. The function must rise with gross income,
+/// as net income does in every entity model, since no marginal rate reaches
+/// 100%.
+///
+/// ```
+/// use norwegian_tax_calculator::money::Money;
+/// use norwegian_tax_calculator::tax_calculator::solve_gross_for_net;
+///
+/// // A flat 25% tax
+/// let gross = solve_gross_for_net(Money(75_000.0), |gross| gross * 0.75).unwrap();
+/// assert!(gross >= Money(100_000.0) && gross <= Money(100_002.0));
+/// ```
+pub fn solve_gross_for_net(target_net: Money, net_income: impl Fn(Money) -> Money) -> Option<Money> {
+    if target_net <= net_income(Money::ZERO) {
+        return Some(Money::ZERO);
+    }
+    if net_income(SOLVER_MAX_GROSS) < target_net {
+        return None;
+    }
+
+    let (mut low, mut high) = (Money::ZERO, SOLVER_MAX_GROSS);
+    while high - low > SOLVER_TOLERANCE {
+        let mid = (low + high) / 2.0;
+        if net_income(mid) < target_net {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some(Money(high.amount().ceil()))
+}
+
+/// The entry points of the engine. Every calculation is a pure function of
+/// the input.
+pub struct NorwegianTaxCalculator;
+
+impl NorwegianTaxCalculator {
+    /// The full calculation, with the breakdown, warnings and assumptions.
+    /// The breakdown is grouped by [`BreakdownCategory`], in calculation order
+    /// within each group, and every item has an id that is unique within the
+    /// result.
+    ///
+    /// ```
+    /// use norwegian_tax_calculator::money::Money;
+    /// use norwegian_tax_calculator::tax_calculator::{EntityType, NorwegianTaxCalculator, TaxCalculationInput, TaxYear};
+    ///
+    /// let input = TaxCalculationInput::new(EntityType::Individual, Money(750_000.0))
+    ///     .with_tax_year(TaxYear::Y2024)
+    ///     .with_deductions(Money(40_000.0));
+    /// let result = NorwegianTaxCalculator::calculate_tax(&input);
+    ///
+    /// assert_eq!(result.alminnelig_inntekt, Money(710_000.0));
+    /// assert_eq!(result.net_income, result.gross_income - result.total_tax);
+    /// let municipal = result.breakdown.iter().find(|item| item.id == "municipal-tax").unwrap();
+    /// assert_eq!(municipal.amount, result.municipal_tax);
+    /// ```
     pub fn calculate_tax(input: &TaxCalculationInput) -> TaxCalculationResult {
-        match input.entity_type {
-            EntityType::Individual => Self::calculate_individual_tax(input),
-            EntityType::Corporation => Self::calculate_corporate_tax(input),
-            EntityType::Partnership => Self::calculate_partnership_tax(input),
-            EntityType::SoleProprietorship => Self::calculate_enk_tax(input),
+        let mut result = Self::calculate(input, true);
+        // Grouped by category in the order of BreakdownCategory, which is how
+        // the results and the report list them. The sort is stable, so items
+        // keep the order of the calculation within a category.
+        result.breakdown.sort_by_key(|item| item.category);
+        result.assumptions = Self::assumptions(input);
+        result
+    }
+
+    /// The same amounts as [`calculate_tax`](Self::calculate_tax) with an
+    /// empty breakdown and no assumptions, for charts and solvers that
+    /// calculate many times and only read the totals.
+    ///
+    /// ```
+    /// use norwegian_tax_calculator::money::Money;
+    /// use norwegian_tax_calculator::tax_calculator::{EntityType, NorwegianTaxCalculator, TaxCalculationInput};
+    ///
+    /// let input = TaxCalculationInput::new(EntityType::Corporation, Money(2_000_000.0));
+    /// let totals = NorwegianTaxCalculator::calculate_totals_only(&input);
+    /// assert!(totals.breakdown.is_empty());
+    /// assert_eq!(totals.total_tax, NorwegianTaxCalculator::calculate_tax(&input).total_tax);
+    /// ```
+    pub fn calculate_totals_only(input: &TaxCalculationInput) -> TaxCalculationResult {
+        Self::calculate(input, false)
+    }
+
+    fn calculate(input: &TaxCalculationInput, detailed: bool) -> TaxCalculationResult {
+        let mut result = match input.entity_type {
+            EntityType::Individual => Self::calculate_individual_tax(input, detailed),
+            EntityType::Corporation => Self::calculate_corporate_tax(input, detailed),
+            EntityType::Partnership => Self::calculate_partnership_tax(input, detailed),
+            EntityType::SoleProprietorship => Self::calculate_enk_tax(input, detailed),
+            EntityType::ForeignBranch => Self::calculate_foreign_branch_tax(input, detailed),
+            EntityType::Nonprofit => Self::calculate_nonprofit_tax(input, detailed),
+        };
+        apply_custom_taxes(&mut result, &input.custom_taxes, detailed);
+        if !input.entity_type.is_company() {
+            apply_property_tax(&mut result, &input.property_tax, detailed);
+        }
+        result.wage_effective_tax_rate = result.effective_tax_rate_excluding(true, true);
+        result
+    }
+
+    fn assumptions(input: &TaxCalculationInput) -> Vec<String> {
+        let mut assumptions = vec![format!(
+            "Skatteår {} med regelverk {}",
+            input.tax_year.year(),
+            Self::ruleset_version(input.tax_year)
+        )];
+
+        let personal = !input.entity_type.is_company();
+        if personal {
+            assumptions.push(match &input.municipality {
+                Some(number) => format!("Kommuneskatt {:.2} % for kommune {}", input.municipal_tax_rate.percent(), number),
+                None => format!("Kommuneskatt {:.2} %, ikke knyttet til en bestemt kommune", input.municipal_tax_rate.percent()),
+            });
+            assumptions.push("Minstefradrag er ikke trukket automatisk, men må tas med i fradragene".to_string());
+        }
+
+        assumptions.push(
+            match input.entity_type {
+                EntityType::Individual => "Hele bruttoinntekten regnes som lønn",
+                EntityType::Partnership => "Overskuddet skattlegges som lønn hos én deltaker",
+                EntityType::SoleProprietorship => "Skjermingsfradrag for kapitalen i foretaket er ikke trukket fra personinntekten",
+                EntityType::Corporation | EntityType::ForeignBranch => {
+                    "Utbytte og gevinster gjelder aksjer som omfattes av fritaksmetoden"
+                }
+                EntityType::Nonprofit => "Bruttoinntekten er bare omsetning fra økonomisk virksomhet",
+            }
+            .to_string(),
+        );
+
+        if input.policy.is_some() {
+            assumptions.push("Beregnet med endrede satser fra hva hvis-modus, ikke gjeldende regler".to_string());
+        }
+
+        if !input.custom_taxes.is_empty() {
+            assumptions.push(format!(
+                "Inkluderer {} egendefinerte poster som ikke følger av gjeldende regler",
+                input.custom_taxes.len()
+            ));
+        }
+
+        if personal && input.investment_wealth > Money::ZERO {
+            assumptions.push("Formuen består bare av aksjer, som også er skjermingsgrunnlaget".to_string());
+            if let Some(rate) = input.municipal_wealth_tax_rate {
+                assumptions.push(format!("Kommunal formueskatt {:.3} % i stedet for maksimalsatsen", rate.percent()));
+            }
+        }
+
+        if personal && input.property_tax.include {
+            assumptions.push(format!(
+                "Samlet skatt inkluderer anslått eiendomsskatt på boligen med {:.1} promille",
+                input.property_tax.rate.per_mille()
+            ));
+        }
+
+        assumptions
+    }
+
+    pub fn ruleset_version(tax_year: TaxYear) -> &'static str {
+        tax_year.rules().version
+    }
+
+    /// One full result per input, in the same order.
+    ///
+    /// ```
+    /// use norwegian_tax_calculator::money::Money;
+    /// use norwegian_tax_calculator::tax_calculator::{EntityType, NorwegianTaxCalculator, TaxCalculationInput};
+    ///
+    /// let inputs = [400_000.0, 800_000.0, 1_600_000.0]
+    ///     .map(|gross| TaxCalculationInput::new(EntityType::Individual, Money(gross)));
+    /// let results = NorwegianTaxCalculator::calculate_many(&inputs);
+    ///
+    /// assert_eq!(results.len(), 3);
+    /// assert!(results.windows(2).all(|pair| pair[0].effective_tax_rate < pair[1].effective_tax_rate));
+    /// ```
+    pub fn calculate_many(inputs: &[TaxCalculationInput]) -> Vec<TaxCalculationResult> {
+        inputs.iter().map(Self::calculate_tax).collect()
+    }
+
+    /// Like [`calculate_many`](Self::calculate_many), but through
+    /// [`calculate_totals_only`](Self::calculate_totals_only), for batches
+    /// where only the totals are read.
+    ///
+    /// ```
+    /// use norwegian_tax_calculator::money::Money;
+    /// use norwegian_tax_calculator::tax_calculator::{EntityType, NorwegianTaxCalculator, TaxCalculationInput};
+    ///
+    /// let inputs = [400_000.0, 800_000.0].map(|gross| TaxCalculationInput::new(EntityType::Individual, Money(gross)));
+    /// let totals = NorwegianTaxCalculator::calculate_many_totals(&inputs);
+    ///
+    /// assert!(totals.iter().all(|result| result.breakdown.is_empty()));
+    /// assert_eq!(totals[1].total_tax, NorwegianTaxCalculator::calculate_many(&inputs)[1].total_tax);
+    /// ```
+    pub fn calculate_many_totals(inputs: &[TaxCalculationInput]) -> Vec<TaxCalculationResult> {
+        inputs.iter().map(Self::calculate_totals_only).collect()
+    }
+
+    /// The gross income that gives `target_net` in net income, with the rest
+    /// of the input as it is, or `None` when it would take more than 100
+    /// million NOK. The result's net income is before business expenses, so
+    /// an ENK target is the owner's income before the costs are paid.
+    ///
+    /// ```
+    /// use norwegian_tax_calculator::money::Money;
+    /// use norwegian_tax_calculator::tax_calculator::{EntityType, NorwegianTaxCalculator, TaxCalculationInput};
+    ///
+    /// let input = TaxCalculationInput::new(EntityType::Individual, Money::ZERO);
+    /// let gross = NorwegianTaxCalculator::gross_for_net(&input, Money(500_000.0)).unwrap();
+    ///
+    /// let net = NorwegianTaxCalculator::calculate_totals_only(&TaxCalculationInput { gross_income: gross, ..input }).net_income;
+    /// assert!(net >= Money(500_000.0) && net < Money(500_002.0));
+    /// ```
+    pub fn gross_for_net(input: &TaxCalculationInput, target_net: Money) -> Option<Money> {
+        solve_gross_for_net(target_net, |gross_income| {
+            Self::calculate_totals_only(&TaxCalculationInput { gross_income, ..input.clone() }).net_income
+        })
+    }
+
+    // Subtracts each deduction from the bases its rule applies to
+    fn apply_deductions(
+        gross_income: Money,
+        deductions: &[Deduction],
+        breakdown: &mut Breakdown,
+        warnings: &mut Vec<TaxWarning>,
+    ) -> IncomeBases {
+        let mut bases = IncomeBases {
+            personinntekt: gross_income,
+            alminnelig_inntekt: gross_income,
+        };
+        let mut deducted = IncomeBases {
+            personinntekt: Money::ZERO,
+            alminnelig_inntekt: Money::ZERO,
+        };
+
+        for deduction in deductions.iter().filter(|deduction| deduction.amount > Money::ZERO) {
+            for base in deduction.rule.deduction_bases() {
+                match base {
+                    IncomeBase::Personinntekt => {
+                        bases.personinntekt -= deduction.amount;
+                        deducted.personinntekt += deduction.amount;
+                    }
+                    IncomeBase::AlminneligInntekt => {
+                        bases.alminnelig_inntekt -= deduction.amount;
+                        deducted.alminnelig_inntekt += deduction.amount;
+                    }
+                }
+            }
+            breakdown.push(|| TaxBreakdownItem {
+                id: deduction.id.into(),
+                description: deduction.description.into(),
+                amount: -deduction.amount,
+                rate: None,
+                category: BreakdownCategory::Deductions,
+                rule: deduction.rule,
+                is_estimate: false,
+            });
+        }
+
+        // Only the part of a negative base that deductions account for is
+        // reported; a negative gross income isn't a deduction problem
+        for (base, remaining, deducted) in [
+            (IncomeBase::Personinntekt, bases.personinntekt, deducted.personinntekt),
+            (IncomeBase::AlminneligInntekt, bases.alminnelig_inntekt, deducted.alminnelig_inntekt),
+        ] {
+            let unused = (-remaining).min(deducted);
+            if unused > Money::ZERO {
+                warnings.push(TaxWarning::Deductions { base, unused });
+            }
+        }
+
+        IncomeBases {
+            personinntekt: bases.personinntekt.max(Money::ZERO),
+            alminnelig_inntekt: bases.alminnelig_inntekt.max(Money::ZERO),
         }
     }
 
-    fn calculate_individual_tax(input: &TaxCalculationInput) -> TaxCalculationResult {
-        let mut breakdown = Vec::new();
+    /// How the tax on alminnelig inntekt is split between the municipality,
+    /// the county and the state. Fellesskatt is whatever the local rates
+    /// leave of the year's total rate, so the total does not depend on the
+    /// municipal rate. Local rates above the total are capped to it.
+    pub fn income_tax_rates(input: &TaxCalculationInput) -> IncomeTaxRates {
+        let rules = input.rules();
+        let total = if input.tiltakssone {
+            rules.ordinary_income_rate_tiltakssone
+        } else {
+            rules.ordinary_income_rate
+        };
+        let municipal = input.municipal_tax_rate.clamp(Rate::ZERO, total);
+        let county = input.county_tax_rate.clamp(Rate::ZERO, total - municipal);
+        IncomeTaxRates {
+            total,
+            municipal,
+            county,
+            fellesskatt: total - municipal - county,
+        }
+    }
+
+    pub fn fellesskatt_rate(input: &TaxCalculationInput) -> Rate {
+        Self::income_tax_rates(input).fellesskatt
+    }
+
+    pub fn local_rate_cuts(input: &TaxCalculationInput) -> Option<LocalRateCuts> {
+        let rates = Self::income_tax_rates(input);
+        (input.municipal_tax_rate.max(Rate::ZERO) + input.county_tax_rate.max(Rate::ZERO) > rates.total).then_some(LocalRateCuts {
+            total: rates.total,
+            municipal: RateCut { entered: input.municipal_tax_rate, applied: rates.municipal },
+            county: RateCut { entered: input.county_tax_rate, applied: rates.county },
+        })
+    }
+
+    // Raised when the local rates had to be capped, with the tax the
+    // uncapped rates would have added
+    fn local_rates_warning(input: &TaxCalculationInput, taxable_income: Money) -> Option<TaxWarning> {
+        Self::local_rate_cuts(input).map(|cuts| {
+            let excess = cuts.municipal.entered.max(Rate::ZERO) + cuts.county.entered.max(Rate::ZERO) - cuts.total;
+            TaxWarning::LocalRatesAboveTotal { cuts, excess: taxable_income * excess }
+        })
+    }
+
+    pub fn pension_accrual(tax_year: TaxYear, pensionable_income: Money) -> Money {
+        let cap = tax_year.rules().grunnbeloep.times(PENSION_ACCRUAL_CAP_G);
+        pensionable_income.clamp(Money::ZERO, cap) * PENSION_ACCRUAL_RATE
+    }
+
+    // The deductions of individuals, partners and sole proprietors, after
+    // any business expenses
+    fn personal_deductions(input: &TaxCalculationInput) -> Vec<Deduction> {
+        let rules = input.rules();
+        let special = &input.special_deductions;
+        // Fiskerfradrag for a sole proprietor is on the profit from fishing
+        let occupation_income = if input.entity_type == EntityType::SoleProprietorship {
+            input.gross_income - input.business_expenses
+        } else {
+            input.gross_income
+        };
+        vec![
+            Deduction { id: "deductions", description: "Fradrag", amount: input.allowable_deductions, rule: TaxRule::Deductions },
+            Deduction { id: "commuter", description: "Reisefradrag", amount: input.commute.deduction(&rules), rule: TaxRule::CommuterDeduction },
+            Deduction { id: "commuter-lodging", description: "Losji som pendler", amount: input.commute.lodging_deduction(&rules), rule: TaxRule::LivingAwayFromHome },
+            Deduction { id: "commuter-board", description: "Kost som pendler", amount: input.commute.board_deduction(&rules), rule: TaxRule::LivingAwayFromHome },
+            Deduction { id: "donations", description: "Gaver til frivillige organisasjoner", amount: input.donations.min(rules.donation_deduction_max), rule: TaxRule::Donations },
+            Deduction { id: "ips", description: "Individuell pensjonssparing (IPS)", amount: input.ips_contribution.min(rules.ips_deduction_max), rule: TaxRule::IndividualPensionSaving },
+            Deduction {
+                id: "large-sickness-costs",
+                description: "Særfradrag for store sykdomskostnader",
+                amount: special.sickness_costs_deduction(&rules),
+                rule: TaxRule::LargeSicknessCosts,
+            },
+            Deduction {
+                id: "occupation",
+                description: special.occupation.deduction_name(),
+                amount: special.occupation_deduction(&rules, occupation_income),
+                rule: TaxRule::OccupationDeduction,
+            },
+        ]
+    }
+
+    // Gifts above the cap are left out of the deductions
+    fn donation_warning(input: &TaxCalculationInput) -> Option<TaxWarning> {
+        let cap = input.rules().donation_deduction_max;
+        (input.donations > cap).then(|| TaxWarning::Donations { cap, unused: input.donations - cap })
+    }
+
+    pub fn personal_deductions_total(input: &TaxCalculationInput) -> Money {
+        Self::personal_deductions(input).iter().map(|deduction| deduction.amount.max(Money::ZERO)).sum()
+    }
+
+    fn calculate_individual_tax(input: &TaxCalculationInput, detailed: bool) -> TaxCalculationResult {
+        let rules = input.rules();
+        let mut breakdown = Breakdown::new(detailed);
+        let mut warnings = Vec::new();
         
-        let personal_allowance = Self::PERSONAL_ALLOWANCE_2024;
-        let taxable_income = (input.gross_income - personal_allowance - input.allowable_deductions).max(0.0);
+        let personal_allowance = rules.personal_allowance;
         
-        breakdown.push(TaxBreakdownItem {
-            description: "Personfradrag".to_string(),
+        breakdown.push(|| TaxBreakdownItem {
+            id: "personal-allowance".into(),
+            description: "Personfradrag".into(),
             amount: -personal_allowance,
             rate: None,
+            category: BreakdownCategory::Deductions,
+            rule: TaxRule::PersonalAllowance,
+            is_estimate: false,
         });
 
-        if input.allowable_deductions > 0.0 {
-            breakdown.push(TaxBreakdownItem {
-                description: "Fradrag".to_string(),
-                amount: -input.allowable_deductions,
-                rate: None,
-            });
+        let IncomeBases { personinntekt, alminnelig_inntekt } = Self::apply_deductions(
+            input.gross_income + input.sick_pay + input.disability_benefit,
+            &Self::personal_deductions(input),
+            &mut breakdown,
+            &mut warnings,
+        );
+        warnings.extend(Self::donation_warning(input));
+        let unused_allowance = (personal_allowance - alminnelig_inntekt).min(personal_allowance);
+        if unused_allowance > Money::ZERO {
+            warnings.push(TaxWarning::PersonalAllowance { unused: unused_allowance });
         }
+        let taxable_income = (alminnelig_inntekt - personal_allowance).max(Money::ZERO);
+
+        let rates = Self::income_tax_rates(input);
+        warnings.extend(Self::local_rates_warning(input, taxable_income));
 
-        let municipal_tax = taxable_income * (input.municipal_tax_rate / 100.0);
-        breakdown.push(TaxBreakdownItem {
-            description: "Kommuneskatt".to_string(),
+        let municipal_tax = taxable_income * rates.municipal;
+        breakdown.push(|| TaxBreakdownItem {
+            id: "municipal-tax".into(),
+            description: "Kommuneskatt".into(),
             amount: municipal_tax,
-            rate: Some(input.municipal_tax_rate),
+            rate: Some(rates.municipal),
+            category: BreakdownCategory::IncomeTax,
+            rule: TaxRule::MunicipalTax,
+            is_estimate: false,
         });
 
-        let county_tax = taxable_income * (input.county_tax_rate / 100.0);
-        breakdown.push(TaxBreakdownItem {
-            description: "Fylkeskatt".to_string(),
+        let county_tax = taxable_income * rates.county;
+        breakdown.push(|| TaxBreakdownItem {
+            id: "county-tax".into(),
+            description: "Fylkeskatt".into(),
             amount: county_tax,
-            rate: Some(input.county_tax_rate),
+            rate: Some(rates.county),
+            category: BreakdownCategory::IncomeTax,
+            rule: TaxRule::CountyTax,
+            is_estimate: false,
         });
 
-        let church_tax = if input.is_church_member {
-            let tax = taxable_income * (input.church_tax_rate / 100.0);
-            breakdown.push(TaxBreakdownItem {
-                description: "Kirkeskatt".to_string(),
+        let fellesskatt = taxable_income * rates.fellesskatt;
+        breakdown.push(|| TaxBreakdownItem {
+            id: "fellesskatt".into(),
+            description: "Fellesskatt".into(),
+            amount: fellesskatt,
+            rate: Some(rates.fellesskatt),
+            category: BreakdownCategory::IncomeTax,
+            rule: TaxRule::Fellesskatt,
+            is_estimate: false,
+        });
+
+        let church_tax = if input.is_church_member && input.historical_church_tax {
+            let tax = taxable_income * input.church_tax_rate;
+            breakdown.push(|| TaxBreakdownItem {
+                id: "church-tax".into(),
+                description: "Kirkeskatt".into(),
                 amount: tax,
                 rate: Some(input.church_tax_rate),
+                category: BreakdownCategory::IncomeTax,
+                rule: TaxRule::ChurchTax,
+                is_estimate: true,
             });
             tax
         } else {
-            0.0
+            Money::ZERO
         };
 
-        let state_tax = Self::calculate_state_tax(input.gross_income, &mut breakdown);
+        let state_tax = Self::calculate_state_tax(&rules, personinntekt, &mut breakdown);
 
-        let national_insurance = input.gross_income * Self::NATIONAL_INSURANCE_RATE_2024;
-        breakdown.push(TaxBreakdownItem {
-            description: "Trygdeavgift".to_string(),
-            amount: national_insurance,
-            rate: Some(Self::NATIONAL_INSURANCE_RATE_2024 * 100.0),
+        let wage_contribution = (input.gross_income + input.sick_pay).max(Money::ZERO) * rules.national_insurance_rate;
+        let disability_contribution = input.disability_benefit.max(Money::ZERO) * rules.national_insurance_rate_pension;
+        let national_insurance =
+            Self::national_insurance(&rules, personinntekt, wage_contribution + disability_contribution);
+        // The lower limit applies to the total, so a reduced amount is shared
+        // between the two rates in proportion
+        let disability_national_insurance = if disability_contribution > Money::ZERO {
+            national_insurance * (disability_contribution / (wage_contribution + disability_contribution))
+        } else {
+            Money::ZERO
+        };
+        breakdown.push(|| TaxBreakdownItem {
+            id: "national-insurance".into(),
+            description: "Trygdeavgift".into(),
+            amount: national_insurance - disability_national_insurance,
+            rate: Some(rules.national_insurance_rate),
+            category: BreakdownCategory::NationalInsurance,
+            rule: TaxRule::NationalInsurance,
+            is_estimate: false,
         });
+        if input.disability_benefit > Money::ZERO {
+            breakdown.push(|| TaxBreakdownItem {
+                id: "national-insurance-disability".into(),
+                description: "Trygdeavgift av uføretrygd".into(),
+                amount: disability_national_insurance,
+                rate: Some(rules.national_insurance_rate_pension),
+                category: BreakdownCategory::NationalInsurance,
+                rule: TaxRule::DisabilityBenefit,
+                is_estimate: false,
+            });
+        }
 
-        let investment_tax = Self::calculate_investment_tax(input, &mut breakdown);
+        let investment_tax = Self::calculate_investment_tax(input, &mut breakdown, &mut warnings);
         let wealth_tax = Self::calculate_wealth_tax(input, &mut breakdown);
 
-        let total_tax = municipal_tax + county_tax + church_tax + state_tax + national_insurance + investment_tax + wealth_tax;
-        let total_gross_income = input.gross_income + input.dividend_income + input.capital_gains;
+        let total_tax = municipal_tax + county_tax + fellesskatt + church_tax + state_tax + national_insurance
+            + investment_tax + wealth_tax;
+        for warning in &warnings {
+            breakdown.push(|| warning.info_item());
+        }
+
+        let total_gross_income = input.gross_income + input.sick_pay + input.disability_benefit
+            + input.dividend_income + input.capital_gains;
         let net_income = total_gross_income - total_tax;
-        let effective_tax_rate = if total_gross_income > 0.0 {
-            (total_tax / total_gross_income) * 100.0
+        let effective_tax_rate = if total_gross_income > Money::ZERO {
+            Rate::from_fraction(total_tax / total_gross_income)
         } else {
-            0.0
+            Rate::ZERO
         };
 
         TaxCalculationResult {
             gross_income: total_gross_income,
             personal_allowance,
+            personinntekt,
+            alminnelig_inntekt,
             taxable_income,
             municipal_tax,
             county_tax,
+            fellesskatt,
             church_tax,
             state_tax,
-            corporate_tax: 0.0,
+            corporate_tax: Money::ZERO,
+            resource_rent_tax: Money::ZERO,
             national_insurance,
             investment_tax,
             wealth_tax,
+            property_tax: Money::ZERO,
             total_tax,
             net_income,
             effective_tax_rate,
-            breakdown,
+            capital_income: input.dividend_income + input.capital_gains,
+            wage_effective_tax_rate: Rate::ZERO,
+            pension_accrual: Self::pension_accrual(input.tax_year, input.gross_income + input.sick_pay),
+            breakdown: breakdown.items,
+            warnings,
+            assumptions: Vec::new(),
+            tax_year: input.tax_year,
         }
     }
 
-    fn calculate_corporate_tax(input: &TaxCalculationInput) -> TaxCalculationResult {
-        let mut breakdown = Vec::new();
+    fn calculate_corporate_tax(input: &TaxCalculationInput, detailed: bool) -> TaxCalculationResult {
+        let rules = input.rules();
+        let mut breakdown = Breakdown::new(detailed);
+        let mut warnings = Vec::new();
         
-        let taxable_income = (input.gross_income - input.allowable_deductions).max(0.0);
-        
-        if input.allowable_deductions > 0.0 {
-            breakdown.push(TaxBreakdownItem {
-                description: "Fradrag".to_string(),
-                amount: -input.allowable_deductions,
-                rate: None,
-            });
-        }
+        // A company has no personinntekt; only alminnelig inntekt is taxed
+        let taxable_income = Self::apply_deductions(
+            input.gross_income,
+            &[Deduction { id: "deductions", description: "Fradrag", amount: input.allowable_deductions, rule: TaxRule::Deductions }],
+            &mut breakdown,
+            &mut warnings,
+        )
+        .alminnelig_inntekt;
 
-        let corporate_tax = taxable_income * Self::CORPORATE_TAX_RATE_2024;
-        breakdown.push(TaxBreakdownItem {
-            description: "Selskapsskatt".to_string(),
+        let corporate_tax = taxable_income * rules.corporate_tax_rate;
+        breakdown.push(|| TaxBreakdownItem {
+            id: "corporate-tax".into(),
+            description: "Selskapsskatt".into(),
             amount: corporate_tax,
-            rate: Some(Self::CORPORATE_TAX_RATE_2024 * 100.0),
+            rate: Some(rules.corporate_tax_rate),
+            category: BreakdownCategory::IncomeTax,
+            rule: TaxRule::CorporateTax,
+            is_estimate: false,
         });
 
         let investment_tax = Self::calculate_corporate_investment_tax(input, &mut breakdown);
+        let resource_rent_tax = Self::calculate_resource_rent_tax(input, &mut breakdown)
+            + Self::calculate_petroleum_tax(input, taxable_income, &mut breakdown);
         
-        let total_tax = corporate_tax + investment_tax;
+        let total_tax = corporate_tax + investment_tax + resource_rent_tax;
+        for warning in &warnings {
+            breakdown.push(|| warning.info_item());
+        }
+
         let total_gross_income = input.gross_income + input.dividend_income + input.capital_gains;
         let net_income = total_gross_income - total_tax;
-        let effective_tax_rate = if total_gross_income > 0.0 {
-            (total_tax / total_gross_income) * 100.0
+        let effective_tax_rate = if total_gross_income > Money::ZERO {
+            Rate::from_fraction(total_tax / total_gross_income)
         } else {
-            0.0
+            Rate::ZERO
         };
 
         TaxCalculationResult {
             gross_income: total_gross_income,
-            personal_allowance: 0.0,
+            personal_allowance: Money::ZERO,
+            personinntekt: Money::ZERO,
+            alminnelig_inntekt: taxable_income,
             taxable_income,
-            municipal_tax: 0.0,
-            county_tax: 0.0,
-            church_tax: 0.0,
-            state_tax: 0.0,
+            municipal_tax: Money::ZERO,
+            county_tax: Money::ZERO,
+            fellesskatt: Money::ZERO,
+            church_tax: Money::ZERO,
+            state_tax: Money::ZERO,
             corporate_tax,
-            national_insurance: 0.0,
+            resource_rent_tax,
+            national_insurance: Money::ZERO,
             investment_tax,
-            wealth_tax: 0.0,
+            wealth_tax: Money::ZERO,
+            property_tax: Money::ZERO,
             total_tax,
             net_income,
             effective_tax_rate,
-            breakdown,
+            capital_income: input.dividend_income + input.capital_gains,
+            wage_effective_tax_rate: Rate::ZERO,
+            pension_accrual: Money::ZERO,
+            breakdown: breakdown.items,
+            warnings,
+            assumptions: Vec::new(),
+            tax_year: input.tax_year,
         }
     }
 
-    fn calculate_partnership_tax(input: &TaxCalculationInput) -> TaxCalculationResult {
-        let mut result = Self::calculate_individual_tax(input);
-        
-        result.breakdown.insert(0, TaxBreakdownItem {
-            description: "Deltakerlignet selskap - beskattes som personinntekt".to_string(),
-            amount: 0.0,
+    fn calculate_foreign_branch_tax(input: &TaxCalculationInput, detailed: bool) -> TaxCalculationResult {
+        let rules = input.rules();
+        let mut breakdown = Breakdown::new(detailed);
+        let mut warnings = Vec::new();
+
+        breakdown.push(|| TaxBreakdownItem {
+            id: "foreign-branch-info".into(),
+            description: "NUF - Norskregistrert utenlandsk foretak".into(),
+            amount: Money::ZERO,
             rate: None,
+            category: BreakdownCategory::Info,
+            rule: TaxRule::ForeignBranch,
+            is_estimate: false,
         });
 
-        result
+        let employer_rate = EmployerZone::default().rate();
+        let employer_contribution = input.branch_payroll.max(Money::ZERO) * employer_rate;
+
+        // Only the profit from the Norwegian branch is taxed here, like an AS
+        let taxable_income = Self::apply_deductions(
+            input.gross_income,
+            &[
+                Deduction { id: "payroll-costs", description: "Lønnskostnader", amount: input.branch_payroll, rule: TaxRule::BusinessExpenses },
+                Deduction { id: "employer-contribution-deduction", description: "Fradrag for arbeidsgiveravgift", amount: employer_contribution, rule: TaxRule::EmployerContribution },
+                Deduction { id: "deductions", description: "Fradrag", amount: input.allowable_deductions, rule: TaxRule::Deductions },
+            ],
+            &mut breakdown,
+            &mut warnings,
+        )
+        .alminnelig_inntekt;
+
+        let corporate_tax = taxable_income * rules.corporate_tax_rate;
+        breakdown.push(|| TaxBreakdownItem {
+            id: "corporate-tax".into(),
+            description: "Skatt på norsk virksomhet".into(),
+            amount: corporate_tax,
+            rate: Some(rules.corporate_tax_rate),
+            category: BreakdownCategory::IncomeTax,
+            rule: TaxRule::CorporateTax,
+            is_estimate: false,
+        });
+
+        if employer_contribution > Money::ZERO {
+            breakdown.push(|| TaxBreakdownItem {
+                id: "employer-contribution".into(),
+                description: "Arbeidsgiveravgift".into(),
+                amount: employer_contribution,
+                rate: Some(employer_rate),
+                category: BreakdownCategory::NationalInsurance,
+                rule: TaxRule::EmployerContribution,
+                is_estimate: true,
+            });
+        }
+
+        let investment_tax = Self::calculate_corporate_investment_tax(input, &mut breakdown);
+
+        let total_tax = corporate_tax + employer_contribution + investment_tax;
+        for warning in &warnings {
+            breakdown.push(|| warning.info_item());
+        }
+
+        let total_gross_income = input.gross_income + input.dividend_income + input.capital_gains;
+        let net_income = total_gross_income - total_tax;
+        let effective_tax_rate = if total_gross_income > Money::ZERO {
+            Rate::from_fraction(total_tax / total_gross_income)
+        } else {
+            Rate::ZERO
+        };
+
+        TaxCalculationResult {
+            gross_income: total_gross_income,
+            personal_allowance: Money::ZERO,
+            personinntekt: Money::ZERO,
+            alminnelig_inntekt: taxable_income,
+            taxable_income,
+            municipal_tax: Money::ZERO,
+            county_tax: Money::ZERO,
+            fellesskatt: Money::ZERO,
+            church_tax: Money::ZERO,
+            state_tax: Money::ZERO,
+            corporate_tax,
+            resource_rent_tax: Money::ZERO,
+            national_insurance: employer_contribution,
+            investment_tax,
+            wealth_tax: Money::ZERO,
+            property_tax: Money::ZERO,
+            total_tax,
+            net_income,
+            effective_tax_rate,
+            capital_income: input.dividend_income + input.capital_gains,
+            wage_effective_tax_rate: Rate::ZERO,
+            pension_accrual: Money::ZERO,
+            breakdown: breakdown.items,
+            warnings,
+            assumptions: Vec::new(),
+            tax_year: input.tax_year,
+        }
     }
 
-    fn calculate_enk_tax(input: &TaxCalculationInput) -> TaxCalculationResult {
-        let mut breakdown = Vec::new();
-        
-        let business_profit = (input.gross_income - input.business_expenses).max(0.0);
-        let taxable_income = (business_profit - input.allowable_deductions).max(0.0);
-        
-        breakdown.push(TaxBreakdownItem {
-            description: "ENK - Enkeltpersonforetak".to_string(),
-            amount: 0.0,
+    fn calculate_nonprofit_tax(input: &TaxCalculationInput, detailed: bool) -> TaxCalculationResult {
+        let rules = input.rules();
+        let mut breakdown = Breakdown::new(detailed);
+        let mut warnings = Vec::new();
+
+        let revenue_limit = if input.charitable_purpose {
+            rules.nonprofit_revenue_limit_charitable
+        } else {
+            rules.nonprofit_revenue_limit
+        };
+        breakdown.push(|| TaxBreakdownItem {
+            id: "nonprofit-revenue-limit".into(),
+            description: "Beløpsgrense for skattefri økonomisk virksomhet".into(),
+            amount: revenue_limit,
             rate: None,
+            category: BreakdownCategory::Info,
+            rule: TaxRule::Nonprofit,
+            is_estimate: false,
         });
 
-        if input.business_expenses > 0.0 {
-            breakdown.push(TaxBreakdownItem {
-                description: "Driftskostnader".to_string(),
-                amount: -input.business_expenses,
+        // Once revenue passes the limit the whole profit is taxed, not just
+        // the part above it
+        let taxable_income = if input.gross_income > revenue_limit {
+            Self::apply_deductions(
+                input.gross_income,
+                &[
+                    Deduction { id: "business-expenses", description: "Driftskostnader", amount: input.business_expenses, rule: TaxRule::BusinessExpenses },
+                    Deduction { id: "deductions", description: "Fradrag", amount: input.allowable_deductions, rule: TaxRule::Deductions },
+                ],
+                &mut breakdown,
+                &mut warnings,
+            )
+            .alminnelig_inntekt
+        } else {
+            breakdown.push(|| TaxBreakdownItem {
+                id: "nonprofit-exempt-revenue".into(),
+                description: "Skattefri omsetning under beløpsgrensen".into(),
+                amount: input.gross_income,
                 rate: None,
+                category: BreakdownCategory::Info,
+                rule: TaxRule::Nonprofit,
+                is_estimate: false,
+            });
+            Money::ZERO
+        };
+
+        let corporate_tax = taxable_income * rules.corporate_tax_rate;
+        if corporate_tax > Money::ZERO {
+            breakdown.push(|| TaxBreakdownItem {
+                id: "corporate-tax".into(),
+                description: "Skatt på økonomisk virksomhet".into(),
+                amount: corporate_tax,
+                rate: Some(rules.corporate_tax_rate),
+                category: BreakdownCategory::IncomeTax,
+                rule: TaxRule::CorporateTax,
+                is_estimate: false,
             });
         }
 
-        if input.allowable_deductions > 0.0 {
-            breakdown.push(TaxBreakdownItem {
-                description: "Fradrag".to_string(),
-                amount: -input.allowable_deductions,
+        let total_tax = corporate_tax;
+        for warning in &warnings {
+            breakdown.push(|| warning.info_item());
+        }
+
+        let total_gross_income = input.gross_income + input.dividend_income + input.capital_gains;
+        let net_income = total_gross_income - total_tax;
+        let effective_tax_rate = if total_gross_income > Money::ZERO {
+            Rate::from_fraction(total_tax / total_gross_income)
+        } else {
+            Rate::ZERO
+        };
+
+        TaxCalculationResult {
+            gross_income: total_gross_income,
+            personal_allowance: Money::ZERO,
+            personinntekt: Money::ZERO,
+            alminnelig_inntekt: taxable_income,
+            taxable_income,
+            municipal_tax: Money::ZERO,
+            county_tax: Money::ZERO,
+            fellesskatt: Money::ZERO,
+            church_tax: Money::ZERO,
+            state_tax: Money::ZERO,
+            corporate_tax,
+            resource_rent_tax: Money::ZERO,
+            national_insurance: Money::ZERO,
+            investment_tax: Money::ZERO,
+            wealth_tax: Money::ZERO,
+            property_tax: Money::ZERO,
+            total_tax,
+            net_income,
+            effective_tax_rate,
+            capital_income: input.dividend_income + input.capital_gains,
+            wage_effective_tax_rate: Rate::ZERO,
+            pension_accrual: Money::ZERO,
+            breakdown: breakdown.items,
+            warnings,
+            assumptions: Vec::new(),
+            tax_year: input.tax_year,
+        }
+    }
+
+    fn calculate_partnership_tax(input: &TaxCalculationInput, detailed: bool) -> TaxCalculationResult {
+        let mut result = Self::calculate_individual_tax(input, detailed);
+
+        if detailed {
+            result.breakdown.insert(0, TaxBreakdownItem {
+                id: "partnership-info".into(),
+                description: "Deltakerlignet selskap - beskattes som personinntekt".into(),
+                amount: Money::ZERO,
                 rate: None,
+                category: BreakdownCategory::Info,
+                rule: TaxRule::PartnershipTaxation,
+                is_estimate: false,
             });
         }
 
-        let municipal_tax = taxable_income * (input.municipal_tax_rate / 100.0);
-        breakdown.push(TaxBreakdownItem {
-            description: "Kommuneskatt".to_string(),
+        result
+    }
+
+    fn calculate_enk_tax(input: &TaxCalculationInput, detailed: bool) -> TaxCalculationResult {
+        let rules = input.rules();
+        let mut breakdown = Breakdown::new(detailed);
+        let mut warnings = Vec::new();
+        
+        breakdown.push(|| TaxBreakdownItem {
+            id: "sole-proprietorship-info".into(),
+            description: "ENK - Enkeltpersonforetak".into(),
+            amount: Money::ZERO,
+            rate: None,
+            category: BreakdownCategory::Info,
+            rule: TaxRule::SoleProprietorship,
+            is_estimate: false,
+        });
+
+        let IncomeBases { personinntekt, alminnelig_inntekt } = Self::apply_deductions(
+            input.gross_income,
+            &std::iter::once(Deduction { id: "business-expenses", description: "Driftskostnader", amount: input.business_expenses, rule: TaxRule::BusinessExpenses })
+                .chain(Self::personal_deductions(input))
+                .collect::<Vec<_>>(),
+            &mut breakdown,
+            &mut warnings,
+        );
+        warnings.extend(Self::donation_warning(input));
+        // Business expenses reduce personinntekt, so it is the business profit
+        let business_profit = personinntekt;
+        let taxable_income = alminnelig_inntekt;
+
+        let rates = Self::income_tax_rates(input);
+        warnings.extend(Self::local_rates_warning(input, taxable_income));
+
+        let municipal_tax = taxable_income * rates.municipal;
+        breakdown.push(|| TaxBreakdownItem {
+            id: "municipal-tax".into(),
+            description: "Kommuneskatt".into(),
             amount: municipal_tax,
-            rate: Some(input.municipal_tax_rate),
+            rate: Some(rates.municipal),
+            category: BreakdownCategory::IncomeTax,
+            rule: TaxRule::MunicipalTax,
+            is_estimate: false,
         });
 
-        let county_tax = taxable_income * (input.county_tax_rate / 100.0);
-        breakdown.push(TaxBreakdownItem {
-            description: "Fylkeskatt".to_string(),
+        let county_tax = taxable_income * rates.county;
+        breakdown.push(|| TaxBreakdownItem {
+            id: "county-tax".into(),
+            description: "Fylkeskatt".into(),
             amount: county_tax,
-            rate: Some(input.county_tax_rate),
+            rate: Some(rates.county),
+            category: BreakdownCategory::IncomeTax,
+            rule: TaxRule::CountyTax,
+            is_estimate: false,
         });
 
-        let church_tax = if input.is_church_member {
-            let tax = taxable_income * (input.church_tax_rate / 100.0);
-            breakdown.push(TaxBreakdownItem {
-                description: "Kirkeskatt".to_string(),
+        let fellesskatt = taxable_income * rates.fellesskatt;
+        breakdown.push(|| TaxBreakdownItem {
+            id: "fellesskatt".into(),
+            description: "Fellesskatt".into(),
+            amount: fellesskatt,
+            rate: Some(rates.fellesskatt),
+            category: BreakdownCategory::IncomeTax,
+            rule: TaxRule::Fellesskatt,
+            is_estimate: false,
+        });
+
+        let church_tax = if input.is_church_member && input.historical_church_tax {
+            let tax = taxable_income * input.church_tax_rate;
+            breakdown.push(|| TaxBreakdownItem {
+                id: "church-tax".into(),
+                description: "Kirkeskatt".into(),
                 amount: tax,
                 rate: Some(input.church_tax_rate),
+                category: BreakdownCategory::IncomeTax,
+                rule: TaxRule::ChurchTax,
+                is_estimate: true,
             });
             tax
         } else {
-            0.0
+            Money::ZERO
         };
 
-        let state_tax = Self::calculate_state_tax(input.gross_income, &mut breakdown);
+        let state_tax = Self::calculate_state_tax(&rules, personinntekt, &mut breakdown);
 
-        let national_insurance = input.gross_income * Self::NATIONAL_INSURANCE_RATE_ENK_2024;
-        breakdown.push(TaxBreakdownItem {
-            description: "Trygdeavgift (ENK)".to_string(),
+        let national_insurance =
+            Self::national_insurance(&rules, personinntekt, personinntekt * rules.national_insurance_rate_enk);
+        breakdown.push(|| TaxBreakdownItem {
+            id: "national-insurance".into(),
+            description: "Trygdeavgift (ENK)".into(),
             amount: national_insurance,
-            rate: Some(Self::NATIONAL_INSURANCE_RATE_ENK_2024 * 100.0),
+            rate: Some(rules.national_insurance_rate_enk),
+            category: BreakdownCategory::NationalInsurance,
+            rule: TaxRule::NationalInsurance,
+            is_estimate: false,
         });
 
-        let investment_tax = Self::calculate_investment_tax(input, &mut breakdown);
+        let sick_pay_premium = business_profit.min(rules.grunnbeloep.times(SICK_PAY_CAP_G))
+            * input.sick_pay_insurance.premium_rate();
+        if sick_pay_premium > Money::ZERO {
+            breakdown.push(|| TaxBreakdownItem {
+                id: "sick-pay-insurance".into(),
+                description: format!("Tilleggstrygd for sykepenger ({})", input.sick_pay_insurance.label()).into(),
+                amount: sick_pay_premium,
+                rate: Some(input.sick_pay_insurance.premium_rate()),
+                category: BreakdownCategory::NationalInsurance,
+                rule: TaxRule::SickPayInsurance,
+                is_estimate: false,
+            });
+        }
+
+        let pension_accrual_cap = rules.grunnbeloep.times(PENSION_ACCRUAL_CAP_G);
+        if business_profit > pension_accrual_cap {
+            breakdown.push(|| TaxBreakdownItem {
+                id: "pension-accrual-cap".into(),
+                description: format!(
+                    "Pensjonsopptjening i folketrygden gjelder personinntekt opp til 7,1G ({})",
+                    Self::format_nok(pension_accrual_cap)
+                )
+                .into(),
+                amount: Money::ZERO,
+                rate: None,
+                category: BreakdownCategory::Info,
+                rule: TaxRule::PensionAccrual,
+                is_estimate: false,
+            });
+        }
+
+        let investment_tax = Self::calculate_investment_tax(input, &mut breakdown, &mut warnings);
         let wealth_tax = Self::calculate_wealth_tax(input, &mut breakdown);
 
-        let total_tax = municipal_tax + county_tax + church_tax + state_tax + national_insurance + investment_tax + wealth_tax;
+        let total_tax = municipal_tax + county_tax + fellesskatt + church_tax + state_tax + national_insurance
+            + sick_pay_premium + investment_tax + wealth_tax;
+        for warning in &warnings {
+            breakdown.push(|| warning.info_item());
+        }
+
         let total_gross_income = input.gross_income + input.dividend_income + input.capital_gains;
         let net_income = total_gross_income - total_tax;
-        let effective_tax_rate = if total_gross_income > 0.0 {
-            (total_tax / total_gross_income) * 100.0
+        let effective_tax_rate = if total_gross_income > Money::ZERO {
+            Rate::from_fraction(total_tax / total_gross_income)
         } else {
-            0.0
+            Rate::ZERO
         };
 
         TaxCalculationResult {
             gross_income: total_gross_income,
-            personal_allowance: 0.0,
+            personal_allowance: Money::ZERO,
+            personinntekt,
+            alminnelig_inntekt,
             taxable_income,
             municipal_tax,
             county_tax,
+            fellesskatt,
             church_tax,
             state_tax,
-            corporate_tax: 0.0,
+            corporate_tax: Money::ZERO,
+            resource_rent_tax: Money::ZERO,
             national_insurance,
             investment_tax,
             wealth_tax,
+            property_tax: Money::ZERO,
             total_tax,
             net_income,
             effective_tax_rate,
-            breakdown,
+            capital_income: input.dividend_income + input.capital_gains,
+            wage_effective_tax_rate: Rate::ZERO,
+            pension_accrual: Self::pension_accrual(input.tax_year, business_profit),
+            breakdown: breakdown.items,
+            warnings,
+            assumptions: Vec::new(),
+            tax_year: input.tax_year,
         }
     }
 
-    fn calculate_state_tax(gross_income: f64, breakdown: &mut Vec<TaxBreakdownItem>) -> f64 {
-        let mut state_tax = 0.0;
+    pub fn state_tax_brackets(tax_year: TaxYear, personal_income: Money) -> Vec<StateTaxBracket> {
+        Self::brackets(tax_year.rules(), personal_income)
+    }
 
-        for &(threshold, rate) in Self::STATE_TAX_BRACKETS {
-            if gross_income > threshold {
-                let taxable_in_bracket = (gross_income - threshold).min(
-                    Self::STATE_TAX_BRACKETS
-                        .iter()
-                        .find(|&&(t, _)| t > threshold)
-                        .map(|&(t, _)| t - threshold)
-                        .unwrap_or(gross_income - threshold)
-                );
-                
-                let tax_in_bracket = taxable_in_bracket * rate;
-                state_tax += tax_in_bracket;
+    fn brackets(rules: &TaxRules, personal_income: Money) -> Vec<StateTaxBracket> {
+        let brackets = &rules.state_tax_brackets;
+        brackets
+            .iter()
+            .enumerate()
+            .map(|(index, &(threshold, rate))| {
+                let upper = brackets.get(index + 1).map(|&(next, _)| next);
+                let income_in_bracket = (personal_income.min(upper.unwrap_or(Money(f64::INFINITY))) - threshold).max(Money::ZERO);
+                StateTaxBracket {
+                    step: index + 1,
+                    threshold,
+                    upper,
+                    rate,
+                    income_in_bracket,
+                    tax: income_in_bracket * rate,
+                }
+            })
+            .collect()
+    }
+
+    // Nothing up to the lower limit, then phased in until the amount at the
+    // full rates is the smaller one
+    fn national_insurance(rules: &TaxRules, personinntekt: Money, full_rates: Money) -> Money {
+        if personinntekt <= rules.national_insurance_lower_limit {
+            return Money::ZERO;
+        }
+        full_rates.min((personinntekt - rules.national_insurance_lower_limit) * NATIONAL_INSURANCE_PHASE_IN_RATE)
+    }
+
+    fn calculate_state_tax(rules: &TaxRules, gross_income: Money, breakdown: &mut Breakdown) -> Money {
+        let mut state_tax = Money::ZERO;
+
+        for bracket in Self::brackets(rules, gross_income) {
+            if gross_income > bracket.threshold {
+                state_tax += bracket.tax;
                 
-                breakdown.push(TaxBreakdownItem {
-                    description: format!("Statsskatt (over {} NOK)", Self::format_currency(threshold)),
-                    amount: tax_in_bracket,
-                    rate: Some(rate * 100.0),
+                breakdown.push(|| TaxBreakdownItem {
+                    id: format!("state-tax-{}", bracket.step).into(),
+                    description: format!("Statsskatt (over {} NOK)", Self::format_currency(bracket.threshold)).into(),
+                    amount: bracket.tax,
+                    rate: Some(bracket.rate),
+                    category: BreakdownCategory::IncomeTax,
+                    rule: TaxRule::StateTax,
+                    is_estimate: false,
                 });
             }
         }
@@ -347,95 +2260,208 @@ impl NorwegianTaxCalculator {
         state_tax
     }
 
-    fn calculate_investment_tax(input: &TaxCalculationInput, breakdown: &mut Vec<TaxBreakdownItem>) -> f64 {
+    fn calculate_investment_tax(
+        input: &TaxCalculationInput,
+        breakdown: &mut Breakdown,
+        warnings: &mut Vec<TaxWarning>,
+    ) -> Money {
+        let rules = input.rules();
         let total_investment_income = input.dividend_income + input.capital_gains;
         
-        if total_investment_income <= 0.0 {
-            return 0.0;
+        if total_investment_income <= Money::ZERO {
+            return Money::ZERO;
         }
 
-        let risk_free_allowance = input.investment_wealth * Self::RISK_FREE_RATE_2024;
-        let taxable_investment_income = (total_investment_income - risk_free_allowance).max(0.0);
+        let risk_free_allowance = input.investment_wealth * rules.risk_free_rate;
+        let taxable_investment_income = (total_investment_income - risk_free_allowance).max(Money::ZERO);
+        if risk_free_allowance > total_investment_income {
+            warnings.push(TaxWarning::RiskFreeAllowance {
+                unused: risk_free_allowance - total_investment_income,
+            });
+        }
         
-        if risk_free_allowance > 0.0 {
-            breakdown.push(TaxBreakdownItem {
-                description: "Risikofritt fradrag".to_string(),
+        if risk_free_allowance > Money::ZERO {
+            breakdown.push(|| TaxBreakdownItem {
+                id: "risk-free-allowance".into(),
+                description: "Risikofritt fradrag".into(),
                 amount: -risk_free_allowance,
-                rate: Some(Self::RISK_FREE_RATE_2024 * 100.0),
+                rate: Some(rules.risk_free_rate),
+                category: BreakdownCategory::Capital,
+                rule: TaxRule::RiskFreeAllowance,
+                is_estimate: true,
             });
         }
 
-        let investment_tax = taxable_investment_income * Self::INVESTMENT_TAX_RATE_2024;
+        let investment_tax = taxable_investment_income * rules.investment_tax_rate;
         
-        if investment_tax > 0.0 {
-            breakdown.push(TaxBreakdownItem {
-                description: "Skatt på aksjeutbytte og gevinst".to_string(),
+        if investment_tax > Money::ZERO {
+            breakdown.push(|| TaxBreakdownItem {
+                id: "shareholder-tax".into(),
+                description: "Skatt på aksjeutbytte og gevinst".into(),
                 amount: investment_tax,
-                rate: Some(Self::INVESTMENT_TAX_RATE_2024 * 100.0),
+                rate: Some(rules.investment_tax_rate),
+                category: BreakdownCategory::Capital,
+                rule: TaxRule::ShareholderTax,
+                is_estimate: false,
             });
         }
 
         investment_tax
     }
 
-    fn calculate_corporate_investment_tax(input: &TaxCalculationInput, breakdown: &mut Vec<TaxBreakdownItem>) -> f64 {
+    fn calculate_corporate_investment_tax(input: &TaxCalculationInput, breakdown: &mut Breakdown) -> Money {
+        let rules = input.rules();
         let total_investment_income = input.dividend_income + input.capital_gains;
         
-        if total_investment_income <= 0.0 {
-            return 0.0;
+        if total_investment_income <= Money::ZERO {
+            return Money::ZERO;
         }
 
-        let taxable_portion = total_investment_income * 0.03;
-        let investment_tax = taxable_portion * Self::CORPORATE_TAX_RATE_2024;
+        let taxable_portion = total_investment_income * PARTICIPATION_EXEMPTION_TAXABLE_SHARE;
+        let investment_tax = taxable_portion * rules.corporate_tax_rate;
         
-        if investment_tax > 0.0 {
-            breakdown.push(TaxBreakdownItem {
-                description: "Deltakermodellen - 3% skattepliktig".to_string(),
+        if investment_tax > Money::ZERO {
+            breakdown.push(|| TaxBreakdownItem {
+                id: "participation-exemption".into(),
+                description: "Deltakermodellen - 3% skattepliktig".into(),
                 amount: investment_tax,
-                rate: Some(0.66),
+                rate: Some(rules.corporate_tax_rate * PARTICIPATION_EXEMPTION_TAXABLE_SHARE),
+                category: BreakdownCategory::Capital,
+                rule: TaxRule::ParticipationExemption,
+                is_estimate: false,
             });
         }
 
         investment_tax
     }
 
-    fn calculate_wealth_tax(input: &TaxCalculationInput, breakdown: &mut Vec<TaxBreakdownItem>) -> f64 {
+    // Kept apart from the corporate tax: the base is the qualifying activity
+    // alone, with its own deductions
+    fn calculate_resource_rent_tax(input: &TaxCalculationInput, breakdown: &mut Breakdown) -> Money {
+        let resource_rent = &input.resource_rent;
+        let activity = resource_rent.activity;
+        if activity == ResourceRentActivity::None {
+            return Money::ZERO;
+        }
+
+        let rules = input.rules();
+        let rent = resource_rent_tax(resource_rent, rules.corporate_tax_rate);
+        let mut item = |id: &'static str, description: Cow<'static, str>, amount: Money, rate: Option<Rate>| {
+            breakdown.push(|| TaxBreakdownItem {
+                id: id.into(),
+                description,
+                amount,
+                rate,
+                category: BreakdownCategory::ResourceRent,
+                rule: TaxRule::ResourceRentTax,
+                is_estimate: true,
+            });
+        };
+        item("resource-rent-costs", "Kostnader i grunnrentevirksomheten".into(), -resource_rent.costs, None);
+        item(
+            "resource-rent-corporate-tax-deduction",
+            "Beregnet selskapsskatt på grunnrenteinntekten".into(),
+            -rent.corporate_tax_deduction,
+            Some(rules.corporate_tax_rate),
+        );
+        if rent.allowance > Money::ZERO {
+            item("resource-rent-allowance", "Bunnfradrag".into(), -rent.allowance, None);
+        }
+        item(
+            "resource-rent-tax",
+            format!("Grunnrenteskatt ({})", activity.label().to_lowercase()).into(),
+            rent.tax,
+            Some(activity.rate()),
+        );
+
+        rent.tax
+    }
+
+    fn calculate_petroleum_tax(
+        input: &TaxCalculationInput,
+        taxable_income: Money,
+        breakdown: &mut Breakdown,
+    ) -> Money {
+        if !input.petroleum.enabled {
+            return Money::ZERO;
+        }
+
+        let rules = input.rules();
+        let petroleum = petroleum_special_tax(&input.petroleum, taxable_income, rules.corporate_tax_rate);
+        let mut item = |id: &'static str, description: &'static str, amount: Money, rate: Option<Rate>| {
+            breakdown.push(|| TaxBreakdownItem {
+                id: id.into(),
+                description: description.into(),
+                amount,
+                rate,
+                category: BreakdownCategory::ResourceRent,
+                rule: TaxRule::PetroleumTax,
+                is_estimate: true,
+            });
+        };
+        item(
+            "petroleum-corporate-tax-deduction",
+            "Fradrag for selskapsskatt i særskattegrunnlaget",
+            -petroleum.corporate_tax_deduction,
+            Some(rules.corporate_tax_rate),
+        );
+        if petroleum.uplift > Money::ZERO {
+            item("petroleum-uplift", "Friinntekt", -petroleum.uplift, None);
+        }
+        item("petroleum-tax", "Særskatt på petroleum", petroleum.tax, Some(PETROLEUM_SPECIAL_TAX_RATE));
+
+        petroleum.tax
+    }
+
+    // The state's share and the municipality's. A municipality below the
+    // maximum only lowers its own share.
+    pub fn wealth_tax_rate(input: &TaxCalculationInput) -> Rate {
+        let rules = input.rules();
+        let reduction = input.municipal_wealth_tax_rate.map_or(Rate::ZERO, |rate| {
+            (rules.municipal_wealth_tax_rate - rate).clamp(Rate::ZERO, rules.municipal_wealth_tax_rate)
+        });
+        (rules.wealth_tax_rate - reduction).max(Rate::ZERO)
+    }
+
+    fn calculate_wealth_tax(input: &TaxCalculationInput, breakdown: &mut Breakdown) -> Money {
+        let rules = input.rules();
         let total_wealth = input.investment_wealth;
         
-        if total_wealth <= Self::WEALTH_TAX_THRESHOLD_2024 {
-            return 0.0;
+        if total_wealth <= rules.wealth_tax_threshold {
+            return Money::ZERO;
         }
 
-        let taxable_wealth = total_wealth - Self::WEALTH_TAX_THRESHOLD_2024;
+        let taxable_wealth = total_wealth - rules.wealth_tax_threshold;
         let discounted_wealth = taxable_wealth * 0.8;
-        let wealth_tax = discounted_wealth * Self::WEALTH_TAX_RATE_2024;
+        let wealth_tax_rate = Self::wealth_tax_rate(input);
+        let wealth_tax = discounted_wealth * wealth_tax_rate;
         
-        if wealth_tax > 0.0 {
-            breakdown.push(TaxBreakdownItem {
-                description: "Formueskatt (20% rabatt på aksjer)".to_string(),
+        if wealth_tax > Money::ZERO {
+            breakdown.push(|| TaxBreakdownItem {
+                id: "wealth-tax".into(),
+                description: "Formueskatt (20% rabatt på aksjer)".into(),
                 amount: wealth_tax,
-                rate: Some(Self::WEALTH_TAX_RATE_2024 * 100.0),
+                rate: Some(wealth_tax_rate),
+                category: BreakdownCategory::Wealth,
+                rule: TaxRule::WealthTax,
+                is_estimate: true,
             });
         }
 
         wealth_tax
     }
 
-    pub fn format_currency(amount: f64) -> String {
-        format!("{:.0}", amount)
-            .chars()
-            .rev()
-            .collect::<Vec<_>>()
-            .chunks(3)
-            .map(|chunk| chunk.iter().collect::<String>())
-            .collect::<Vec<_>>()
-            .join(" ")
-            .chars()
-            .rev()
-            .collect()
+    // Groups of three digits from the right, separated by spaces
+    pub fn format_currency(amount: impl Into<Money>) -> String {
+        amount.into().grouped()
+    }
+
+    pub fn format_nok(amount: impl Into<Money>) -> String {
+        amount.into().to_string()
     }
 
-    pub fn get_default_rates() -> (f64, f64, f64) {
-        (10.0, 11.4, 1.3) // municipal, county, church tax rates
+    pub fn get_default_rates() -> (Rate, Rate, Rate) {
+        // Municipal, county and church tax
+        (Rate::from_percent(10.0), Rate::from_percent(11.4), Rate::from_percent(1.3))
     }
 }
\ No newline at end of file
@@ -0,0 +1,116 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use leptos::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Event, EventInit, HtmlElement, HtmlInputElement};
+
+use crate::components::{entity_tab_id, TaxCalculator};
+use crate::storage;
+use crate::tax_calculator::{EntityType, TaxCalculationInput, TaxCalculationResult};
+
+// Helpers for the component tests in tests/components.rs, which run in a
+// headless browser. Fields are found by their label, as a user would.
+pub struct MountedCalculator {
+    host: HtmlElement,
+    result: Rc<RefCell<Option<TaxCalculationResult>>>,
+}
+
+// Mounts an embedded calculator in its own element in the body, so it
+// leaves the URL alone and starts from the given input rather than a saved
+// session. Settings saved by earlier tests are cleared first.
+pub fn mount(input: TaxCalculationInput) -> MountedCalculator {
+    storage::clear();
+    let document = document();
+    let host = document
+        .create_element("div")
+        .expect("can create an element")
+        .unchecked_into::<HtmlElement>();
+    document.body().expect("the page has a body").append_child(&host).expect("can append to the body");
+
+    let result = Rc::new(RefCell::new(None));
+    let latest = result.clone();
+    let on_calculate = Callback::new(move |calculated| *latest.borrow_mut() = Some(calculated));
+    leptos::mount_to(host.clone(), move || view! {
+        <TaxCalculator embedded=true initial_input=input on_calculate=on_calculate />
+    });
+
+    MountedCalculator { host, result }
+}
+
+impl MountedCalculator {
+    // The last result the calculator reported
+    pub fn result(&self) -> TaxCalculationResult {
+        self.result.borrow().clone().expect("the calculator reports a result when mounted")
+    }
+
+    // The input under the first label starting with the given text
+    pub fn field(&self, label: &str) -> Option<HtmlInputElement> {
+        let labels = self.host.query_selector_all("label").ok()?;
+        (0..labels.length())
+            .filter_map(|index| labels.item(index)?.dyn_into::<Element>().ok())
+            .find(|element| element.text_content().is_some_and(|text| text.trim().starts_with(label)))
+            .and_then(|element| match element.get_attribute("for") {
+                Some(id) => self.host.query_selector(&format!("#{}", id)).ok()?,
+                None => element.query_selector("input").ok()?,
+            })
+            .and_then(|input| input.dyn_into().ok())
+    }
+
+    pub fn has_field(&self, label: &str) -> bool {
+        self.field(label).is_some()
+    }
+
+    // Types a value into a number field
+    pub fn set_value(&self, label: &str, value: &str) {
+        let input = self.expect_field(label);
+        input.set_value(value);
+        let init = EventInit::new();
+        init.set_bubbles(true);
+        let event = Event::new_with_event_init_dict("input", &init).expect("can create an input event");
+        input.dispatch_event(&event).expect("can dispatch the input event");
+    }
+
+    // Clicks a checkbox if it isn't in the given state already
+    pub fn set_checked(&self, label: &str, checked: bool) {
+        let input = self.expect_field(label);
+        if input.checked() != checked {
+            input.click();
+        }
+    }
+
+    pub fn select_entity(&self, entity_type: EntityType) {
+        self.host
+            .query_selector(&format!("#{}", entity_tab_id(entity_type)))
+            .ok()
+            .flatten()
+            .and_then(|tab| tab.dyn_into::<HtmlElement>().ok())
+            .expect("every entity type has a tab")
+            .click();
+    }
+
+    // The ids of the breakdown rows on screen, in order
+    pub fn breakdown_ids(&self) -> Vec<String> {
+        let Ok(rows) = self.host.query_selector_all(".result-item[data-id]") else {
+            return Vec::new();
+        };
+        (0..rows.length())
+            .filter_map(|index| rows.item(index)?.dyn_into::<Element>().ok()?.get_attribute("data-id"))
+            .collect()
+    }
+
+    // The text of the first element matching the selector
+    pub fn text(&self, selector: &str) -> Option<String> {
+        self.host.query_selector(selector).ok()??.text_content()
+    }
+
+    fn expect_field(&self, label: &str) -> HtmlInputElement {
+        self.field(label).unwrap_or_else(|| panic!("no field labelled {:?}", label))
+    }
+}
+
+impl Drop for MountedCalculator {
+    fn drop(&mut self) {
+        self.host.remove();
+    }
+}
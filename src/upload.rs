@@ -0,0 +1,19 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Event, File, HtmlInputElement};
+
+pub fn selected_file(ev: &Event) -> Option<File> {
+    ev.target()?
+        .dyn_into::<HtmlInputElement>()
+        .ok()?
+        .files()?
+        .get(0)
+}
+
+pub async fn read_file_text(file: File) -> Result<String, String> {
+    JsFuture::from(file.text())
+        .await
+        .ok()
+        .and_then(|text| text.as_string())
+        .ok_or_else(|| "Kunne ikke lese filen".to_string())
+}
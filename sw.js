@@ -0,0 +1,48 @@
+// Cache name comes from the registration URL (see src/pwa.rs), which changes
+// with every release and ruleset update
+const VERSION = new URL(self.location).searchParams.get('v') || 'dev';
+const CACHE = `ntc-${VERSION}`;
+
+const ASSETS = [
+    './',
+    'index.html',
+    'style.css',
+    'manifest.webmanifest',
+    'icon.svg',
+    'pkg/norwegian_tax_calculator.js',
+    'pkg/norwegian_tax_calculator_bg.wasm',
+];
+
+self.addEventListener('install', (event) => {
+    event.waitUntil(
+        caches.open(CACHE)
+            .then((cache) => cache.addAll(ASSETS))
+            .then(() => self.skipWaiting())
+    );
+});
+
+self.addEventListener('activate', (event) => {
+    event.waitUntil(
+        caches.keys()
+            .then((keys) => Promise.all(
+                keys.filter((key) => key.startsWith('ntc-') && key !== CACHE).map((key) => caches.delete(key))
+            ))
+            .then(() => self.clients.claim())
+    );
+});
+
+// Cache first for the app shell; online lookups (SSB, Norges Bank) go
+// straight to the network and simply fail offline
+self.addEventListener('fetch', (event) => {
+    const request = event.request;
+    if (request.method !== 'GET' || new URL(request.url).origin !== self.location.origin) {
+        return;
+    }
+    // Deep links such as /enk are routed in the app, so offline they get
+    // the cached index.html
+    const offline = (error) => request.mode === 'navigate' ? caches.match('index.html') : Promise.reject(error);
+    event.respondWith(
+        caches.match(request, { ignoreSearch: request.mode === 'navigate' })
+            .then((cached) => cached || fetch(request).catch(offline))
+    );
+});
@@ -0,0 +1,279 @@
+// The full breakdown for one representative input per entity type. A change
+// to the order, ids, wording or amounts of the lines shows up as a snapshot
+// diff to review, see the README.
+use norwegian_tax_calculator::commuter::CommuteInput;
+use norwegian_tax_calculator::custom_taxes::{CustomTax, CustomTaxBase, CustomTaxKind};
+use norwegian_tax_calculator::industries::{ResourceRentActivity, ResourceRentInput};
+use norwegian_tax_calculator::money::{Money, Rate};
+use norwegian_tax_calculator::property_tax::PropertyTaxInput;
+use norwegian_tax_calculator::special_deductions::{Occupation, SpecialDeductionsInput};
+use norwegian_tax_calculator::tax_calculator::{
+    EntityType, NorwegianTaxCalculator, TaxCalculationInput, TaxCalculationResult, TaxYear,
+};
+
+// Pinned to one year so a new default year doesn't rewrite every snapshot
+fn input(entity_type: EntityType, gross_income: f64) -> TaxCalculationInput {
+    TaxCalculationInput {
+        entity_type,
+        gross_income: Money(gross_income),
+        tax_year: TaxYear::Y2024,
+        ..TaxCalculationInput::default()
+    }
+}
+
+fn render(result: &TaxCalculationResult) -> String {
+    let mut lines = result
+        .breakdown
+        .iter()
+        .map(|item| {
+            let estimate = if item.is_estimate { " (anslag)" } else { "" };
+            let rate = item.rate.map(|rate| format!(" | {:.2}", rate)).unwrap_or_default();
+            let category = format!("{:?}", item.category);
+            format!("{:<18} {:<38} {}{} | {}{}", category, item.id, item.description, estimate, item.amount, rate)
+        })
+        .collect::<Vec<_>>();
+    lines.push(String::new());
+    lines.push(format!("Total skatt: {}", result.total_tax));
+    lines.push(format!("Nettoinntekt: {}", result.net_income));
+    lines.push(format!("Effektiv skattesats: {:.2}", result.effective_tax_rate));
+    lines.join("\n")
+}
+
+fn assert_breakdown(name: &str, input: &TaxCalculationInput) {
+    insta::assert_snapshot!(name, render(&NorwegianTaxCalculator::calculate_tax(input)));
+}
+
+#[test]
+fn individual() {
+    assert_breakdown(
+        "individual",
+        &TaxCalculationInput {
+            allowable_deductions: Money(40_000.0),
+            dividend_income: Money(60_000.0),
+            capital_gains: Money(25_000.0),
+            investment_wealth: Money(3_000_000.0),
+            is_church_member: true,
+            historical_church_tax: true,
+            ..input(EntityType::Individual, 750_000.0)
+        },
+    );
+}
+
+#[test]
+fn individual_with_unused_allowance() {
+    assert_breakdown("individual_with_unused_allowance", &input(EntityType::Individual, 50_000.0));
+}
+
+#[test]
+fn corporation() {
+    assert_breakdown(
+        "corporation",
+        &TaxCalculationInput {
+            dividend_income: Money(200_000.0),
+            capital_gains: Money(100_000.0),
+            ..input(EntityType::Corporation, 2_000_000.0)
+        },
+    );
+}
+
+#[test]
+fn corporation_with_resource_rent() {
+    assert_breakdown(
+        "corporation_with_resource_rent",
+        &TaxCalculationInput {
+            resource_rent: ResourceRentInput {
+                activity: ResourceRentActivity::Hydropower,
+                income: Money(10_000_000.0),
+                costs: Money(4_000_000.0),
+            },
+            ..input(EntityType::Corporation, 12_000_000.0)
+        },
+    );
+}
+
+#[test]
+fn partnership() {
+    assert_breakdown("partnership", &input(EntityType::Partnership, 1_200_000.0));
+}
+
+#[test]
+fn sole_proprietorship() {
+    assert_breakdown(
+        "sole_proprietorship",
+        &TaxCalculationInput {
+            business_expenses: Money(120_000.0),
+            allowable_deductions: Money(20_000.0),
+            ..input(EntityType::SoleProprietorship, 900_000.0)
+        },
+    );
+}
+
+#[test]
+fn foreign_branch() {
+    assert_breakdown(
+        "foreign_branch",
+        &TaxCalculationInput {
+            business_expenses: Money(300_000.0),
+            branch_payroll: Money(800_000.0),
+            ..input(EntityType::ForeignBranch, 3_000_000.0)
+        },
+    );
+}
+
+#[test]
+fn nonprofit() {
+    assert_breakdown(
+        "nonprofit",
+        &TaxCalculationInput {
+            business_expenses: Money(200_000.0),
+            charitable_purpose: true,
+            ..input(EntityType::Nonprofit, 1_500_000.0)
+        },
+    );
+}
+
+#[test]
+fn custom_taxes() {
+    assert_breakdown(
+        "custom_taxes",
+        &TaxCalculationInput {
+            custom_taxes: vec![
+                CustomTax {
+                    name: "Avgift".to_string(),
+                    kind: CustomTaxKind::Rate(Rate::from_percent(1.5)),
+                    base: CustomTaxBase::Personinntekt,
+                },
+                CustomTax {
+                    name: "Rabatt".to_string(),
+                    kind: CustomTaxKind::Fixed(Money(-2_000.0)),
+                    base: CustomTaxBase::default(),
+                },
+            ],
+            ..input(EntityType::Individual, 600_000.0)
+        },
+    );
+}
+
+#[test]
+fn individual_with_property_tax() {
+    assert_breakdown(
+        "individual_with_property_tax",
+        &TaxCalculationInput {
+            property_tax: PropertyTaxInput {
+                include: true,
+                home_value: Money(5_000_000.0),
+                rate: Rate::from_per_mille(2.0),
+                bunnfradrag: Money(1_000_000.0),
+            },
+            ..input(EntityType::Individual, 700_000.0)
+        },
+    );
+}
+
+#[test]
+fn individual_with_benefits() {
+    assert_breakdown(
+        "individual_with_benefits",
+        &TaxCalculationInput {
+            sick_pay: Money(120_000.0),
+            disability_benefit: Money(200_000.0),
+            ..input(EntityType::Individual, 150_000.0)
+        },
+    );
+}
+
+#[test]
+fn individual_with_commute() {
+    assert_breakdown(
+        "individual_with_commute",
+        &TaxCalculationInput {
+            commute: CommuteInput { distance_km: 40.0, workdays: 230.0, ..CommuteInput::default() },
+            ..input(EntityType::Individual, 600_000.0)
+        },
+    );
+}
+
+#[test]
+fn individual_living_away_from_home() {
+    assert_breakdown(
+        "individual_living_away_from_home",
+        &TaxCalculationInput {
+            commute: CommuteInput {
+                nights_away: 180.0,
+                lodging: Money(60_000.0),
+                home_visits: 40.0,
+                home_visit_km: 250.0,
+                ..CommuteInput::default()
+            },
+            ..input(EntityType::Individual, 600_000.0)
+        },
+    );
+}
+
+#[test]
+fn individual_with_donations_over_cap() {
+    assert_breakdown(
+        "individual_with_donations_over_cap",
+        &TaxCalculationInput {
+            donations: Money(30_000.0),
+            ..input(EntityType::Individual, 600_000.0)
+        },
+    );
+}
+
+#[test]
+fn individual_with_ips_over_cap() {
+    assert_breakdown(
+        "individual_with_ips_over_cap",
+        &TaxCalculationInput {
+            ips_contribution: Money(20_000.0),
+            ..input(EntityType::Individual, 600_000.0)
+        },
+    );
+}
+
+#[test]
+fn individual_with_special_deductions() {
+    assert_breakdown(
+        "individual_with_special_deductions",
+        &TaxCalculationInput {
+            special_deductions: SpecialDeductionsInput {
+                sickness_costs_transitional: true,
+                sickness_costs: Money(40_000.0),
+                occupation: Occupation::Seafarer,
+            },
+            ..input(EntityType::Individual, 600_000.0)
+        },
+    );
+}
+
+#[test]
+fn individual_with_local_rates_above_total() {
+    assert_breakdown(
+        "individual_with_local_rates_above_total",
+        &TaxCalculationInput {
+            municipal_tax_rate: Rate::from_percent(15.0),
+            county_tax_rate: Rate::from_percent(10.0),
+            ..input(EntityType::Individual, 600_000.0)
+        },
+    );
+}
+
+// Fellesskatt takes up whatever the municipal rate leaves, so the rate only
+// moves tax between the municipality and the state
+#[test]
+fn municipal_rate_does_not_change_total_tax() {
+    for entity_type in [EntityType::Individual, EntityType::SoleProprietorship] {
+        let results = [8.0, 9.0, 10.5].map(|rate| {
+            NorwegianTaxCalculator::calculate_tax(&TaxCalculationInput {
+                municipal_tax_rate: Rate::from_percent(rate),
+                ..input(entity_type, 600_000.0)
+            })
+        });
+        for result in &results[1..] {
+            assert_eq!(result.total_tax.round(), results[0].total_tax.round(), "{:?}", entity_type);
+            assert!(result.municipal_tax > results[0].municipal_tax, "{:?}", entity_type);
+            assert!(result.warnings.is_empty(), "{:?}", entity_type);
+        }
+    }
+}
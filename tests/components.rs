@@ -0,0 +1,55 @@
+// Component tests for the calculator UI. They run in a headless browser
+// rather than under cargo test, see the README.
+#![cfg(target_arch = "wasm32")]
+
+use norwegian_tax_calculator::money::Money;
+use norwegian_tax_calculator::tax_calculator::{EntityType, TaxCalculationInput};
+use norwegian_tax_calculator::testing::mount;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn editing_gross_income_recalculates() {
+    let calculator = mount(TaxCalculationInput::default());
+    calculator.set_value("Bruttoinntekt (NOK)", "800000");
+    assert_eq!(calculator.result().gross_income, Money(800_000.0));
+}
+
+#[wasm_bindgen_test]
+fn entity_type_decides_the_fields() {
+    let calculator = mount(TaxCalculationInput::default());
+    assert!(!calculator.has_field("Veldedig eller allmennyttig formål"));
+    assert!(!calculator.has_field("Lønn til ansatte i Norge"));
+
+    calculator.select_entity(EntityType::Nonprofit);
+    assert!(calculator.has_field("Veldedig eller allmennyttig formål"));
+
+    calculator.select_entity(EntityType::ForeignBranch);
+    assert!(!calculator.has_field("Veldedig eller allmennyttig formål"));
+    assert!(calculator.has_field("Lønn til ansatte i Norge"));
+}
+
+#[wasm_bindgen_test]
+fn church_tax_rate_only_for_historical_members() {
+    let calculator = mount(TaxCalculationInput::default());
+    calculator.set_checked("Avansert", true);
+    assert!(!calculator.has_field("Kirkeskatt (%)"));
+
+    calculator.set_checked("Historisk modell med kirkeskatt", true);
+    assert!(calculator.has_field("Kirkeskatt (%)"));
+
+    calculator.set_checked("Medlem av Den norske kirke", false);
+    assert!(!calculator.has_field("Kirkeskatt (%)"));
+}
+
+#[wasm_bindgen_test]
+fn breakdown_follows_the_input() {
+    let calculator = mount(TaxCalculationInput::default());
+    assert!(calculator.breakdown_ids().iter().any(|id| id == "municipal-tax"));
+
+    calculator.select_entity(EntityType::Corporation);
+    let ids = calculator.breakdown_ids();
+    assert!(ids.iter().any(|id| id == "corporate-tax"));
+    assert!(!ids.iter().any(|id| id == "municipal-tax"));
+}